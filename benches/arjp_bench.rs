@@ -1,4 +1,4 @@
-use arjp::JsonParser;
+use arjp::{JsonParser, JsonValue, ReaderParser};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 /// Benchmark for parsing a complex JSON string.
@@ -11,7 +11,138 @@ fn bench_parse(c: &mut Criterion) {
             parser.parse().unwrap()
         })
     });
+
+    c.bench_function("parse_complex_json_borrowed", |b| {
+        b.iter(|| {
+            let mut parser = JsonParser::new(black_box(big_file.as_str()));
+            parser.parse_borrowed().unwrap()
+        })
+    });
+
+    c.bench_function("parse_complex_json_bytes", |b| {
+        b.iter(|| {
+            let mut parser = JsonParser::from_bytes(black_box(big_file.as_bytes()));
+            parser.parse().unwrap()
+        })
+    });
+}
+
+/// Benchmark for parsing a file through a reader versus reading it to a
+/// string first.
+fn bench_parse_reader(c: &mut Criterion) {
+    c.bench_function("parse_reader_file", |b| {
+        b.iter(|| {
+            let file = std::fs::File::open("data/github.json").expect("error");
+            let mut parser = ReaderParser::new(black_box(file));
+            parser.parse().unwrap()
+        })
+    });
+
+    c.bench_function("parse_read_to_string_file", |b| {
+        b.iter(|| {
+            let text = std::fs::read_to_string("data/github.json").expect("error");
+            let mut parser = JsonParser::new(black_box(text.as_str()));
+            parser.parse().unwrap()
+        })
+    });
+}
+
+/// Benchmark for parsing a whitespace-heavy, pretty-printed document.
+fn bench_parse_whitespace(c: &mut Criterion) {
+    let big_file = std::fs::read_to_string("data/github.json").expect("error");
+    let pretty = JsonParser::new(&big_file)
+        .parse()
+        .unwrap()
+        .to_string_pretty(8);
+
+    c.bench_function("parse_pretty_json", |b| {
+        b.iter(|| {
+            let mut parser = JsonParser::new(black_box(pretty.as_str()));
+            parser.parse().unwrap()
+        })
+    });
+}
+
+/// Benchmark for parsing deeply nested containers with `parse` and with
+/// `parse_borrowed`, which keep nesting on separate explicit stacks.
+fn bench_parse_nested(c: &mut Criterion) {
+    let element = format!("{}1{}", "[{\"a\":".repeat(50), "}]".repeat(50));
+    let nested = format!("[{}]", vec![element; 1000].join(","));
+
+    c.bench_function("parse_nested_json", |b| {
+        b.iter(|| {
+            let mut parser = JsonParser::new(black_box(nested.as_str()));
+            parser.parse().unwrap()
+        })
+    });
+
+    c.bench_function("parse_nested_json_borrowed", |b| {
+        b.iter(|| {
+            let mut parser = JsonParser::new(black_box(nested.as_str()));
+            parser.parse_borrowed().unwrap()
+        })
+    });
+}
+
+/// Benchmark for parsing documents with large string fields, with and
+/// without escape sequences.
+fn bench_parse_long_strings(c: &mut Criterion) {
+    let plain = "lorem ipsum dolor sit amet ".repeat(4000);
+    let escaped = format!("{}\\n", "lorem ipsum dolor sit amet ".repeat(100)).repeat(40);
+    let plain_doc = format!("[{}]", vec![format!("{:?}", plain); 100].join(","));
+    let escaped_doc = format!("[{}]", vec![format!("\"{}\"", escaped); 100].join(","));
+
+    c.bench_function("parse_long_strings", |b| {
+        b.iter(|| {
+            let mut parser = JsonParser::new(black_box(plain_doc.as_str()));
+            parser.parse().unwrap()
+        })
+    });
+
+    c.bench_function("parse_long_escaped_strings", |b| {
+        b.iter(|| {
+            let mut parser = JsonParser::new(black_box(escaped_doc.as_str()));
+            parser.parse().unwrap()
+        })
+    });
+}
+
+/// Benchmark for parsing many small messages in a loop, allocating a fresh
+/// value for each versus reusing one through `parse_into`.
+fn bench_parse_reuse(c: &mut Criterion) {
+    let message = r#"{"id": 42, "tags": [1, 2, 3], "meta": {"ok": true, "score": 0.5}}"#;
+    let mut group = c.benchmark_group("parse_small_100k");
+    group.sample_size(10);
+
+    group.bench_function("fresh", |b| {
+        b.iter(|| {
+            for _ in 0..100_000 {
+                let mut parser = JsonParser::new(black_box(message));
+                black_box(parser.parse().unwrap());
+            }
+        })
+    });
+
+    group.bench_function("parse_into", |b| {
+        let mut scratch = JsonValue::Null;
+        b.iter(|| {
+            for _ in 0..100_000 {
+                let mut parser = JsonParser::new(black_box(message));
+                parser.parse_into(&mut scratch).unwrap();
+                black_box(&scratch);
+            }
+        })
+    });
+    group.finish();
 }
 
-criterion_group!(benches, bench_parse);
+criterion_group!(
+    benches,
+    bench_parse,
+    bench_parse_reader,
+    bench_parse_whitespace,
+    bench_parse_nested,
+    bench_parse_long_strings,
+    bench_parse_reuse
+);
 criterion_main!(benches);
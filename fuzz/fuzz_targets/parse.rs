@@ -0,0 +1,47 @@
+#![no_main]
+
+use arjp::{JsonParser, JsonValue, ReaderParser};
+use libfuzzer_sys::fuzz_target;
+
+/// Enables the lenient options selected by the bits of `flags`.
+fn configure(parser: JsonParser<'_>, flags: u8) -> JsonParser<'_> {
+    parser
+        .with_trailing_commas(flags & 1 != 0)
+        .allow_comments(flags & 2 != 0)
+        .allow_single_quotes(flags & 4 != 0)
+        .allow_unquoted_keys(flags & 8 != 0)
+        .allow_non_finite_numbers(flags & 16 != 0)
+        .allow_json5_numbers(flags & 16 != 0)
+        .allow_control_characters(flags & 32 != 0)
+        .case_insensitive_literals(flags & 64 != 0)
+        .with_arbitrary_precision(flags & 128 != 0)
+}
+
+// Parsing must return a `Result` for any input, never panic. The first byte
+// picks the options so that the lenient code paths are covered too.
+fuzz_target!(|data: &[u8]| {
+    let Some((&flags, input)) = data.split_first() else {
+        return;
+    };
+
+    let mut parser = configure(JsonParser::from_bytes(input), flags);
+    if let Ok(value) = parser.clone().parse() {
+        let _ = value.to_string();
+    }
+    while parser.parse_one().is_ok() && !parser.remaining().trim().is_empty() {}
+    let _ = (parser.remaining(), parser.consumed_bytes());
+    let _ = configure(JsonParser::from_bytes(input), flags).parse_borrowed();
+    let _ = configure(JsonParser::from_bytes(input), flags).parse_recovering();
+    let _ = configure(JsonParser::from_bytes(input), flags)
+        .events()
+        .count();
+    let _ = ReaderParser::with_buffer_size(input, 7).parse();
+
+    if let Ok(text) = std::str::from_utf8(input) {
+        let mut parser = configure(JsonParser::new(text), flags);
+        let _ = parser.parse_with_spans();
+        if let Err(err) = configure(JsonParser::new(text), flags).parse_into(&mut JsonValue::Null) {
+            let _ = (err.to_string(), err.snippet(text));
+        }
+    }
+});
@@ -0,0 +1,38 @@
+//! Exercises the public API of `arjp` from a `#![no_std]` crate. This crate
+//! only has to compile; CI builds it for a bare-metal target.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use arjp::{json, parse_json, parse_json_bytes, FromJson, JsonParser, JsonValue, ParseError};
+
+/// Parses `input` leniently and serializes it back to compact JSON.
+pub fn normalize(input: &str) -> Result<String, ParseError> {
+    let value = JsonParser::new(input)
+        .allow_comments(true)
+        .with_trailing_commas(true)
+        .parse()?;
+    Ok(value.to_string())
+}
+
+/// Reads the `id` field of a JSON object given as bytes.
+pub fn read_id(input: &[u8]) -> Result<u64, ParseError> {
+    let value = parse_json_bytes(input)?;
+    u64::from_json(value.get("id").unwrap_or(&JsonValue::Null))
+}
+
+/// Builds a value with the `json!` macro and compares it with parsed text.
+pub fn macro_matches_parser() -> bool {
+    let built = json!({"list": [1, 2.5, null], "name": "arjp"});
+    parse_json(r#"{"list": [1, 2.5, null], "name": "arjp"}"#).ok() == Some(built)
+}
+
+/// Renders an error together with the offending input.
+pub fn describe(error: &ParseError, input: &str) -> String {
+    let mut text = error.to_string();
+    text.push('\n');
+    text.push_str(&error.snippet(input));
+    text
+}
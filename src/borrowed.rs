@@ -0,0 +1,150 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::value::{JsonValue, Map};
+
+/// The map type backing `BorrowedValue::Object`.
+///
+/// Like [`Map`], this is a `HashMap` by default, an insertion-ordered
+/// `IndexMap` with the `preserve_order` feature and a `BTreeMap` without the
+/// `std` feature.
+#[cfg(all(feature = "std", not(feature = "preserve_order")))]
+pub type BorrowedMap<'a> = std::collections::HashMap<Cow<'a, str>, BorrowedValue<'a>>;
+
+/// `BorrowedMap` used without the `std` feature.
+#[cfg(not(feature = "std"))]
+pub type BorrowedMap<'a> = alloc::collections::BTreeMap<Cow<'a, str>, BorrowedValue<'a>>;
+
+/// Insertion-ordered `BorrowedMap` used when the `preserve_order` feature is
+/// enabled.
+#[cfg(feature = "preserve_order")]
+pub type BorrowedMap<'a> = indexmap::IndexMap<Cow<'a, str>, BorrowedValue<'a>>;
+
+/// Creates an empty `BorrowedMap` with room for `capacity` members, where
+/// the backing map supports pre-allocation.
+pub(crate) fn borrowed_map_with_capacity<'a>(capacity: usize) -> BorrowedMap<'a> {
+    #[cfg(feature = "std")]
+    return BorrowedMap::with_capacity(capacity);
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = capacity;
+        BorrowedMap::new()
+    }
+}
+
+/// A JSON value whose strings may borrow from the parsed input.
+///
+/// Produced by [`JsonParser::parse_borrowed`](crate::JsonParser::parse_borrowed).
+/// Strings and object keys without escape sequences point straight into the
+/// source buffer; only those containing escapes are allocated.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BorrowedValue<'a> {
+    /// The JSON null value.
+    Null,
+    /// A JSON boolean value (true or false).
+    Boolean(bool),
+    /// A JSON integer that fits in a signed 64-bit integer.
+    Integer(i64),
+    /// A JSON integer above `i64::MAX` that fits in an unsigned 64-bit
+    /// integer.
+    Unsigned(u64),
+    /// Any other JSON number, stored as a 64-bit float.
+    Number(f64),
+    /// A JSON number kept as its exact source text.
+    RawNumber(String),
+    /// A JSON number stored as an exact decimal.
+    #[cfg(feature = "decimal")]
+    Decimal(rust_decimal::Decimal),
+    /// A JSON string, borrowed from the input when it has no escapes.
+    String(Cow<'a, str>),
+    /// A JSON array containing a list of values.
+    Array(Vec<BorrowedValue<'a>>),
+    /// A JSON object containing key-value pairs.
+    Object(BorrowedMap<'a>),
+    /// Unparsed source text carried over from a [`JsonValue::Raw`].
+    Raw(String),
+}
+
+impl BorrowedValue<'_> {
+    /// Copies any borrowed strings, detaching the value from the input.
+    pub fn into_owned(self) -> JsonValue {
+        match self {
+            BorrowedValue::Null => JsonValue::Null,
+            BorrowedValue::Boolean(b) => JsonValue::Boolean(b),
+            BorrowedValue::Integer(i) => JsonValue::Integer(i),
+            BorrowedValue::Unsigned(u) => JsonValue::Unsigned(u),
+            BorrowedValue::Number(f) => JsonValue::Number(f),
+            BorrowedValue::RawNumber(raw) => JsonValue::RawNumber(raw),
+            #[cfg(feature = "decimal")]
+            BorrowedValue::Decimal(d) => JsonValue::Decimal(d),
+            BorrowedValue::String(s) => JsonValue::String(s.into_owned()),
+            BorrowedValue::Array(values) => {
+                JsonValue::Array(values.into_iter().map(Self::into_owned).collect())
+            }
+            BorrowedValue::Object(map) => JsonValue::Object(
+                map.into_iter()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect::<Map>(),
+            ),
+            BorrowedValue::Raw(raw) => JsonValue::Raw(raw),
+        }
+    }
+}
+
+impl From<JsonValue> for BorrowedValue<'_> {
+    /// Wraps an owned value; none of its strings are borrowed.
+    fn from(value: JsonValue) -> Self {
+        match value {
+            JsonValue::Null => BorrowedValue::Null,
+            JsonValue::Boolean(b) => BorrowedValue::Boolean(b),
+            JsonValue::Integer(i) => BorrowedValue::Integer(i),
+            JsonValue::Unsigned(u) => BorrowedValue::Unsigned(u),
+            JsonValue::Number(f) => BorrowedValue::Number(f),
+            JsonValue::RawNumber(raw) => BorrowedValue::RawNumber(raw),
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) => BorrowedValue::Decimal(d),
+            JsonValue::String(s) => BorrowedValue::String(Cow::Owned(s)),
+            JsonValue::SharedString(s) => BorrowedValue::String(Cow::Owned(String::from(&*s))),
+            JsonValue::Array(values) => {
+                BorrowedValue::Array(values.into_iter().map(Self::from).collect())
+            }
+            JsonValue::Object(map) => BorrowedValue::Object(
+                map.into_iter()
+                    .map(|(k, v)| (Cow::Owned(k), Self::from(v)))
+                    .collect(),
+            ),
+            JsonValue::Raw(raw) => BorrowedValue::Raw(raw),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+
+    #[test]
+    fn test_round_trip_through_owned() {
+        let value = parse_json(r#"{"a": [1, 2.5, null, true, "x"], "b": {"c": "d"}}"#).unwrap();
+        assert_eq!(BorrowedValue::from(value.clone()).into_owned(), value);
+
+        let raw = JsonValue::Raw("not json".into());
+        assert_eq!(
+            BorrowedValue::from(raw.clone()),
+            BorrowedValue::Raw("not json".into())
+        );
+        assert_eq!(BorrowedValue::from(raw.clone()).into_owned(), raw);
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let mut map = BorrowedMap::new();
+        map.insert(
+            Cow::Borrowed("k"),
+            BorrowedValue::String(Cow::Borrowed("v")),
+        );
+        let value = BorrowedValue::Object(map);
+        assert_eq!(value.into_owned(), parse_json(r#"{"k": "v"}"#).unwrap());
+    }
+}
@@ -0,0 +1,438 @@
+use crate::error::Result;
+#[cfg(feature = "std")]
+use crate::error::{ParseError, ParseErrorKind};
+use crate::parser::{JsonParser, DEFAULT_MAX_DEPTH};
+#[cfg(feature = "std")]
+use crate::reader::ReaderParser;
+use crate::value::JsonValue;
+
+/// A reusable set of parser options.
+///
+/// Each setter does the same as the `JsonParser` method of the same name,
+/// but the builder is not tied to an input, so one configuration can be
+/// stored, cloned and applied to many documents. A new builder has the same
+/// defaults as [`JsonParser::new`].
+///
+/// ```rust
+/// use arjp::JsonParserBuilder;
+///
+/// let lenient = JsonParserBuilder::new()
+///     .allow_comments(true)
+///     .with_trailing_commas(true);
+/// let value = lenient.parse("[1, 2, /* three */ 3,]").unwrap();
+/// assert_eq!(value.as_array().unwrap().len(), 3);
+/// assert!(lenient.build("{\"a\": 1,}").parse().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonParserBuilder {
+    max_depth: usize,
+    reject_duplicate_keys: bool,
+    allow_non_finite_numbers: bool,
+    allow_trailing_commas: bool,
+    allow_comments: bool,
+    allow_single_quotes: bool,
+    allow_unquoted_keys: bool,
+    max_string_length: Option<usize>,
+    max_total_values: Option<usize>,
+    step_budget: Option<usize>,
+    arbitrary_precision: bool,
+    #[cfg(feature = "decimal")]
+    decimal_numbers: bool,
+    allow_control_characters: bool,
+    reject_number_underflow: bool,
+    allow_bom: bool,
+    require_top_level_aggregate: bool,
+    case_insensitive_literals: bool,
+    allow_json5_numbers: bool,
+    utf8_lossy: bool,
+}
+
+impl Default for JsonParserBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonParserBuilder {
+    /// Creates a builder with the default options.
+    pub fn new() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            reject_duplicate_keys: false,
+            allow_non_finite_numbers: false,
+            allow_trailing_commas: false,
+            allow_comments: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            max_string_length: None,
+            max_total_values: None,
+            step_budget: None,
+            arbitrary_precision: false,
+            #[cfg(feature = "decimal")]
+            decimal_numbers: false,
+            allow_control_characters: false,
+            reject_number_underflow: false,
+            allow_bom: true,
+            require_top_level_aggregate: false,
+            case_insensitive_literals: false,
+            allow_json5_numbers: false,
+            utf8_lossy: false,
+        }
+    }
+
+    /// See [`JsonParser::with_max_depth`].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// See [`JsonParser::reject_duplicate_keys`].
+    pub fn reject_duplicate_keys(mut self, reject: bool) -> Self {
+        self.reject_duplicate_keys = reject;
+        self
+    }
+
+    /// See [`JsonParser::allow_non_finite_numbers`].
+    pub fn allow_non_finite_numbers(mut self, allow: bool) -> Self {
+        self.allow_non_finite_numbers = allow;
+        self
+    }
+
+    /// See [`JsonParser::with_trailing_commas`].
+    pub fn with_trailing_commas(mut self, allow: bool) -> Self {
+        self.allow_trailing_commas = allow;
+        self
+    }
+
+    /// See [`JsonParser::allow_comments`].
+    pub fn allow_comments(mut self, allow: bool) -> Self {
+        self.allow_comments = allow;
+        self
+    }
+
+    /// See [`JsonParser::allow_single_quotes`].
+    pub fn allow_single_quotes(mut self, allow: bool) -> Self {
+        self.allow_single_quotes = allow;
+        self
+    }
+
+    /// See [`JsonParser::allow_unquoted_keys`].
+    pub fn allow_unquoted_keys(mut self, allow: bool) -> Self {
+        self.allow_unquoted_keys = allow;
+        self
+    }
+
+    /// See [`JsonParser::with_max_string_length`].
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = Some(max_string_length);
+        self
+    }
+
+    /// See [`JsonParser::with_max_total_values`].
+    pub fn with_max_total_values(mut self, max_total_values: usize) -> Self {
+        self.max_total_values = Some(max_total_values);
+        self
+    }
+
+    /// See [`JsonParser::with_step_budget`].
+    pub fn with_step_budget(mut self, steps: usize) -> Self {
+        self.step_budget = Some(steps);
+        self
+    }
+
+    /// See [`JsonParser::with_arbitrary_precision`].
+    pub fn with_arbitrary_precision(mut self, enabled: bool) -> Self {
+        self.arbitrary_precision = enabled;
+        self
+    }
+
+    /// See [`JsonParser::with_decimal_numbers`].
+    #[cfg(feature = "decimal")]
+    pub fn with_decimal_numbers(mut self, enabled: bool) -> Self {
+        self.decimal_numbers = enabled;
+        self
+    }
+
+    /// See [`JsonParser::allow_control_characters`].
+    pub fn allow_control_characters(mut self, allow: bool) -> Self {
+        self.allow_control_characters = allow;
+        self
+    }
+
+    /// See [`JsonParser::reject_number_underflow`].
+    pub fn reject_number_underflow(mut self, reject: bool) -> Self {
+        self.reject_number_underflow = reject;
+        self
+    }
+
+    /// See [`JsonParser::allow_bom`].
+    pub fn allow_bom(mut self, allow: bool) -> Self {
+        self.allow_bom = allow;
+        self
+    }
+
+    /// See [`JsonParser::require_top_level_aggregate`].
+    pub fn require_top_level_aggregate(mut self, require: bool) -> Self {
+        self.require_top_level_aggregate = require;
+        self
+    }
+
+    /// See [`JsonParser::case_insensitive_literals`].
+    pub fn case_insensitive_literals(mut self, enabled: bool) -> Self {
+        self.case_insensitive_literals = enabled;
+        self
+    }
+
+    /// See [`JsonParser::allow_json5_numbers`].
+    pub fn allow_json5_numbers(mut self, allow: bool) -> Self {
+        self.allow_json5_numbers = allow;
+        self
+    }
+
+    /// See [`JsonParser::utf8_lossy`].
+    pub fn utf8_lossy(mut self, lossy: bool) -> Self {
+        self.utf8_lossy = lossy;
+        self
+    }
+
+    /// Creates a parser for `input` with these options.
+    pub fn build<'a>(&self, input: &'a str) -> JsonParser<'a> {
+        self.configure(JsonParser::new(input))
+    }
+
+    /// Creates a parser over raw bytes with these options, as
+    /// [`JsonParser::from_bytes`] does.
+    pub fn build_from_bytes<'a>(&self, input: &'a [u8]) -> JsonParser<'a> {
+        self.configure(JsonParser::from_bytes(input))
+    }
+
+    /// Parses `input` with these options, as [`JsonParser::parse`] does.
+    pub fn parse(&self, input: &str) -> Result<JsonValue> {
+        self.build(input).parse()
+    }
+
+    /// Creates a parser pulling its input from `reader` with these options,
+    /// as [`JsonParser::from_reader`] does.
+    ///
+    /// A [`ReaderParser`] only supports `with_max_depth`,
+    /// `allow_control_characters` and `allow_bom`. If any other option
+    /// differs from its default, this fails with
+    /// `ParseErrorKind::UnsupportedOption` naming it, rather than parsing
+    /// the input under different rules than configured.
+    #[cfg(feature = "std")]
+    pub fn build_reader<R: std::io::Read>(&self, reader: R) -> Result<ReaderParser<R>> {
+        let unsupported = [
+            ("reject_duplicate_keys", self.reject_duplicate_keys),
+            ("allow_non_finite_numbers", self.allow_non_finite_numbers),
+            ("with_trailing_commas", self.allow_trailing_commas),
+            ("allow_comments", self.allow_comments),
+            ("allow_single_quotes", self.allow_single_quotes),
+            ("allow_unquoted_keys", self.allow_unquoted_keys),
+            ("with_max_string_length", self.max_string_length.is_some()),
+            ("with_max_total_values", self.max_total_values.is_some()),
+            ("with_step_budget", self.step_budget.is_some()),
+            ("with_arbitrary_precision", self.arbitrary_precision),
+            #[cfg(feature = "decimal")]
+            ("with_decimal_numbers", self.decimal_numbers),
+            ("reject_number_underflow", self.reject_number_underflow),
+            (
+                "require_top_level_aggregate",
+                self.require_top_level_aggregate,
+            ),
+            ("case_insensitive_literals", self.case_insensitive_literals),
+            ("allow_json5_numbers", self.allow_json5_numbers),
+            ("utf8_lossy", self.utf8_lossy),
+        ];
+        if let Some((option, _)) = unsupported.iter().find(|(_, set)| *set) {
+            return Err(ParseError::new(
+                ParseErrorKind::UnsupportedOption,
+                &format!("ReaderParser does not support {}", option),
+            ));
+        }
+        Ok(JsonParser::from_reader(reader)
+            .with_max_depth(self.max_depth)
+            .allow_control_characters(self.allow_control_characters)
+            .allow_bom(self.allow_bom))
+    }
+
+    /// Applies these options to a freshly created parser.
+    fn configure<'a>(&self, parser: JsonParser<'a>) -> JsonParser<'a> {
+        let mut parser = parser
+            .with_max_depth(self.max_depth)
+            .reject_duplicate_keys(self.reject_duplicate_keys)
+            .allow_non_finite_numbers(self.allow_non_finite_numbers)
+            .with_trailing_commas(self.allow_trailing_commas)
+            .allow_comments(self.allow_comments)
+            .allow_single_quotes(self.allow_single_quotes)
+            .allow_unquoted_keys(self.allow_unquoted_keys)
+            .with_arbitrary_precision(self.arbitrary_precision)
+            .allow_control_characters(self.allow_control_characters)
+            .reject_number_underflow(self.reject_number_underflow)
+            .allow_bom(self.allow_bom)
+            .require_top_level_aggregate(self.require_top_level_aggregate)
+            .case_insensitive_literals(self.case_insensitive_literals)
+            .allow_json5_numbers(self.allow_json5_numbers)
+            .utf8_lossy(self.utf8_lossy);
+        #[cfg(feature = "decimal")]
+        {
+            parser = parser.with_decimal_numbers(self.decimal_numbers);
+        }
+        if let Some(max) = self.max_string_length {
+            parser = parser.with_max_string_length(max);
+        }
+        if let Some(max) = self.max_total_values {
+            parser = parser.with_max_total_values(max);
+        }
+        if let Some(steps) = self.step_budget {
+            parser = parser.with_step_budget(steps);
+        }
+        parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseErrorKind;
+    use crate::parse_json;
+
+    #[test]
+    fn test_default_builder_is_strict() {
+        let builder = JsonParserBuilder::default();
+        assert_eq!(builder, JsonParserBuilder::new());
+        assert_eq!(
+            builder.parse(r#"{"a": [1, 2]}"#).unwrap(),
+            parse_json(r#"{"a": [1, 2]}"#).unwrap()
+        );
+        let cases = [
+            ("[1,]", ParseErrorKind::UnexpectedChar),
+            ("[1] // c", ParseErrorKind::TrailingData),
+            ("{a: 1}", ParseErrorKind::InvalidKey),
+            ("NaN", ParseErrorKind::UnexpectedChar),
+        ];
+        for (input, kind) in cases {
+            assert_eq!(builder.parse(input).unwrap_err().kind(), kind, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_builder_options_take_effect() {
+        let builder = JsonParserBuilder::new()
+            .with_max_depth(2)
+            .reject_duplicate_keys(true)
+            .with_trailing_commas(true)
+            .allow_comments(true)
+            .allow_single_quotes(true)
+            .allow_unquoted_keys(true)
+            .allow_non_finite_numbers(true)
+            .with_max_string_length(3)
+            .require_top_level_aggregate(true)
+            .case_insensitive_literals(true)
+            .allow_json5_numbers(true);
+
+        let value = builder
+            .parse("{a: ['x', NaN,], /* note */ 'b': Infinity,}")
+            .unwrap();
+        assert_eq!(value["a"][0], JsonValue::String("x".into()));
+        assert!(value["a"][1].as_f64().unwrap().is_nan());
+        assert_eq!(value["b"], JsonValue::Number(f64::INFINITY));
+        assert_eq!(
+            builder.parse("[TRUE]").unwrap()[0],
+            JsonValue::Boolean(true)
+        );
+        assert_eq!(builder.parse("[0x1F]").unwrap()[0], JsonValue::Integer(31));
+
+        let cases = [
+            ("[[[1]]]", ParseErrorKind::DepthLimitExceeded),
+            ("{a: 1, a: 2}", ParseErrorKind::DuplicateKey),
+            ("['abcd']", ParseErrorKind::StringLengthLimitExceeded),
+            ("'abc'", ParseErrorKind::UnexpectedChar),
+        ];
+        for (input, kind) in cases {
+            assert_eq!(builder.parse(input).unwrap_err().kind(), kind, "{}", input);
+        }
+
+        // The same builder configures any number of parsers.
+        let mut parser = builder.build_from_bytes(b"[1, 2,] [3]");
+        assert_eq!(parser.parse_one().unwrap(), parse_json("[1, 2]").unwrap());
+        assert_eq!(parser.parse_one().unwrap(), parse_json("[3]").unwrap());
+    }
+
+    #[test]
+    fn test_builder_limits_and_numbers() {
+        let builder = JsonParserBuilder::new()
+            .with_max_total_values(3)
+            .with_step_budget(20)
+            .with_arbitrary_precision(true)
+            .allow_bom(false)
+            .allow_control_characters(true);
+        assert_eq!(
+            builder.parse("[1, 2, 3]").unwrap_err().kind(),
+            ParseErrorKind::ValueCountLimitExceeded
+        );
+        assert_eq!(
+            builder
+                .parse(&format!("[{}1]", " ".repeat(20)))
+                .unwrap_err()
+                .kind(),
+            ParseErrorKind::BudgetExceeded
+        );
+        assert_eq!(
+            builder.parse("[0.10, \"\t\"]").unwrap()[0],
+            JsonValue::RawNumber("0.10".into())
+        );
+        assert_eq!(
+            builder.parse("\u{feff}1").unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
+
+        let builder = JsonParserBuilder::new().reject_number_underflow(true);
+        assert_eq!(
+            builder.parse("1e-400").unwrap_err().kind(),
+            ParseErrorKind::InvalidNumber
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_builder_reader() {
+        let builder = JsonParserBuilder::new()
+            .with_max_depth(2)
+            .allow_control_characters(true)
+            .allow_bom(false);
+        let mut parser = builder.build_reader(&b"[\"a\tb\"]"[..]).unwrap();
+        assert_eq!(parser.parse().unwrap(), parse_json("[\"a\\tb\"]").unwrap());
+        let mut parser = builder.build_reader(&b"[[[1]]]"[..]).unwrap();
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::DepthLimitExceeded
+        );
+        let mut parser = builder.build_reader(&b"\xEF\xBB\xBF1"[..]).unwrap();
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
+
+        // Options the reader cannot honour are rejected up front.
+        let unsupported = [
+            JsonParserBuilder::new().allow_comments(true),
+            JsonParserBuilder::new().with_trailing_commas(true),
+            JsonParserBuilder::new().reject_duplicate_keys(true),
+            JsonParserBuilder::new().with_max_string_length(8),
+            JsonParserBuilder::new().allow_non_finite_numbers(true),
+        ];
+        for builder in unsupported {
+            let err = builder.build_reader(&b"[]"[..]).err().unwrap();
+            assert_eq!(err.kind(), ParseErrorKind::UnsupportedOption);
+        }
+        let err = JsonParserBuilder::new()
+            .allow_comments(true)
+            .build_reader(&b"[]"[..])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.message(),
+            "ReaderParser does not support allow_comments"
+        );
+    }
+}
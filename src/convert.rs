@@ -0,0 +1,268 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::{ParseError, Result};
+use crate::from_json::{type_error, FromJson};
+use crate::value::{JsonValue, Map};
+
+impl From<bool> for JsonValue {
+    fn from(b: bool) -> Self {
+        JsonValue::Boolean(b)
+    }
+}
+
+/// Implements `From` for integer types that always fit in an `i64`.
+macro_rules! from_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for JsonValue {
+                fn from(i: $ty) -> Self {
+                    JsonValue::Integer(i64::from(i))
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<u64> for JsonValue {
+    /// Uses `Integer` when the value fits in an `i64` and `Unsigned` above.
+    fn from(u: u64) -> Self {
+        i64::try_from(u).map_or(JsonValue::Unsigned(u), JsonValue::Integer)
+    }
+}
+
+impl From<f32> for JsonValue {
+    fn from(f: f32) -> Self {
+        JsonValue::Number(f64::from(f))
+    }
+}
+
+impl From<f64> for JsonValue {
+    fn from(f: f64) -> Self {
+        JsonValue::Number(f)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for JsonValue {
+    fn from(d: rust_decimal::Decimal) -> Self {
+        JsonValue::Decimal(d)
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(s: String) -> Self {
+        JsonValue::String(s)
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(s: &str) -> Self {
+        JsonValue::String(s.to_string())
+    }
+}
+
+impl From<Vec<JsonValue>> for JsonValue {
+    fn from(values: Vec<JsonValue>) -> Self {
+        JsonValue::Array(values)
+    }
+}
+
+impl From<Map> for JsonValue {
+    fn from(map: Map) -> Self {
+        JsonValue::Object(map)
+    }
+}
+
+impl<T: Into<JsonValue>> From<Option<T>> for JsonValue {
+    /// Converts `None` to `null` and `Some(v)` to the conversion of `v`.
+    fn from(option: Option<T>) -> Self {
+        option.map_or(JsonValue::Null, Into::into)
+    }
+}
+
+/// Implements `TryFrom<&JsonValue>` and `TryFrom<JsonValue>` through
+/// `FromJson`, for types that are cheap to extract from a reference.
+macro_rules! try_from_json {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<&JsonValue> for $ty {
+                type Error = ParseError;
+
+                fn try_from(value: &JsonValue) -> Result<Self> {
+                    <$ty>::from_json(value)
+                }
+            }
+
+            impl TryFrom<JsonValue> for $ty {
+                type Error = ParseError;
+
+                fn try_from(value: JsonValue) -> Result<Self> {
+                    <$ty>::from_json(&value)
+                }
+            }
+        )*
+    };
+}
+
+try_from_json!(bool, i64, f64);
+
+impl TryFrom<&JsonValue> for String {
+    type Error = ParseError;
+
+    fn try_from(value: &JsonValue) -> Result<Self> {
+        String::from_json(value)
+    }
+}
+
+impl TryFrom<JsonValue> for String {
+    type Error = ParseError;
+
+    /// Moves the string out without copying it.
+    fn try_from(value: JsonValue) -> Result<Self> {
+        match value {
+            JsonValue::String(s) => Ok(s),
+            JsonValue::SharedString(s) => Ok(String::from(&*s)),
+            other => Err(type_error("string", &other)),
+        }
+    }
+}
+
+impl TryFrom<&JsonValue> for Vec<JsonValue> {
+    type Error = ParseError;
+
+    fn try_from(value: &JsonValue) -> Result<Self> {
+        Vec::from_json(value)
+    }
+}
+
+impl TryFrom<JsonValue> for Vec<JsonValue> {
+    type Error = ParseError;
+
+    /// Moves the elements out without copying them.
+    fn try_from(value: JsonValue) -> Result<Self> {
+        match value {
+            JsonValue::Array(values) => Ok(values),
+            other => Err(type_error("array", &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bool() {
+        assert_eq!(JsonValue::from(true), JsonValue::Boolean(true));
+    }
+
+    #[test]
+    fn test_from_integers() {
+        assert_eq!(JsonValue::from(42), JsonValue::Integer(42));
+        assert_eq!(JsonValue::from(i64::MIN), JsonValue::Integer(i64::MIN));
+        assert_eq!(JsonValue::from(255u8), JsonValue::Integer(255));
+        assert_eq!(JsonValue::from(u32::MAX), JsonValue::Integer(4294967295));
+        assert_eq!(JsonValue::from(5u64), JsonValue::Integer(5));
+        assert_eq!(JsonValue::from(u64::MAX), JsonValue::Unsigned(u64::MAX));
+    }
+
+    #[test]
+    fn test_from_floats() {
+        assert_eq!(JsonValue::from(1.5), JsonValue::Number(1.5));
+        assert_eq!(JsonValue::from(0.5f32), JsonValue::Number(0.5));
+    }
+
+    #[test]
+    fn test_from_strings() {
+        assert_eq!(
+            JsonValue::from("hello"),
+            JsonValue::String("hello".to_string())
+        );
+        assert_eq!(
+            JsonValue::from("owned".to_string()),
+            JsonValue::String("owned".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_containers() {
+        assert_eq!(
+            JsonValue::from(vec![JsonValue::from(1), JsonValue::Null]),
+            JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Null])
+        );
+
+        let mut map = Map::new();
+        map.insert("k".to_string(), JsonValue::from("v"));
+        assert_eq!(JsonValue::from(map.clone()), JsonValue::Object(map));
+    }
+
+    #[test]
+    fn test_from_option() {
+        assert_eq!(JsonValue::from(None::<i64>), JsonValue::Null);
+        assert_eq!(JsonValue::from(Some("x")), JsonValue::from("x"));
+        assert_eq!(JsonValue::from(Some(Some(3))), JsonValue::Integer(3));
+    }
+
+    #[test]
+    fn test_try_from() {
+        let value = crate::parse_json(r#"[true, 7, 2.5, "s", [null]]"#).unwrap();
+        assert!(bool::try_from(&value[0]).unwrap());
+        assert_eq!(i64::try_from(&value[1]).unwrap(), 7);
+        assert_eq!(f64::try_from(&value[1]).unwrap(), 7.0);
+        assert_eq!(f64::try_from(&value[2]).unwrap(), 2.5);
+        assert_eq!(String::try_from(&value[3]).unwrap(), "s");
+        assert_eq!(
+            Vec::<JsonValue>::try_from(&value[4]).unwrap(),
+            [JsonValue::Null]
+        );
+
+        let s: String = JsonValue::from("owned").try_into().unwrap();
+        assert_eq!(s, "owned");
+        let values: Vec<JsonValue> = value.try_into().unwrap();
+        assert_eq!(values.len(), 5);
+        let b: bool = JsonValue::Boolean(false).try_into().unwrap();
+        assert!(!b);
+    }
+
+    #[test]
+    fn test_try_from_mismatch() {
+        let cases = [
+            (
+                bool::try_from(JsonValue::Null).map(|_| ()),
+                "Expected boolean, found null",
+            ),
+            (
+                i64::try_from(JsonValue::Number(2.5)).map(|_| ()),
+                "Expected i64, found number",
+            ),
+            (
+                f64::try_from(&JsonValue::from("1")).map(|_| ()),
+                "Expected number, found string",
+            ),
+            (
+                String::try_from(JsonValue::Integer(1)).map(|_| ()),
+                "Expected string, found number",
+            ),
+            (
+                String::try_from(&JsonValue::Array(vec![])).map(|_| ()),
+                "Expected string, found array",
+            ),
+            (
+                Vec::<JsonValue>::try_from(JsonValue::Object(Map::new())).map(|_| ()),
+                "Expected array, found object",
+            ),
+            (
+                Vec::<JsonValue>::try_from(&JsonValue::Boolean(true)).map(|_| ()),
+                "Expected array, found boolean",
+            ),
+        ];
+        for (result, message) in cases {
+            let err = result.unwrap_err();
+            assert_eq!(err.kind(), crate::ParseErrorKind::UnexpectedType);
+            assert_eq!(err.to_string(), message);
+        }
+    }
+}
@@ -1,28 +1,479 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Categories of JSON parsing failures.
+///
+/// Every `ParseError` carries one of these kinds so callers can react to the
+/// cause of a failure programmatically instead of inspecting the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A character that cannot start or continue the current construct.
+    UnexpectedChar,
+    /// The input ended while a value was still being parsed.
+    UnexpectedEof,
+    /// A string literal is missing its closing quote.
+    UnterminatedString,
+    /// A number literal does not follow the JSON number grammar.
+    InvalidNumber,
+    /// An unknown or malformed escape sequence inside a string.
+    InvalidEscape,
+    /// A raw control character (U+0000 to U+001F) inside a string, which
+    /// must be escaped in strict JSON.
+    InvalidControlChar,
+    /// A `\u` escape that does not describe a valid Unicode scalar value.
+    InvalidUnicode,
+    /// Byte input that is not valid UTF-8.
+    InvalidUtf8,
+    /// A misspelled `null`, `true` or `false` literal.
+    InvalidLiteral,
+    /// An object key that is not a string.
+    InvalidKey,
+    /// Non-whitespace characters after the top-level value.
+    TrailingData,
+    /// The nesting of arrays and objects exceeds the configured limit.
+    DepthLimitExceeded,
+    /// An object contains the same key more than once.
+    DuplicateKey,
+    /// Reading the input from an I/O source failed.
+    Io,
+    /// A string or object key is longer than the configured limit.
+    StringLengthLimitExceeded,
+    /// The document contains more values than the configured limit.
+    ValueCountLimitExceeded,
+    /// A value does not have the type requested by a typed conversion such
+    /// as `FromJson`.
+    UnexpectedType,
+    /// A JSON Patch document is malformed or one of its paths does not
+    /// resolve.
+    InvalidPatch,
+    /// A JSON Patch `test` operation found a different value.
+    PatchTestFailed,
+    /// Parsing took more steps than the budget set by
+    /// `JsonParser::with_step_budget`.
+    BudgetExceeded,
+    /// A JSON Pointer passed to `JsonValue::pointer_set` is malformed or
+    /// does not lead to a settable location.
+    InvalidPointer,
+    /// A parser option was set that the chosen entry point does not
+    /// support, such as `allow_comments` for a `ReaderParser`.
+    UnsupportedOption,
+}
+
+impl ParseErrorKind {
+    /// Returns a stable identifier for the kind, such as
+    /// `"E_UNTERMINATED_STRING"`.
+    ///
+    /// Unlike error messages, which are English prose and may be reworded,
+    /// codes never change once released, so they can key translated messages
+    /// or be aggregated in logs. Each is `E_` followed by the kind's name in
+    /// upper snake case.
+    pub fn code(self) -> &'static str {
+        match self {
+            ParseErrorKind::UnexpectedChar => "E_UNEXPECTED_CHAR",
+            ParseErrorKind::UnexpectedEof => "E_UNEXPECTED_EOF",
+            ParseErrorKind::UnterminatedString => "E_UNTERMINATED_STRING",
+            ParseErrorKind::InvalidNumber => "E_INVALID_NUMBER",
+            ParseErrorKind::InvalidEscape => "E_INVALID_ESCAPE",
+            ParseErrorKind::InvalidControlChar => "E_INVALID_CONTROL_CHAR",
+            ParseErrorKind::InvalidUnicode => "E_INVALID_UNICODE",
+            ParseErrorKind::InvalidUtf8 => "E_INVALID_UTF8",
+            ParseErrorKind::InvalidLiteral => "E_INVALID_LITERAL",
+            ParseErrorKind::InvalidKey => "E_INVALID_KEY",
+            ParseErrorKind::TrailingData => "E_TRAILING_DATA",
+            ParseErrorKind::DepthLimitExceeded => "E_DEPTH_LIMIT_EXCEEDED",
+            ParseErrorKind::DuplicateKey => "E_DUPLICATE_KEY",
+            ParseErrorKind::Io => "E_IO",
+            ParseErrorKind::StringLengthLimitExceeded => "E_STRING_LENGTH_LIMIT_EXCEEDED",
+            ParseErrorKind::ValueCountLimitExceeded => "E_VALUE_COUNT_LIMIT_EXCEEDED",
+            ParseErrorKind::UnexpectedType => "E_UNEXPECTED_TYPE",
+            ParseErrorKind::InvalidPatch => "E_INVALID_PATCH",
+            ParseErrorKind::PatchTestFailed => "E_PATCH_TEST_FAILED",
+            ParseErrorKind::BudgetExceeded => "E_BUDGET_EXCEEDED",
+            ParseErrorKind::InvalidPointer => "E_INVALID_POINTER",
+            ParseErrorKind::UnsupportedOption => "E_UNSUPPORTED_OPTION",
+        }
+    }
+}
+
+/// Formats as a short lowercase label for the category, such as
+/// `unterminated string`, suitable for grouping errors in a UI. The full
+/// description of a particular failure is the `ParseError` display.
+impl core::fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            ParseErrorKind::UnexpectedChar => "unexpected character",
+            ParseErrorKind::UnexpectedEof => "unexpected end of input",
+            ParseErrorKind::UnterminatedString => "unterminated string",
+            ParseErrorKind::InvalidNumber => "invalid number",
+            ParseErrorKind::InvalidEscape => "invalid escape sequence",
+            ParseErrorKind::InvalidControlChar => "unescaped control character",
+            ParseErrorKind::InvalidUnicode => "invalid unicode escape",
+            ParseErrorKind::InvalidUtf8 => "invalid UTF-8",
+            ParseErrorKind::InvalidLiteral => "invalid literal",
+            ParseErrorKind::InvalidKey => "invalid object key",
+            ParseErrorKind::TrailingData => "trailing data",
+            ParseErrorKind::DepthLimitExceeded => "nesting too deep",
+            ParseErrorKind::DuplicateKey => "duplicate key",
+            ParseErrorKind::Io => "I/O error",
+            ParseErrorKind::StringLengthLimitExceeded => "string too long",
+            ParseErrorKind::ValueCountLimitExceeded => "too many values",
+            ParseErrorKind::UnexpectedType => "unexpected type",
+            ParseErrorKind::InvalidPatch => "invalid patch",
+            ParseErrorKind::PatchTestFailed => "patch test failed",
+            ParseErrorKind::BudgetExceeded => "work budget exceeded",
+            ParseErrorKind::InvalidPointer => "invalid pointer",
+            ParseErrorKind::UnsupportedOption => "unsupported option",
+        })
+    }
+}
+
 /// Custom error type for JSON parsing failures.
 #[derive(Debug)]
 pub struct ParseError {
-    message: String,
+    kind: ParseErrorKind,
+    // The text fields are boxed rather than `String`s to keep the error
+    // small, since it is returned by value everywhere.
+    message: Box<str>,
+    offset: Option<usize>,
+    /// One-based line and column of `offset`, once known.
+    location: Option<(usize, usize)>,
+    /// JSONPath-style location of the value being parsed, once known.
+    path: Option<Box<str>>,
+    /// Caller-supplied context, outermost first, shown before the message.
+    context: Option<Box<str>>,
+    /// The input around `offset`, captured along with the location.
+    snippet: Option<Box<str>>,
+    #[cfg(feature = "std")]
+    source: Option<std::io::Error>,
 }
 
 impl ParseError {
-    /// Creates a new ParseError with the given message.
-    pub fn new(message: &str) -> Self {
+    /// Creates a new ParseError with the given kind and message.
+    pub fn new(kind: ParseErrorKind, message: &str) -> Self {
         ParseError {
-            message: message.to_string(),
+            kind,
+            message: message.into(),
+            offset: None,
+            location: None,
+            path: None,
+            context: None,
+            snippet: None,
+            #[cfg(feature = "std")]
+            source: None,
+        }
+    }
+
+    /// Attaches the byte offset in the input at which the error was detected.
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Adds context describing what the caller was doing, such as
+    /// `"while reading config.json"`, which `Display` puts before the
+    /// message.
+    ///
+    /// The kind, offset and location are unchanged, and so is
+    /// [`ParseError::message`]. Adding context again, as an error passes up
+    /// through more layers, puts the newer context first.
+    ///
+    /// ```rust
+    /// use arjp::parse_json;
+    ///
+    /// let err = parse_json("[1, 2").unwrap_err().with_context("while reading config.json");
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "while reading config.json: Unterminated array at line 1 column 6"
+    /// );
+    /// ```
+    pub fn with_context(mut self, context: &str) -> Self {
+        self.context = Some(match self.context.take() {
+            Some(inner) => format!("{}: {}", context, inner).into(),
+            None => context.into(),
+        });
+        self
+    }
+
+    /// Returns the context added by [`ParseError::with_context`], if any,
+    /// with the outermost first and the layers separated by `": "`.
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// Attaches the one-based line and column at which the error was
+    /// detected.
+    pub(crate) fn with_location(mut self, line: usize, column: usize) -> Self {
+        self.location = Some((line, column));
+        self
+    }
+
+    /// Attaches the JSONPath-style location of the value being parsed,
+    /// unless one is already known.
+    pub(crate) fn with_path(mut self, path: String) -> Self {
+        self.path.get_or_insert(path.into());
+        self
+    }
+
+    /// Fills in the line and column of the error offset from the complete
+    /// `input`, unless they are already known, and captures the snippet
+    /// around it for the alternate `Display` form.
+    pub(crate) fn locate(mut self, input: &[u8]) -> Self {
+        match self.offset {
+            Some(offset) if self.location.is_none() => {
+                let before = &input[..offset.min(input.len())];
+                let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+                let line_start = before
+                    .iter()
+                    .rposition(|&b| b == b'\n')
+                    .map_or(0, |i| i + 1);
+                self.snippet = Some(snippet_around(input, offset).into());
+                self.with_location(line, offset - line_start + 1)
+            }
+            _ => self,
+        }
+    }
+
+    /// Returns the category of this error.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the stable identifier of the error's kind, as
+    /// [`ParseErrorKind::code`] does.
+    ///
+    /// ```rust
+    /// use arjp::parse_json;
+    ///
+    /// assert_eq!(parse_json("[1, 2").unwrap_err().code(), "E_UNEXPECTED_EOF");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// Returns whether the input ended before the value was complete, as
+    /// opposed to being malformed.
+    ///
+    /// This holds for `UnexpectedEof` and `UnterminatedString` errors. A
+    /// streaming caller seeing one can wait for more input and parse again,
+    /// since appending to the input may still make it valid.
+    pub fn is_eof(&self) -> bool {
+        matches!(
+            self.kind,
+            ParseErrorKind::UnexpectedEof | ParseErrorKind::UnterminatedString
+        )
+    }
+
+    /// Returns the byte offset in the input at which the error was detected,
+    /// if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Returns the one-based line of the error, if known.
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|(line, _)| line)
+    }
+
+    /// Returns the one-based column of the error, if known. Columns count
+    /// bytes from the start of the line, as in `serde_json`.
+    pub fn column(&self) -> Option<usize> {
+        self.location.map(|(_, column)| column)
+    }
+
+    /// Returns the location of the value being parsed when the error
+    /// occurred, as a JSONPath such as `$.users[3].email`, or an empty
+    /// string if it is not known.
+    ///
+    /// The path names the innermost array element or object member that had
+    /// begun, so an error between members, such as a missing comma, gives
+    /// the path of the enclosing container, and an error outside any
+    /// container gives `$`. Keys that are not plain identifiers are written
+    /// in brackets, as in `$['first name']`. Paths are recorded by the
+    /// methods that build a `JsonValue`: [`JsonParser::parse`],
+    /// [`JsonParser::parse_one`] and the methods built on them.
+    ///
+    /// [`JsonParser::parse`]: crate::JsonParser::parse
+    /// [`JsonParser::parse_one`]: crate::JsonParser::parse_one
+    ///
+    /// `Display` leaves the path out, so existing messages keep their form;
+    /// combine the two where it helps:
+    ///
+    /// ```rust
+    /// use arjp::parse_json;
+    ///
+    /// let err = parse_json(r#"{"users": [{}, {"active": tru}]}"#).unwrap_err();
+    /// assert_eq!(err.path(), "$.users[1].active");
+    /// assert_eq!(
+    ///     format!("error at {}: {}", err.path(), err),
+    ///     "error at $.users[1].active: Invalid true value at line 1 column 27"
+    /// );
+    /// ```
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or("")
+    }
+
+    /// Returns the message without the location suffix that `Display` adds.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Renders the part of `input` around the error offset, with a caret
+    /// on the next line pointing at the offending position:
+    ///
+    /// ```text
+    /// {"a":1 "b":2}
+    ///        ^
+    /// ```
+    ///
+    /// Only the line containing the offset is shown, trimmed to about
+    /// `SNIPPET_RADIUS` characters either side, with `...` marking each cut.
+    /// `input` must be the text that was parsed. Returns an empty string if
+    /// the error has no offset.
+    ///
+    /// Errors from [`JsonParser`](crate::JsonParser) already carry this
+    /// snippet, which the alternate form of `Display` (`{:#}`) prints; this
+    /// method is for errors that were not located against the full input,
+    /// such as those from [`ReaderParser`](crate::ReaderParser).
+    pub fn snippet(&self, input: &str) -> String {
+        match self.offset {
+            Some(offset) => snippet_around(input.as_bytes(), offset),
+            None => String::new(),
+        }
+    }
+}
+
+/// Number of characters `ParseError::snippet` keeps on each side of the
+/// error position.
+const SNIPPET_RADIUS: usize = 20;
+
+/// Renders the line of `input` around `offset` with a caret under it, as
+/// described on [`ParseError::snippet`].
+///
+/// Only a window of bytes either side of the offset is decoded, so an error
+/// in a long single-line document stays cheap, and invalid UTF-8 is shown
+/// as replacement characters.
+fn snippet_around(input: &[u8], offset: usize) -> String {
+    let is_continuation = |b: u8| b & 0xC0 == 0x80;
+    let mut offset = offset.min(input.len());
+    while offset > 0 && offset < input.len() && is_continuation(input[offset]) {
+        offset -= 1;
+    }
+
+    let line_start = input[..offset]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    let line_end = input[offset..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(input.len(), |i| offset + i);
+    let line_end = if line_end > offset && input[line_end - 1] == b'\r' {
+        line_end - 1
+    } else {
+        line_end
+    };
+
+    // Any `SNIPPET_RADIUS + 1` characters fit in this many bytes, even
+    // after skipping a character cut in half at the edge of the window.
+    let window = 4 * (SNIPPET_RADIUS + 2);
+    let before =
+        String::from_utf8_lossy(&input[line_start.max(offset.saturating_sub(window))..offset]);
+    let after = String::from_utf8_lossy(&input[offset..line_end.min(offset + window)]);
+
+    let mut before: Vec<char> = before.chars().rev().take(SNIPPET_RADIUS + 1).collect();
+    before.reverse();
+    let mut after: Vec<char> = after.chars().take(SNIPPET_RADIUS + 1).collect();
+    let before_cut = before.len() > SNIPPET_RADIUS;
+    let after_cut = after.len() > SNIPPET_RADIUS;
+    if before_cut {
+        before.remove(0);
+    }
+    after.truncate(SNIPPET_RADIUS);
+
+    let mut line = String::new();
+    if before_cut {
+        line.push_str("...");
+    }
+    line.extend(&before);
+    let caret_column = line.chars().count();
+    line.extend(&after);
+    if after_cut {
+        line.push_str("...");
+    }
+    format!("{}\n{}^", line, " ".repeat(caret_column))
+}
+
+/// Formats as `"{message} at line {line} column {column}"` when the location
+/// is known, following `serde_json`, and as the bare message otherwise. Any
+/// context comes first, followed by `": "`.
+///
+/// The alternate form (`{:#}`) adds the snippet of input around the error,
+/// with a caret under the offending position, on the following lines:
+///
+/// ```rust
+/// use arjp::parse_json;
+///
+/// let err = parse_json(r#"{"a":1 "b":2}"#).unwrap_err();
+/// assert_eq!(
+///     format!("{:#}", err),
+///     "Expected comma or closing brace, got '\"' at line 1 column 8\n\
+///      {\"a\":1 \"b\":2}\n       ^"
+/// );
+/// ```
+///
+/// The default form stays on one line so that it can be embedded in logs
+/// and other messages. Errors that were not located against the full input,
+/// such as those from [`ReaderParser`](crate::ReaderParser), have no
+/// snippet; [`ParseError::snippet`] renders one from the input instead.
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(context) = &self.context {
+            write!(f, "{}: ", context)?;
+        }
+        match self.location {
+            Some((line, column)) => {
+                write!(f, "{} at line {} column {}", self.message, line, column)?
+            }
+            None => write!(f, "{}", self.message)?,
+        }
+        match &self.snippet {
+            Some(snippet) if f.alternate() => write!(f, "\n{}", snippet),
+            _ => Ok(()),
         }
     }
 }
 
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+/// Uses `core::error::Error`, so the impl is available without `std` too.
+impl core::error::Error for ParseError {
+    #[cfg(feature = "std")]
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.source.as_ref().map(|e| e as _)
     }
 }
 
-impl std::error::Error for ParseError {}
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ParseError {
+    /// Wraps an I/O failure as a `ParseErrorKind::Io` error, keeping the
+    /// original error available through `source()`.
+    fn from(error: std::io::Error) -> Self {
+        ParseError {
+            kind: ParseErrorKind::Io,
+            message: format!("I/O error: {}", error).into(),
+            offset: None,
+            location: None,
+            path: None,
+            context: None,
+            snippet: None,
+            source: Some(error),
+        }
+    }
+}
 
 /// Result type alias for parsing operations.
-pub type Result<T> = std::result::Result<T, ParseError>;
+pub type Result<T> = core::result::Result<T, ParseError>;
 
 #[cfg(test)]
 mod tests {
@@ -30,20 +481,267 @@ mod tests {
 
     #[test]
     fn test_error_creation() {
-        let error = ParseError::new("test error");
-        assert_eq!(error.message, "test error");
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error");
+        assert_eq!(error.message(), "test error");
+    }
+
+    #[test]
+    fn test_error_kind() {
+        let error = ParseError::new(ParseErrorKind::UnterminatedString, "test error");
+        assert_eq!(error.kind(), ParseErrorKind::UnterminatedString);
+    }
+
+    #[test]
+    fn test_error_code() {
+        // These codes are part of the public API and must never change.
+        let cases = [
+            (ParseErrorKind::UnexpectedChar, "E_UNEXPECTED_CHAR"),
+            (ParseErrorKind::UnexpectedEof, "E_UNEXPECTED_EOF"),
+            (ParseErrorKind::UnterminatedString, "E_UNTERMINATED_STRING"),
+            (ParseErrorKind::InvalidNumber, "E_INVALID_NUMBER"),
+            (ParseErrorKind::InvalidEscape, "E_INVALID_ESCAPE"),
+            (ParseErrorKind::InvalidControlChar, "E_INVALID_CONTROL_CHAR"),
+            (ParseErrorKind::InvalidUnicode, "E_INVALID_UNICODE"),
+            (ParseErrorKind::InvalidUtf8, "E_INVALID_UTF8"),
+            (ParseErrorKind::InvalidLiteral, "E_INVALID_LITERAL"),
+            (ParseErrorKind::InvalidKey, "E_INVALID_KEY"),
+            (ParseErrorKind::TrailingData, "E_TRAILING_DATA"),
+            (ParseErrorKind::DepthLimitExceeded, "E_DEPTH_LIMIT_EXCEEDED"),
+            (ParseErrorKind::DuplicateKey, "E_DUPLICATE_KEY"),
+            (ParseErrorKind::Io, "E_IO"),
+            (
+                ParseErrorKind::StringLengthLimitExceeded,
+                "E_STRING_LENGTH_LIMIT_EXCEEDED",
+            ),
+            (
+                ParseErrorKind::ValueCountLimitExceeded,
+                "E_VALUE_COUNT_LIMIT_EXCEEDED",
+            ),
+            (ParseErrorKind::UnexpectedType, "E_UNEXPECTED_TYPE"),
+            (ParseErrorKind::InvalidPatch, "E_INVALID_PATCH"),
+            (ParseErrorKind::PatchTestFailed, "E_PATCH_TEST_FAILED"),
+            (ParseErrorKind::BudgetExceeded, "E_BUDGET_EXCEEDED"),
+            (ParseErrorKind::InvalidPointer, "E_INVALID_POINTER"),
+            (ParseErrorKind::UnsupportedOption, "E_UNSUPPORTED_OPTION"),
+        ];
+        for (kind, code) in cases {
+            assert_eq!(kind.code(), code);
+            assert_eq!(ParseError::new(kind, "test error").code(), code);
+        }
+        let codes: std::collections::HashSet<_> = cases.iter().map(|(_, code)| code).collect();
+        assert_eq!(codes.len(), cases.len());
+    }
+
+    #[test]
+    fn test_error_kind_display() {
+        let cases = [
+            (ParseErrorKind::UnexpectedChar, "unexpected character"),
+            (ParseErrorKind::UnexpectedEof, "unexpected end of input"),
+            (ParseErrorKind::UnterminatedString, "unterminated string"),
+            (ParseErrorKind::InvalidNumber, "invalid number"),
+            (ParseErrorKind::InvalidEscape, "invalid escape sequence"),
+            (
+                ParseErrorKind::InvalidControlChar,
+                "unescaped control character",
+            ),
+            (ParseErrorKind::InvalidUnicode, "invalid unicode escape"),
+            (ParseErrorKind::InvalidUtf8, "invalid UTF-8"),
+            (ParseErrorKind::InvalidLiteral, "invalid literal"),
+            (ParseErrorKind::InvalidKey, "invalid object key"),
+            (ParseErrorKind::TrailingData, "trailing data"),
+            (ParseErrorKind::DepthLimitExceeded, "nesting too deep"),
+            (ParseErrorKind::DuplicateKey, "duplicate key"),
+            (ParseErrorKind::Io, "I/O error"),
+            (ParseErrorKind::StringLengthLimitExceeded, "string too long"),
+            (ParseErrorKind::ValueCountLimitExceeded, "too many values"),
+            (ParseErrorKind::UnexpectedType, "unexpected type"),
+            (ParseErrorKind::InvalidPatch, "invalid patch"),
+            (ParseErrorKind::PatchTestFailed, "patch test failed"),
+            (ParseErrorKind::BudgetExceeded, "work budget exceeded"),
+            (ParseErrorKind::InvalidPointer, "invalid pointer"),
+            (ParseErrorKind::UnsupportedOption, "unsupported option"),
+        ];
+        for (kind, label) in cases {
+            assert_eq!(kind.to_string(), label);
+        }
+        let labels: std::collections::HashSet<_> = cases.iter().map(|(_, label)| label).collect();
+        assert_eq!(labels.len(), cases.len());
+
+        // `Debug` still names the variant, and the error's own display is
+        // the full message rather than the label.
+        let kind = ParseErrorKind::UnterminatedString;
+        assert_eq!(format!("{:?}", kind), "UnterminatedString");
+        let error = crate::parse_json("\"abc").unwrap_err();
+        assert_eq!(error.kind().to_string(), "unterminated string");
+        assert_ne!(error.to_string(), "unterminated string");
+    }
+
+    #[test]
+    fn test_error_offset() {
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error");
+        assert_eq!(error.offset(), None);
+        assert_eq!(error.with_offset(7).offset(), Some(7));
+    }
+
+    #[test]
+    fn test_error_snippet() {
+        let input = r#"{"a":1 "b":2}"#;
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error").with_offset(7);
+        assert_eq!(error.snippet(input), "{\"a\":1 \"b\":2}\n       ^");
+
+        let at_start = ParseError::new(ParseErrorKind::UnexpectedChar, "test error").with_offset(0);
+        assert_eq!(at_start.snippet("x"), "x\n^");
+
+        let at_end = ParseError::new(ParseErrorKind::UnexpectedEof, "test error").with_offset(99);
+        assert_eq!(at_end.snippet("[1,"), "[1,\n   ^");
+
+        let no_offset = ParseError::new(ParseErrorKind::UnexpectedEof, "test error");
+        assert_eq!(no_offset.snippet("[1,"), "");
+    }
+
+    #[test]
+    fn test_error_snippet_long_and_multiline() {
+        let input = format!("[\n  {}!{}\n]", "a".repeat(30), "b".repeat(30));
+        let offset = input.find('!').unwrap();
+        let error =
+            ParseError::new(ParseErrorKind::UnexpectedChar, "test error").with_offset(offset);
+        assert_eq!(
+            error.snippet(&input),
+            format!(
+                "...{}!{}...\n{}^",
+                "a".repeat(20),
+                "b".repeat(19),
+                " ".repeat(23)
+            )
+        );
+
+        let input = "[\"é\", x]";
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error").with_offset(3);
+        assert_eq!(error.snippet(input), "[\"é\", x]\n  ^");
+    }
+
+    #[test]
+    fn test_error_display_alternate_shows_snippet() {
+        let input = format!("{{\"items\": [1, 2, {}x, 3]}}", "9".repeat(40));
+        let error = crate::parse_json(&input).unwrap_err();
+        let offset = input.find('x').unwrap();
+        assert_eq!(error.offset(), Some(offset));
+        assert_eq!(
+            format!("{:#}", error),
+            format!("{}\n{}", error, error.snippet(&input))
+        );
+        assert!(!error.to_string().contains('\n'));
+
+        // The captured snippet decodes only a window around the offset, but
+        // matches the one rendered from the whole input.
+        let long = format!("[{}, nul]", "\"é\", ".repeat(500));
+        let error = crate::parse_json(&long).unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            format!("{}\n{}", error, error.snippet(&long))
+        );
+
+        // Invalid UTF-8 is shown as replacement characters.
+        let error = crate::parse_json_bytes(b"[\"\xff\", 1]").unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::InvalidUtf8);
+        assert_eq!(
+            format!("{:#}", error).lines().skip(1).collect::<Vec<_>>(),
+            ["[\"\u{FFFD}\", 1]", "  ^"]
+        );
+
+        // Context still comes first, and errors never located have no
+        // snippet to show.
+        let error = crate::parse_json("[1 2]").unwrap_err().with_context("in a");
+        assert_eq!(
+            format!("{:#}", error),
+            "in a: Expected comma or closing bracket, got '2' at line 1 column 4\n[1 2]\n   ^"
+        );
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error").with_offset(1);
+        assert_eq!(format!("{:#}", error), "test error");
     }
 
     #[test]
     fn test_error_display() {
-        let error = ParseError::new("test error");
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error");
         assert_eq!(format!("{}", error), "test error");
+
+        let error = error.with_location(3, 14);
+        assert_eq!(error.to_string(), "test error at line 3 column 14");
+        assert_eq!(error.message(), "test error");
+    }
+
+    #[test]
+    fn test_error_with_context() {
+        let error = crate::parse_json("{\n  \"a\": 1,\n  \"b\": \"open").unwrap_err();
+        let (kind, offset, message) = (error.kind(), error.offset(), error.to_string());
+        assert_eq!(error.context(), None);
+
+        let error = error.with_context("while reading config.json");
+        assert_eq!(error.kind(), kind);
+        assert_eq!(error.offset(), offset);
+        assert_eq!(error.line(), Some(3));
+        assert_eq!(error.context(), Some("while reading config.json"));
+        assert_eq!(
+            error.to_string(),
+            format!("while reading config.json: {}", message)
+        );
+        assert_eq!(kind, ParseErrorKind::UnterminatedString);
+        assert!(error.to_string().ends_with("at line 3 column 13"));
+        assert!(!error.message().contains("config.json"));
+
+        let error = error.with_context("loading settings");
+        assert_eq!(
+            error.to_string(),
+            format!("loading settings: while reading config.json: {}", message)
+        );
+        assert_eq!(
+            ParseError::new(ParseErrorKind::Io, "disk full")
+                .with_context("saving")
+                .to_string(),
+            "saving: disk full"
+        );
+    }
+
+    #[test]
+    fn test_error_locate() {
+        let input = b"{\n  \"a\": x\n}";
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error").with_offset(9);
+        let error = error.locate(input);
+        assert_eq!((error.line(), error.column()), (Some(2), Some(8)));
+
+        let at_start = ParseError::new(ParseErrorKind::UnexpectedChar, "test error")
+            .with_offset(0)
+            .locate(input);
+        assert_eq!((at_start.line(), at_start.column()), (Some(1), Some(1)));
+
+        let after_newline = ParseError::new(ParseErrorKind::UnexpectedEof, "test error")
+            .with_offset(input.len())
+            .locate(input);
+        assert_eq!(
+            (after_newline.line(), after_newline.column()),
+            (Some(3), Some(2))
+        );
+
+        let no_offset = ParseError::new(ParseErrorKind::UnexpectedEof, "test error").locate(input);
+        assert_eq!((no_offset.line(), no_offset.column()), (None, None));
     }
 
     #[test]
     fn test_error_as_std_error() {
-        let error = ParseError::new("test error");
+        let error = ParseError::new(ParseErrorKind::UnexpectedChar, "test error");
         let std_error: &dyn std::error::Error = &error;
         assert_eq!(std_error.to_string(), "test error");
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_error_from_io() {
+        use std::error::Error;
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "disk gone");
+        let error = ParseError::from(io_error);
+        assert_eq!(error.kind(), ParseErrorKind::Io);
+        assert_eq!(error.to_string(), "I/O error: disk gone");
+        assert_eq!(error.source().unwrap().to_string(), "disk gone");
+    }
 }
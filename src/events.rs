@@ -0,0 +1,120 @@
+use alloc::borrow::Cow;
+
+use crate::value::JsonValue;
+
+/// A single step of a document scanned by
+/// [`JsonParser::events`](crate::JsonParser::events).
+///
+/// Containers produce a start event, the events for their contents and a
+/// matching end event. Each object member produces a `Key` followed by the
+/// events of its value. Scalars produce a single `Value`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent<'a> {
+    /// The start of an object.
+    StartObject,
+    /// The end of the innermost open object.
+    EndObject,
+    /// The start of an array.
+    StartArray,
+    /// The end of the innermost open array.
+    EndArray,
+    /// An object key, borrowed from the input when it has no escapes.
+    Key(Cow<'a, str>),
+    /// A null, boolean, number or string value.
+    Value(JsonValue),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsonParser, ParseErrorKind, Result};
+    use alloc::vec::Vec;
+
+    fn events(input: &str) -> Result<Vec<JsonEvent<'_>>> {
+        JsonParser::new(input).events().collect()
+    }
+
+    #[test]
+    fn test_events_nested_document() {
+        let events = events(r#" {"a": [1, {"b\n": null}, []], "c": {}, "d": "x"} "#).unwrap();
+        assert_eq!(
+            events,
+            [
+                JsonEvent::StartObject,
+                JsonEvent::Key(Cow::Borrowed("a")),
+                JsonEvent::StartArray,
+                JsonEvent::Value(JsonValue::Integer(1)),
+                JsonEvent::StartObject,
+                JsonEvent::Key(Cow::Owned("b\n".into())),
+                JsonEvent::Value(JsonValue::Null),
+                JsonEvent::EndObject,
+                JsonEvent::StartArray,
+                JsonEvent::EndArray,
+                JsonEvent::EndArray,
+                JsonEvent::Key(Cow::Borrowed("c")),
+                JsonEvent::StartObject,
+                JsonEvent::EndObject,
+                JsonEvent::Key(Cow::Borrowed("d")),
+                JsonEvent::Value(JsonValue::String("x".into())),
+                JsonEvent::EndObject,
+            ]
+        );
+        assert!(matches!(&events[1], JsonEvent::Key(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_events_scalar_document() {
+        assert_eq!(
+            events("true").unwrap(),
+            [JsonEvent::Value(JsonValue::Boolean(true))]
+        );
+    }
+
+    #[test]
+    fn test_events_errors_end_the_stream() {
+        let mut parser = JsonParser::new(r#"[1, 2 3]"#);
+        let mut iter = parser.events();
+        assert_eq!(iter.next().unwrap().unwrap(), JsonEvent::StartArray);
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            JsonEvent::Value(JsonValue::Integer(1))
+        );
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            JsonEvent::Value(JsonValue::Integer(2))
+        );
+        assert_eq!(
+            iter.next().unwrap().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
+        assert!(iter.next().is_none());
+
+        let cases = [
+            ("[1] x", ParseErrorKind::TrailingData),
+            ("[1,", ParseErrorKind::UnexpectedEof),
+            (r#"{"a" 1}"#, ParseErrorKind::UnexpectedChar),
+            ("{1: 2}", ParseErrorKind::InvalidKey),
+            ("[[[]]]", ParseErrorKind::DepthLimitExceeded),
+        ];
+        for (input, kind) in cases {
+            let mut parser = JsonParser::new(input).with_max_depth(2);
+            let err = parser.events().find_map(|event| event.err()).unwrap();
+            assert_eq!(err.kind(), kind, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_events_apply_parser_options() {
+        let mut parser = JsonParser::new(r#"{"a": 1, "a": 2}"#).reject_duplicate_keys(true);
+        let err = parser.events().find_map(|event| event.err()).unwrap();
+        assert_eq!(err.kind(), ParseErrorKind::DuplicateKey);
+        assert_eq!(err.offset(), Some(9));
+
+        let mut parser = JsonParser::new("[1, [2,],]").with_trailing_commas(true);
+        assert_eq!(parser.events().count(), 6);
+
+        let mut parser = JsonParser::new("[1, [2], 3]").with_max_total_values(3);
+        let err = parser.events().find_map(|event| event.err()).unwrap();
+        assert_eq!(err.kind(), ParseErrorKind::ValueCountLimitExceeded);
+    }
+}
@@ -0,0 +1,228 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::error::{ParseError, ParseErrorKind, Result};
+use crate::value::JsonValue;
+
+/// Extracts a typed Rust value from a `JsonValue`.
+///
+/// This is a lightweight alternative to serde for pulling typed fields out
+/// of a parsed document. A value of the wrong type fails with
+/// `ParseErrorKind::UnexpectedType` and a message naming the expected and
+/// actual types.
+///
+/// ```rust
+/// use arjp::{parse_json, FromJson};
+///
+/// let value = parse_json(r#"{"age": 30, "tags": ["a", "b"]}"#).unwrap();
+/// let age = i64::from_json(&value["age"]).unwrap();
+/// let tags = Vec::<String>::from_json(&value["tags"]).unwrap();
+/// assert_eq!((age, tags.len()), (30, 2));
+/// ```
+pub trait FromJson: Sized {
+    /// Converts `value`, failing if it does not have the expected type.
+    fn from_json(value: &JsonValue) -> Result<Self>;
+}
+
+/// Names the type of a value for error messages.
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Boolean(_) => "boolean",
+        JsonValue::Integer(_)
+        | JsonValue::Unsigned(_)
+        | JsonValue::Number(_)
+        | JsonValue::RawNumber(_) => "number",
+        #[cfg(feature = "decimal")]
+        JsonValue::Decimal(_) => "number",
+        JsonValue::String(_) | JsonValue::SharedString(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+        JsonValue::Raw(_) => "raw",
+    }
+}
+
+/// Builds the error for a value that is not of the `expected` type.
+pub(crate) fn type_error(expected: &str, value: &JsonValue) -> ParseError {
+    ParseError::new(
+        ParseErrorKind::UnexpectedType,
+        &format!("Expected {}, found {}", expected, type_name(value)),
+    )
+}
+
+impl FromJson for JsonValue {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        value.as_bool().ok_or_else(|| type_error("boolean", value))
+    }
+}
+
+/// Implements `FromJson` for integer types, rejecting numbers that are
+/// fractional or out of the target type's range.
+macro_rules! from_json_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl FromJson for $ty {
+                fn from_json(value: &JsonValue) -> Result<Self> {
+                    value
+                        .as_i64()
+                        .and_then(|i| <$ty>::try_from(i).ok())
+                        .or_else(|| value.as_u64().and_then(|u| <$ty>::try_from(u).ok()))
+                        .ok_or_else(|| type_error(stringify!($ty), value))
+                }
+            }
+        )*
+    };
+}
+
+from_json_integer!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl FromJson for f64 {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        value.as_f64().ok_or_else(|| type_error("number", value))
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        f64::from_json(value).map(|f| f as f32)
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| type_error("string", value))
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    /// Maps `null` to `None` and anything else to `Some` of its conversion.
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        match value {
+            JsonValue::Null => Ok(None),
+            _ => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        value
+            .as_array()
+            .ok_or_else(|| type_error("array", value))?
+            .iter()
+            .map(T::from_json)
+            .collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        value
+            .as_object()
+            .ok_or_else(|| type_error("object", value))?
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), T::from_json(v)?)))
+            .collect()
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: &JsonValue) -> Result<Self> {
+        value
+            .as_object()
+            .ok_or_else(|| type_error("object", value))?
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), T::from_json(v)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+
+    #[test]
+    fn test_from_json_scalars() {
+        let value = parse_json(r#"[true, -5, 300, 2.5, "s", null, 18446744073709551615]"#).unwrap();
+        assert!(bool::from_json(&value[0]).unwrap());
+        assert_eq!(i8::from_json(&value[1]).unwrap(), -5);
+        assert_eq!(u16::from_json(&value[2]).unwrap(), 300);
+        assert_eq!(f64::from_json(&value[3]).unwrap(), 2.5);
+        assert_eq!(f32::from_json(&value[2]).unwrap(), 300.0);
+        assert_eq!(String::from_json(&value[4]).unwrap(), "s");
+        assert_eq!(Option::<i64>::from_json(&value[5]).unwrap(), None);
+        assert_eq!(Option::<i64>::from_json(&value[1]).unwrap(), Some(-5));
+        assert_eq!(u64::from_json(&value[6]).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_from_json_errors() {
+        let value = parse_json(r#"[-5, 300, 2.5, "s", {}]"#).unwrap();
+        let cases = [
+            (
+                u32::from_json(&value[0]).map(|_| ()),
+                "Expected u32, found number",
+            ),
+            (
+                i8::from_json(&value[1]).map(|_| ()),
+                "Expected i8, found number",
+            ),
+            (
+                i64::from_json(&value[2]).map(|_| ()),
+                "Expected i64, found number",
+            ),
+            (
+                bool::from_json(&value[3]).map(|_| ()),
+                "Expected boolean, found string",
+            ),
+            (
+                String::from_json(&value).map(|_| ()),
+                "Expected string, found array",
+            ),
+            (
+                Vec::<i64>::from_json(&value[4]).map(|_| ()),
+                "Expected array, found object",
+            ),
+        ];
+        for (result, message) in cases {
+            let err = result.unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::UnexpectedType);
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_json_nested() {
+        let value = parse_json(
+            r#"{"users": [{"name": "Ann", "scores": {"math": 9, "art": null}},
+                          {"name": "Bob", "scores": {}}]}"#,
+        )
+        .unwrap();
+        let users = Vec::<HashMap<String, JsonValue>>::from_json(&value["users"]).unwrap();
+        assert_eq!(String::from_json(&users[1]["name"]).unwrap(), "Bob");
+
+        let scores =
+            HashMap::<String, Option<u8>>::from_json(&value["users"][0]["scores"]).unwrap();
+        assert_eq!(scores["math"], Some(9));
+        assert_eq!(scores["art"], None);
+
+        let err = Vec::<HashMap<String, String>>::from_json(&value["users"]).unwrap_err();
+        assert_eq!(err.to_string(), "Expected string, found object");
+    }
+}
@@ -19,15 +19,81 @@
 //! // Or use the convenience function:
 //! let value = parse_json(json_str).unwrap();
 //! println!("Parsed: {:?}", value);
+//!
+//! // Values serialize back to compact JSON through `Display`:
+//! let text = value.to_string();
+//! assert_eq!(parse_json(&text).unwrap(), value);
+//! ```
+//!
+//! # Building values
+//!
+//! `JsonValue` implements `From` for `bool`, the integer types up to `i64`/`u64`,
+//! `f32`/`f64`, `String`, `&str`, `Vec<JsonValue>` and `Map`, and maps
+//! `Option<T>` to `null` when it is `None`:
+//!
+//! ```rust
+//! use arjp::{JsonValue, Map};
+//!
+//! let mut user = Map::new();
+//! user.insert("name".to_string(), JsonValue::from("John"));
+//! user.insert("age".to_string(), JsonValue::from(30));
+//! user.insert("email".to_string(), JsonValue::from(None::<&str>));
+//! let value = JsonValue::from(user);
+//! assert_eq!(value["age"], JsonValue::Integer(30));
+//! assert!(value["email"].is_null());
+//! ```
+//!
+//! The [`json!`] macro builds the same values from literal syntax:
+//!
+//! ```rust
+//! use arjp::json;
+//!
+//! let value = json!({"name": "John", "age": 30, "email": null});
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[macro_use]
+mod macros;
+
+mod borrowed;
+mod builder;
+mod convert;
 mod error;
+mod events;
+mod from_json;
 mod parser;
+mod patch;
+#[cfg(feature = "std")]
+mod reader;
+mod ser;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod shape;
+mod shared;
+mod spanned;
 mod value;
+mod visitor;
 
-pub use error::{ParseError, Result};
-pub use parser::JsonParser;
-pub use value::JsonValue;
+pub use borrowed::{BorrowedMap, BorrowedValue};
+pub use builder::JsonParserBuilder;
+pub use error::{ParseError, ParseErrorKind, Result};
+pub use events::JsonEvent;
+pub use from_json::FromJson;
+pub use parser::{
+    ArrayElements, Checkpoint, Events, JsonParser, Leniency, ParseStats, DEFAULT_MAX_DEPTH,
+};
+pub use patch::apply_patch;
+#[cfg(feature = "std")]
+pub use reader::ReaderParser;
+pub use ser::{FloatNotation, SerializeOptions};
+pub use shape::Shape;
+pub use shared::{SharedMap, SharedValue};
+pub use spanned::{SpannedKind, SpannedMember, SpannedValue};
+pub use value::{Children, Entry, JsonValue, Map};
+pub use visitor::Visitor;
 
 /// Convenience function to parse a JSON string in one step.
 ///
@@ -42,3 +108,159 @@ pub fn parse_json(input: &str) -> Result<JsonValue> {
     let mut parser = JsonParser::new(input);
     parser.parse()
 }
+
+/// Convenience function to parse an owned JSON string in one step.
+///
+/// The input is consumed, and the returned `JsonValue` owns all of its data,
+/// so it is `'static` and can be stored or sent across threads without any
+/// lifetime tied to the input. Use [`JsonParser::parse_borrowed`] instead to
+/// borrow strings from a buffer that outlives the value.
+///
+/// # Arguments
+///
+/// * `input` - The JSON string to parse.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `JsonValue` or a `ParseError`.
+pub fn parse_json_owned(input: alloc::string::String) -> Result<JsonValue> {
+    parse_json(&input)
+}
+
+/// Convenience function to parse JSON from raw bytes in one step.
+///
+/// UTF-8 is validated only inside string literals, so there is no need to
+/// convert the input to a `&str` first.
+///
+/// # Arguments
+///
+/// * `input` - The JSON bytes to parse.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `JsonValue` or a `ParseError`.
+pub fn parse_json_bytes(input: &[u8]) -> Result<JsonValue> {
+    let mut parser = JsonParser::from_bytes(input);
+    parser.parse()
+}
+
+/// Convenience function to parse JSON pulled incrementally from a reader.
+///
+/// The input is read through a fixed-size buffer rather than loaded into
+/// memory first. I/O failures are reported as `ParseErrorKind::Io`.
+///
+/// # Arguments
+///
+/// * `reader` - The source to read JSON from, such as a `File`.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `JsonValue` or a `ParseError`.
+#[cfg(feature = "std")]
+pub fn parse_json_reader<R: std::io::Read>(reader: R) -> Result<JsonValue> {
+    let mut parser = JsonParser::from_reader(reader);
+    parser.parse()
+}
+
+/// Parses newline-delimited JSON (NDJSON / JSON Lines), one value per line.
+///
+/// Blank lines are skipped. Each line is parsed independently, so a malformed
+/// line yields an `Err` without stopping iteration over the rest. Error
+/// offsets are relative to the start of the offending line, so every error
+/// reports line 1.
+///
+/// # Arguments
+///
+/// * `input` - The newline-delimited JSON text to parse.
+///
+/// # Returns
+///
+/// An iterator yielding a `Result` for every non-blank line.
+pub fn parse_lines(input: &str) -> impl Iterator<Item = Result<JsonValue>> + '_ {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_json)
+}
+
+/// Parses a sequence of JSON values separated by arbitrary whitespace, with
+/// no enclosing array, as in `1 true "x" [2]`.
+///
+/// Unlike [`parse_lines`], values may share a line or span several, and an
+/// error in any value stops parsing. Its offset is relative to the start of
+/// `input`. Input that is empty or only whitespace yields an empty `Vec`.
+///
+/// # Arguments
+///
+/// * `input` - The whitespace-separated JSON values to parse.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed values in order, or the first `ParseError`.
+pub fn parse_many(input: &str) -> Result<alloc::vec::Vec<JsonValue>> {
+    let mut parser = JsonParser::new(input);
+    let mut values = alloc::vec::Vec::new();
+    while !parser
+        .remaining()
+        .trim_start_matches([' ', '\t', '\n', '\r'])
+        .is_empty()
+    {
+        values.push(parser.parse_one()?);
+    }
+    Ok(values)
+}
+
+/// Parses one length-prefixed JSON frame from the start of `buf`: a 4-byte
+/// big-endian length followed by exactly that many bytes of JSON.
+///
+/// Anything after the frame is left alone, so a buffer holding several
+/// frames can be consumed by slicing off the returned length each time. A
+/// buffer too short for the prefix or the declared body fails with
+/// `ParseErrorKind::UnexpectedEof`, so a streaming caller can wait for more
+/// bytes. Errors inside the body have offsets relative to the start of the
+/// body rather than of `buf`.
+///
+/// # Arguments
+///
+/// * `buf` - The bytes starting at a frame's length prefix.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed `JsonValue` and the number of bytes the
+/// frame occupied, prefix included, or a `ParseError`.
+///
+/// ```rust
+/// use arjp::{parse_framed, JsonValue};
+///
+/// let buf = b"\x00\x00\x00\x07[1,2,3]\x00\x00\x00\x04true";
+/// let (first, used) = parse_framed(buf).unwrap();
+/// assert_eq!((first[2].as_i64(), used), (Some(3), 11));
+/// let (second, _) = parse_framed(&buf[used..]).unwrap();
+/// assert_eq!(second, JsonValue::Boolean(true));
+/// ```
+pub fn parse_framed(buf: &[u8]) -> Result<(JsonValue, usize)> {
+    let Some((prefix, rest)) = buf.split_first_chunk::<4>() else {
+        return Err(ParseError::new(
+            ParseErrorKind::UnexpectedEof,
+            &alloc::format!("Frame length prefix needs 4 bytes, got {}", buf.len()),
+        )
+        .with_offset(buf.len()));
+    };
+    let len = u32::from_be_bytes(*prefix) as usize;
+    let Some(body) = rest.get(..len) else {
+        return Err(ParseError::new(
+            ParseErrorKind::UnexpectedEof,
+            &alloc::format!("Frame declares {} bytes of JSON, got {}", len, rest.len()),
+        )
+        .with_offset(buf.len()));
+    };
+    let value = parse_json_bytes(body)?;
+    Ok((value, prefix.len() + len))
+}
+
+/// Re-exports used by the `json!` macro, so that it works in `no_std` crates
+/// that have not imported `alloc` themselves.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec;
+}
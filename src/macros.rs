@@ -0,0 +1,169 @@
+/// Builds a `JsonValue` from JSON-like literal syntax.
+///
+/// Arrays and objects nest freely, `null` maps to `JsonValue::Null`, and any
+/// other value is an arbitrary Rust expression converted with
+/// `JsonValue::from`. Object keys are string expressions; wrap a computed key
+/// in parentheses.
+///
+/// ```rust
+/// use arjp::{json, parse_json};
+///
+/// let age = 30;
+/// let value = json!({
+///     "name": "John",
+///     "age": age,
+///     "tags": [1, 2, 3],
+///     "spouse": null
+/// });
+/// assert_eq!(
+///     value,
+///     parse_json(r#"{"name": "John", "age": 30, "tags": [1, 2, 3], "spouse": null}"#).unwrap()
+/// );
+/// ```
+#[macro_export]
+macro_rules! json {
+    // Array elements are accumulated one at a time. `[$($elems,)*]` holds the
+    // finished elements and the remaining tokens follow.
+    (@array [$($elems:expr,)*]) => {
+        $crate::__private::vec![$($elems,)*]
+    };
+    (@array [$($elems:expr),*]) => {
+        $crate::__private::vec![$($elems),*]
+    };
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!({$($map)*})] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)* $crate::json!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::json!(@array [$($elems,)* $crate::json!($last)])
+    };
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // Object members are inserted into `$object` one at a time. The key is
+    // munched token by token in `($($key)*)` until the colon; the remaining
+    // tokens are passed twice so errors can point at the offending token.
+    (@object $object:ident () () ()) => {};
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+        $crate::json!(@object $object () ($($rest)*) ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+    };
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!({$($map)*})) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::json!(@object $object [$($key)+] ($crate::json!($value)));
+    };
+    (@object $object:ident (($key:expr)) (: $($rest:tt)*) $copy:tt) => {
+        $crate::json!(@object $object ($key) (: $($rest)*) (: $($rest)*));
+    };
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::json!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::JsonValue::Null
+    };
+    ([]) => {
+        $crate::JsonValue::Array($crate::__private::vec![])
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::JsonValue::Array($crate::json!(@array [] $($tt)+))
+    };
+    ({}) => {
+        $crate::JsonValue::Object($crate::Map::new())
+    };
+    ({ $($tt:tt)+ }) => {
+        $crate::JsonValue::Object({
+            let mut object = $crate::Map::new();
+            $crate::json!(@object object () ($($tt)+) ($($tt)+));
+            object
+        })
+    };
+    ($other:expr) => {
+        $crate::JsonValue::from($other)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_json, JsonValue};
+
+    #[test]
+    fn test_json_scalars() {
+        assert_eq!(json!(null), JsonValue::Null);
+        assert_eq!(json!(true), JsonValue::Boolean(true));
+        assert_eq!(json!(-7), JsonValue::Integer(-7));
+        assert_eq!(json!(2.5), JsonValue::Number(2.5));
+        assert_eq!(json!("hi"), JsonValue::String("hi".to_string()));
+    }
+
+    #[test]
+    fn test_json_arrays() {
+        assert_eq!(json!([]), parse_json("[]").unwrap());
+        assert_eq!(
+            json!([1, null, "two", [true, []], {}]),
+            parse_json(r#"[1, null, "two", [true, []], {}]"#).unwrap()
+        );
+        assert_eq!(json!([1, 2,]), parse_json("[1, 2]").unwrap());
+    }
+
+    #[test]
+    fn test_json_objects() {
+        assert_eq!(json!({}), parse_json("{}").unwrap());
+        assert_eq!(
+            json!({
+                "name": "John",
+                "age": 30,
+                "tags": [1, 2, 3],
+                "address": {"city": "Rome", "zip": null},
+            }),
+            parse_json(
+                r#"{"name": "John", "age": 30, "tags": [1, 2, 3],
+                    "address": {"city": "Rome", "zip": null}}"#
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_interpolation() {
+        let name = "Ann";
+        let scores = vec![JsonValue::from(1.5), JsonValue::from(2.5)];
+        let key = format!("user_{}", 7);
+        let value = json!({
+            (key): {"name": name, "scores": scores, "adult": 20 >= 18},
+            "missing": None::<i64>,
+            "nested": json!([name]),
+        });
+        assert_eq!(
+            value,
+            parse_json(
+                r#"{"user_7": {"name": "Ann", "scores": [1.5, 2.5], "adult": true},
+                    "missing": null, "nested": ["Ann"]}"#
+            )
+            .unwrap()
+        );
+    }
+}
@@ -1,115 +1,1288 @@
-use crate::error::{ParseError, Result};
-use crate::value::JsonValue;
-use std::collections::HashMap;
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::borrowed::{borrowed_map_with_capacity, BorrowedMap, BorrowedValue};
+use crate::builder::JsonParserBuilder;
+use crate::error::{ParseError, ParseErrorKind, Result};
+use crate::events::JsonEvent;
+#[cfg(feature = "std")]
+use crate::reader::ReaderParser;
+use crate::spanned::{SpannedKind, SpannedMember, SpannedValue};
+use crate::value::{drain_map, map_with_capacity, pointer_tokens, JsonValue, Map};
+
+/// The UTF-8 encoding of the byte order mark U+FEFF.
+pub(crate) const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Default limit on how deeply arrays and objects may nest.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Counts of what a document contains, collected by
+/// [`JsonParser::parse_with_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// Number of objects, including empty ones.
+    pub object_count: usize,
+    /// Number of arrays, including empty ones.
+    pub array_count: usize,
+    /// Number of string values. Object keys are not counted.
+    pub string_count: usize,
+    /// Number of numbers, including any non-finite ones.
+    pub number_count: usize,
+    /// Deepest nesting of arrays and objects; 0 for a lone scalar.
+    pub max_depth: usize,
+    /// Number of input bytes consumed, including surrounding whitespace but
+    /// not a skipped byte order mark.
+    pub total_bytes: usize,
+}
+
+/// A non-standard construct accepted by one of the lenient parser options,
+/// as reported by [`JsonParser::parse_with_report`].
+///
+/// Each variant holds the byte offset at which the construct starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Leniency {
+    /// A byte order mark before the value.
+    ByteOrderMark(usize),
+    /// A `//` or `/* */` comment.
+    Comment(usize),
+    /// A comma directly before a closing bracket or brace.
+    TrailingComma(usize),
+    /// A string or object key in single quotes.
+    SingleQuotedString(usize),
+    /// A `\'` escape sequence in a string.
+    EscapedSingleQuote(usize),
+    /// An object key without quotes.
+    UnquotedKey(usize),
+    /// A `NaN` or `Infinity` token, or a number too large for an `f64`.
+    NonFiniteNumber(usize),
+    /// An unescaped control character inside a string.
+    ControlCharacter(usize),
+    /// A `null`, `true` or `false` literal not written in lowercase.
+    CaseInsensitiveLiteral(usize),
+    /// A number in one of the JSON5 forms: hexadecimal, with a leading `+`,
+    /// or with a leading or trailing decimal point.
+    Json5Number(usize),
+}
+
+impl Leniency {
+    /// Returns the byte offset at which the construct starts.
+    pub fn offset(&self) -> usize {
+        match *self {
+            Leniency::ByteOrderMark(offset)
+            | Leniency::Comment(offset)
+            | Leniency::TrailingComma(offset)
+            | Leniency::SingleQuotedString(offset)
+            | Leniency::EscapedSingleQuote(offset)
+            | Leniency::UnquotedKey(offset)
+            | Leniency::NonFiniteNumber(offset)
+            | Leniency::ControlCharacter(offset)
+            | Leniency::CaseInsensitiveLiteral(offset)
+            | Leniency::Json5Number(offset) => offset,
+        }
+    }
+}
+
+/// A saved parser position, created by [`JsonParser::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    position: usize,
+}
+
+impl Checkpoint {
+    /// Returns the byte offset the checkpoint was taken at.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
 
 /// JSON parser implementation with performance optimizations.
+///
+/// The parser works over raw bytes. Structural characters are always ASCII,
+/// so UTF-8 only needs to be validated inside string literals. Cloning a
+/// parser is cheap: the input is borrowed, not copied.
+#[derive(Clone)]
 pub struct JsonParser<'a> {
-    input: &'a str,
+    input: &'a [u8],
+    /// The input as text when it is already known to be valid UTF-8, letting
+    /// string contents be sliced out without re-validation.
+    text: Option<&'a str>,
     position: usize,
-    remaining: &'a str,
+    /// Position just past the last top-level value parsed.
+    value_end: usize,
+    /// Byte range of the last number parsed.
+    number_span: Option<Range<usize>>,
+    depth: usize,
+    max_depth: usize,
+    reject_duplicate_keys: bool,
+    allow_non_finite_numbers: bool,
+    allow_trailing_commas: bool,
+    allow_comments: bool,
+    allow_single_quotes: bool,
+    allow_unquoted_keys: bool,
+    max_string_length: Option<usize>,
+    max_total_values: Option<usize>,
+    value_count: usize,
+    step_budget: Option<usize>,
+    /// Position at which the current top-level value began, from which
+    /// consumed bytes count towards `step_budget`.
+    budget_start: usize,
+    arbitrary_precision: bool,
+    #[cfg(feature = "decimal")]
+    decimal_numbers: bool,
+    allow_control_characters: bool,
+    reject_number_underflow: bool,
+    allow_bom: bool,
+    require_top_level_aggregate: bool,
+    case_insensitive_literals: bool,
+    allow_json5_numbers: bool,
+    utf8_lossy: bool,
+    /// Statistics being collected by `parse_with_stats`, if any.
+    stats: Option<ParseStats>,
+    /// Lenient constructs being recorded by `parse_with_report`, if any.
+    leniencies: Option<Vec<Leniency>>,
+    /// Emptied containers to reuse during `parse_into`, if any.
+    recycled: Option<Recycled>,
 }
 
 impl<'a> JsonParser<'a> {
     /// Creates a new parser instance for the given JSON string.
     pub fn new(input: &'a str) -> Self {
+        Self::from_input(input.as_bytes(), Some(input))
+    }
+
+    /// Returns a [`JsonParserBuilder`] for a configuration that can be
+    /// reused across inputs.
+    pub fn builder() -> JsonParserBuilder {
+        JsonParserBuilder::new()
+    }
+
+    /// Creates a new parser instance over raw bytes.
+    ///
+    /// The bytes are not validated up front; invalid UTF-8 inside a string
+    /// literal fails with `ParseErrorKind::InvalidUtf8` at the offending
+    /// offset, and anywhere else it is an unexpected character.
+    pub fn from_bytes(input: &'a [u8]) -> Self {
+        Self::from_input(input, None)
+    }
+
+    /// Creates a parser that pulls its input incrementally from `reader`.
+    ///
+    /// A `JsonParser` works on a complete document in memory and borrows
+    /// from it, so a streamed input gets a [`ReaderParser`] instead, which
+    /// owns the reader and a fixed-size buffer. It parses strict JSON and
+    /// offers only `with_max_depth`, `allow_control_characters` and
+    /// `allow_bom`; `JsonParserBuilder::build_reader` rejects any other
+    /// option. Errors carry the same kinds, messages and offsets as
+    /// `JsonParser` reports for that input.
+    ///
+    /// ```rust
+    /// use arjp::{JsonParser, JsonValue};
+    ///
+    /// let input: &[u8] = br#"{"ok": true}"#;
+    /// let value = JsonParser::from_reader(input).parse().unwrap();
+    /// assert_eq!(value["ok"], JsonValue::Boolean(true));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(reader: R) -> ReaderParser<R> {
+        ReaderParser::new(reader)
+    }
+
+    fn from_input(input: &'a [u8], text: Option<&'a str>) -> Self {
         JsonParser {
             input,
+            text,
             position: 0,
-            remaining: input,
+            value_end: 0,
+            number_span: None,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            reject_duplicate_keys: false,
+            allow_non_finite_numbers: false,
+            allow_trailing_commas: false,
+            allow_comments: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            max_string_length: None,
+            max_total_values: None,
+            value_count: 0,
+            step_budget: None,
+            budget_start: 0,
+            arbitrary_precision: false,
+            #[cfg(feature = "decimal")]
+            decimal_numbers: false,
+            allow_control_characters: false,
+            reject_number_underflow: false,
+            allow_bom: true,
+            require_top_level_aggregate: false,
+            case_insensitive_literals: false,
+            allow_json5_numbers: false,
+            utf8_lossy: false,
+            stats: None,
+            leniencies: None,
+            recycled: None,
+        }
+    }
+
+    /// Sets the maximum nesting depth of arrays and objects.
+    ///
+    /// Input nested more deeply fails with `ParseErrorKind::DepthLimitExceeded`.
+    /// The parsing methods keep their nesting on the heap, so the limit
+    /// only bounds memory use there; a `ReaderParser` recurses per level,
+    /// and so do dropping, comparing and serializing the values built, so
+    /// the limit still protects them against stack exhaustion on untrusted
+    /// input. Defaults to [`DEFAULT_MAX_DEPTH`].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Controls whether an object may repeat a key.
+    ///
+    /// By default the last occurrence of a key wins. When enabled, a repeated
+    /// key fails with `ParseErrorKind::DuplicateKey` at the offset of its
+    /// second occurrence.
+    pub fn reject_duplicate_keys(mut self, reject: bool) -> Self {
+        self.reject_duplicate_keys = reject;
+        self
+    }
+
+    /// Controls whether the non-standard `NaN`, `Infinity` and `-Infinity`
+    /// tokens are accepted as numbers.
+    ///
+    /// Strict JSON forbids them, so they are rejected by default. Some
+    /// producers, such as Python's `json.dumps`, emit them anyway. When
+    /// enabled, number literals too large for `f64`, such as `1e400`, also
+    /// become infinities instead of failing with
+    /// `ParseErrorKind::InvalidNumber`.
+    pub fn allow_non_finite_numbers(mut self, allow: bool) -> Self {
+        self.allow_non_finite_numbers = allow;
+        self
+    }
+
+    /// Controls whether a comma may directly precede the closing `]` or `}`
+    /// of an array or object, as in `[1, 2,]`.
+    ///
+    /// Strict JSON forbids this, so it is rejected by default.
+    pub fn with_trailing_commas(mut self, allow: bool) -> Self {
+        self.allow_trailing_commas = allow;
+        self
+    }
+
+    /// Controls whether `//` line comments and `/* */` block comments are
+    /// skipped wherever whitespace is allowed, as in JSONC files.
+    ///
+    /// Block comments do not nest, and an unterminated block comment runs to
+    /// the end of the input. Strict JSON has no comments, so they are rejected
+    /// by default.
+    pub fn allow_comments(mut self, allow: bool) -> Self {
+        self.allow_comments = allow;
+        self
+    }
+
+    /// Controls whether strings and object keys may be delimited by single
+    /// quotes, as in `{'key': 'value'}`.
+    ///
+    /// Single-quoted strings support the same escapes as double-quoted
+    /// ones, plus `\'`; a `"` inside them needs no escape. Strict JSON only
+    /// has double-quoted strings, so single quotes are rejected by default.
+    pub fn allow_single_quotes(mut self, allow: bool) -> Self {
+        self.allow_single_quotes = allow;
+        self
+    }
+
+    /// Controls whether object keys may be bare identifiers, as in
+    /// `{name: "John"}`.
+    ///
+    /// An unquoted key is made of ASCII letters, digits, `_` and `$`, and
+    /// must not start with a digit. Quoted keys are still accepted. Strict
+    /// JSON requires quoted keys, so bare ones are rejected by default.
+    pub fn allow_unquoted_keys(mut self, allow: bool) -> Self {
+        self.allow_unquoted_keys = allow;
+        self
+    }
+
+    /// Sets the maximum length in bytes of a decoded string or object key.
+    ///
+    /// A longer string fails with `ParseErrorKind::StringLengthLimitExceeded`
+    /// at the offset of its opening quote. Unlimited by default.
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = Some(max_string_length);
+        self
+    }
+
+    /// Sets the maximum number of values in the document, counting every
+    /// array, object and scalar but not object keys.
+    ///
+    /// The value that exceeds the limit fails with
+    /// `ParseErrorKind::ValueCountLimitExceeded` at its offset, so a document
+    /// made of millions of tiny values is rejected as soon as the limit is
+    /// reached. Unlimited by default.
+    pub fn with_max_total_values(mut self, max_total_values: usize) -> Self {
+        self.max_total_values = Some(max_total_values);
+        self
+    }
+
+    /// Sets a hard limit on the work spent parsing one top-level value,
+    /// counted in steps: one per byte consumed and one per value produced.
+    ///
+    /// Once the count passes `steps` parsing fails with
+    /// `ParseErrorKind::BudgetExceeded`, which gives a predictable upper
+    /// bound on the cost of untrusted input whatever its shape. The budget
+    /// is checked as each value starts and once the value is complete, so a
+    /// single long string is only caught at its end; combine this with
+    /// [`JsonParser::with_max_string_length`] to bound that too. Unlimited by
+    /// default.
+    pub fn with_step_budget(mut self, steps: usize) -> Self {
+        self.step_budget = Some(steps);
+        self
+    }
+
+    /// Controls whether numbers are kept as their exact source text.
+    ///
+    /// When enabled every number becomes a `JsonValue::RawNumber` holding the
+    /// literal as written, so no precision is lost to `f64` rounding and the
+    /// serializer writes it back byte-for-byte. Conversion happens on demand
+    /// through `as_f64`, `as_i64` and `as_u64`. Disabled by default.
+    pub fn with_arbitrary_precision(mut self, enabled: bool) -> Self {
+        self.arbitrary_precision = enabled;
+        self
+    }
+
+    /// Controls whether numbers with a fraction or exponent are parsed into
+    /// an exact `rust_decimal::Decimal`.
+    ///
+    /// When enabled such numbers become `JsonValue::Decimal`, so `0.1` is
+    /// stored as exactly one tenth and serializes back as `0.1`. Literals
+    /// that a `Decimal` cannot hold exactly, such as `1e100`, fail with
+    /// `ParseErrorKind::InvalidNumber`. Integers are unaffected, and
+    /// `with_arbitrary_precision` takes priority. Requires the `decimal`
+    /// feature; disabled by default.
+    #[cfg(feature = "decimal")]
+    pub fn with_decimal_numbers(mut self, enabled: bool) -> Self {
+        self.decimal_numbers = enabled;
+        self
+    }
+
+    /// Controls whether raw control characters (U+0000 to U+001F) may appear
+    /// unescaped inside strings and object keys.
+    ///
+    /// Strict JSON requires them to be escaped, so by default they fail with
+    /// `ParseErrorKind::InvalidControlChar` at their offset. When enabled
+    /// they are kept as they are. Disabled by default.
+    pub fn allow_control_characters(mut self, allow: bool) -> Self {
+        self.allow_control_characters = allow;
+        self
+    }
+
+    /// Controls whether a non-zero number too small for `f64`, such as
+    /// `1e-400`, is an error.
+    ///
+    /// By default such numbers silently become `0.0`. When enabled they fail
+    /// with `ParseErrorKind::InvalidNumber`. Numbers too large for `f64`
+    /// always fail unless `allow_non_finite_numbers` is enabled, in which
+    /// case they become infinities.
+    pub fn reject_number_underflow(mut self, reject: bool) -> Self {
+        self.reject_number_underflow = reject;
+        self
+    }
+
+    /// Controls whether a UTF-8 byte order mark (`EF BB BF`) at the very
+    /// start of the input is skipped.
+    ///
+    /// Files saved by some Windows tools begin with one. Enabled by default;
+    /// when disabled the mark is an unexpected character.
+    pub fn allow_bom(mut self, allow: bool) -> Self {
+        self.allow_bom = allow;
+        self
+    }
+
+    /// Controls whether the top-level value must be an object or array.
+    ///
+    /// RFC 8259 allows any value at the top level, so bare scalars such as
+    /// `42` are accepted by default. Some consumers follow the older RFC 4627
+    /// rule instead; when enabled, a scalar at the top level fails with
+    /// `ParseErrorKind::UnexpectedChar` at its first character.
+    pub fn require_top_level_aggregate(mut self, require: bool) -> Self {
+        self.require_top_level_aggregate = require;
+        self
+    }
+
+    /// Controls whether the `null`, `true` and `false` literals are
+    /// matched regardless of ASCII case, accepting `NULL`, `True` and the
+    /// like from non-conforming producers.
+    ///
+    /// Strict JSON literals are lowercase, so other spellings fail with
+    /// `ParseErrorKind::InvalidLiteral` by default, or
+    /// `ParseErrorKind::UnexpectedChar` when they start with a capital.
+    /// Disabled by default.
+    pub fn case_insensitive_literals(mut self, enabled: bool) -> Self {
+        self.case_insensitive_literals = enabled;
+        self
+    }
+
+    /// Controls whether numbers may take the extra forms allowed by JSON5:
+    /// hexadecimal integers such as `0xFF`, a leading `+`, and a leading or
+    /// trailing decimal point as in `.5` and `5.`.
+    ///
+    /// These are stored like their decimal equivalents, so `0xFF` becomes
+    /// `JsonValue::Integer(255)` and `5.` becomes `JsonValue::Number(5.0)`;
+    /// `with_arbitrary_precision` keeps them as `255` and `5.0`. Hexadecimal
+    /// integers beyond `u64::MAX` fail with `ParseErrorKind::InvalidNumber`.
+    /// Strict JSON rejects all of these forms. Disabled by default.
+    pub fn allow_json5_numbers(mut self, allow: bool) -> Self {
+        self.allow_json5_numbers = allow;
+        self
+    }
+
+    /// Controls whether invalid UTF-8 inside string literals is replaced
+    /// instead of rejected.
+    ///
+    /// When enabled each invalid sequence in a string or object key becomes
+    /// U+FFFD, as `String::from_utf8_lossy` does, rather than failing with
+    /// `ParseErrorKind::InvalidUtf8`. Invalid bytes outside strings are
+    /// still unexpected characters. This only matters for
+    /// [`JsonParser::from_bytes`], since `&str` input is always valid.
+    /// Disabled by default.
+    pub fn utf8_lossy(mut self, lossy: bool) -> Self {
+        self.utf8_lossy = lossy;
+        self
+    }
+
+    /// Returns whether `b` ends a run of plain string bytes: the closing
+    /// quote `close`, a backslash, or a control character when those are
+    /// rejected.
+    #[inline]
+    fn is_string_special(&self, b: u8, close: u8) -> bool {
+        b == close || b == b'\\' || (b < 0x20 && !self.allow_control_characters)
+    }
+
+    /// Returns whether `b` opens a string literal.
+    #[inline]
+    fn is_quote(&self, b: u8) -> bool {
+        b == b'"' || (b == b'\'' && self.allow_single_quotes)
+    }
+
+    /// Adds the line and column to an error leaving a public method.
+    fn locate<T>(&self, result: Result<T>) -> Result<T> {
+        result.map_err(|err| err.locate(self.input))
+    }
+
+    /// Builds an error of the given kind located at the current position.
+    fn error(&self, kind: ParseErrorKind, message: &str) -> ParseError {
+        ParseError::new(kind, message).with_offset(self.position)
+    }
+
+    /// Builds an error of the given kind, or `UnexpectedEof` if the input
+    /// has run out, since more input could then still complete the value.
+    fn error_unless_eof(&self, kind: ParseErrorKind, message: &str) -> ParseError {
+        match self.peek() {
+            Some(_) => self.error(kind, message),
+            None => self.error(ParseErrorKind::UnexpectedEof, message),
+        }
+    }
+
+    /// Returns the unconsumed part of the input.
+    ///
+    /// Only the byte `position` is tracked while parsing; this slice is
+    /// derived from it on demand rather than maintained on every step.
+    #[inline]
+    fn rest(&self) -> &'a [u8] {
+        &self.input[self.position..]
+    }
+
+    /// Returns the next byte without consuming it.
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    /// Decodes the character at the current position along with its length
+    /// in bytes. An invalid UTF-8 sequence decodes as a one-byte U+FFFD.
+    #[inline]
+    fn peek_char(&self) -> Option<(char, usize)> {
+        let rest = self.rest();
+        let first = *rest.first()?;
+        if first.is_ascii() {
+            return Some((first as char, 1));
+        }
+        let width = utf8_char_width(first).min(rest.len());
+        match core::str::from_utf8(&rest[..width]) {
+            Ok(s) => s.chars().next().map(|c| (c, width)),
+            Err(_) => Some((char::REPLACEMENT_CHARACTER, 1)),
         }
     }
 
     /// Advances the parser to the next character and returns it.
     #[inline]
     fn next_char(&mut self) -> Option<char> {
-        let c = self.remaining.chars().next();
-        if let Some(ch) = c {
-            self.position += ch.len_utf8();
-            self.remaining = &self.input[self.position..];
-        }
-        c
+        let (c, width) = self.peek_char()?;
+        self.position += width;
+        Some(c)
     }
 
-    /// Skips whitespace characters efficiently.
+    /// Skips whitespace characters efficiently, and comments when they are
+    /// allowed.
+    ///
+    /// JSON whitespace is always ASCII (space, tab, CR, LF), so the run is
+    /// measured over raw bytes and skipped with a single position update.
+    /// Fails only on a block comment that is never closed.
     #[inline]
-    fn skip_whitespace(&mut self) {
-        while let Some(c) = self.remaining.chars().next() {
-            if !c.is_whitespace() {
-                break;
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            let skipped = self
+                .rest()
+                .iter()
+                .take_while(|&&b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+                .count();
+            self.position += skipped;
+            if !self.allow_comments || !self.skip_comment()? {
+                return Ok(());
             }
-            self.next_char();
         }
     }
 
+    /// Skips a single comment at the current position, returning whether
+    /// there was one.
+    ///
+    /// A block comment without its closing `*/` fails with
+    /// `ParseErrorKind::UnexpectedEof` at the opening `/*`, since more input
+    /// could still close it.
+    fn skip_comment(&mut self) -> Result<bool> {
+        let rest = self.rest();
+        if rest.starts_with(b"//") || rest.starts_with(b"/*") {
+            self.note(Leniency::Comment(self.position));
+        }
+        if rest.starts_with(b"//") {
+            self.position += rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+            Ok(true)
+        } else if rest.starts_with(b"/*") {
+            match rest[2..].windows(2).position(|w| w == b"*/") {
+                Some(end) => self.position += end + 4,
+                None => {
+                    return Err(
+                        self.error(ParseErrorKind::UnexpectedEof, "Unterminated block comment")
+                    )
+                }
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Returns the byte offset of the next unconsumed byte of the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes from the start of the input to the end of
+    /// the last top-level value parsed, or 0 before any value is parsed.
+    ///
+    /// This counts any byte order mark and whitespace before the value, but
+    /// not whitespace after it, which [`JsonParser::parse`] consumes while
+    /// checking for trailing data. After `parse` it is therefore the length
+    /// of the document without its trailing whitespace, while `position()`
+    /// is the length of the whole input.
+    ///
+    /// ```rust
+    /// use arjp::JsonParser;
+    ///
+    /// let mut parser = JsonParser::new(" [1, 2]  \n");
+    /// parser.parse().unwrap();
+    /// assert_eq!(parser.consumed_bytes(), 7);
+    /// assert_eq!(parser.position(), 10);
+    /// ```
+    pub fn consumed_bytes(&self) -> usize {
+        self.value_end
+    }
+
+    /// Returns the source text of the last number parsed, exactly as it
+    /// appears in the input, or `None` before any number is parsed.
+    ///
+    /// The text is sliced from the input without allocating, so a caller
+    /// can show a number as it was written, such as `1.200` rather than the
+    /// `1.2` its `f64` prints as. To keep the text of every number in the
+    /// tree instead, enable `with_arbitrary_precision`: each number is then
+    /// a `JsonValue::RawNumber` that still converts with `as_f64`.
+    ///
+    /// ```rust
+    /// use arjp::JsonParser;
+    ///
+    /// let mut parser = JsonParser::new("1.200");
+    /// assert_eq!(parser.parse().unwrap().as_f64(), Some(1.2));
+    /// assert_eq!(parser.number_source(), Some("1.200"));
+    /// ```
+    pub fn number_source(&self) -> Option<&'a str> {
+        let span = self.number_span.clone()?;
+        match self.text {
+            Some(text) => text.get(span),
+            None => core::str::from_utf8(&self.input[span]).ok(),
+        }
+    }
+
+    /// Returns the unconsumed part of the input, starting at `position()`.
+    ///
+    /// After [`JsonParser::parse_one`] this is whatever follows the value,
+    /// including any whitespace. For a parser created with `from_bytes`
+    /// whose remaining bytes are not valid UTF-8, only the valid prefix is
+    /// returned; use [`JsonParser::remaining_bytes`] to get all of them.
+    pub fn remaining(&self) -> &'a str {
+        // A checkpoint restored from another parser may point inside a
+        // character, so the text is not sliced unchecked.
+        if let Some(rest) = self.text.and_then(|text| text.get(self.position..)) {
+            return rest;
+        }
+        let rest = self.rest();
+        match core::str::from_utf8(rest) {
+            Ok(s) => s,
+            Err(e) => core::str::from_utf8(&rest[..e.valid_up_to()]).unwrap_or_default(),
+        }
+    }
+
+    /// Returns the unconsumed bytes of the input, starting at `position()`.
+    ///
+    /// Useful for protocols that follow a JSON value with binary data.
+    pub fn remaining_bytes(&self) -> &'a [u8] {
+        self.rest()
+    }
+
+    /// Saves the current position so that it can be returned to with
+    /// [`JsonParser::restore`].
+    ///
+    /// Together they allow speculative parsing: take a checkpoint, try a
+    /// parse, and restore if it fails. A failed parse leaves the position
+    /// wherever the error occurred, so restoring is needed to retry from the
+    /// start of the attempt.
+    ///
+    /// ```rust
+    /// use arjp::{JsonParser, JsonValue};
+    ///
+    /// let mut parser = JsonParser::new("[1, oops] 2");
+    /// let checkpoint = parser.checkpoint();
+    /// assert!(parser.parse_one().is_err());
+    /// parser.restore(checkpoint);
+    /// assert_eq!(parser.remaining(), "[1, oops] 2");
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            position: self.position,
+        }
+    }
+
+    /// Returns to a position saved by [`JsonParser::checkpoint`], so that
+    /// `position()` and `remaining()` are as they were then. Options are
+    /// unaffected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint lies beyond the end of the input, which can
+    /// only happen if it was taken from a parser over a longer input.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        assert!(
+            checkpoint.position <= self.input.len(),
+            "checkpoint is beyond the end of the input"
+        );
+        self.position = checkpoint.position;
+    }
+
+    /// Parses a single value and stops right after it, without requiring the
+    /// rest of the input to be empty.
+    ///
+    /// Leading whitespace is skipped; whitespace after the value is left
+    /// unconsumed. Calling this repeatedly reads a stream of concatenated or
+    /// whitespace-separated values, with `position()` marking where each one
+    /// ended.
+    pub fn parse_one(&mut self) -> Result<JsonValue> {
+        self.begin();
+        let result = self
+            .check_root()
+            .and_then(|()| self.parse_value())
+            .and_then(|value| self.check_budget().map(|()| value));
+        if result.is_ok() {
+            self.value_end = self.position;
+        }
+        self.locate(result.map_err(|err| err.with_path(String::from("$"))))
+    }
+
     /// Parses the input JSON string into a JsonValue.
     pub fn parse(&mut self) -> Result<JsonValue> {
-        self.skip_whitespace();
-        let result = self.parse_value()?;
-        self.skip_whitespace();
-        if !self.remaining.is_empty() {
-            return Err(ParseError::new("Extra characters after JSON value"));
-        }
+        let result = self.parse_one()?;
+        let end = self.expect_end();
+        self.locate(end.map_err(|err| err.with_path(String::from("$"))))?;
         Ok(result)
     }
 
+    /// Parses the input into a `BorrowedValue` whose strings borrow from the
+    /// input wherever they contain no escape sequences.
+    ///
+    /// This avoids an allocation per string for read-heavy workloads. All
+    /// parser options apply as they do to [`JsonParser::parse`].
+    pub fn parse_borrowed(&mut self) -> Result<BorrowedValue<'a>> {
+        self.begin();
+        let result = self
+            .check_root()
+            .and_then(|()| self.parse_tree(&mut BorrowedTree))
+            .and_then(|value| self.expect_end().map(|()| value));
+        self.locate(result)
+    }
+
+    /// Parses the input into a `SpannedValue` tree recording the byte range
+    /// of every value and object key.
+    ///
+    /// Objects keep their members in source order, including repeated keys.
+    /// All parser options apply as they do to [`JsonParser::parse`].
+    pub fn parse_with_spans(&mut self) -> Result<SpannedValue> {
+        self.begin();
+        let result = self
+            .check_root()
+            .and_then(|()| self.parse_tree(&mut SpannedTree))
+            .and_then(|value| self.expect_end().map(|()| value));
+        self.locate(result)
+    }
+
+    /// Parses the input like [`JsonParser::parse`], but fully builds only
+    /// the values at or below the given JSON Pointers, keeping everything
+    /// else as a [`JsonValue::Raw`] holding its exact source text.
+    ///
+    /// Arrays and objects on the way to a selected value are parsed so they
+    /// can be descended into, and their other members become raw. Raw values
+    /// are still checked for syntax, but nothing is allocated for their
+    /// contents, which makes picking a few fields out of a large document
+    /// cheaper. Their text is sliced straight from the input, including any
+    /// comments or other lenient constructs inside them, so compact
+    /// serialization forwards each one byte for byte. `reject_duplicate_keys` does
+    /// not look inside raw values. The empty pointer selects the whole
+    /// document; malformed pointers select nothing.
+    ///
+    /// ```rust
+    /// use arjp::{JsonParser, JsonValue};
+    ///
+    /// let input = r#"{"id": 7, "payload": {"big": [1, 2, 3]}}"#;
+    /// let value = JsonParser::new(input).parse_partial(&["/id"]).unwrap();
+    /// assert_eq!(value["id"], JsonValue::Integer(7));
+    /// assert_eq!(value["payload"], JsonValue::Raw(r#"{"big": [1, 2, 3]}"#.to_string()));
+    /// ```
+    pub fn parse_partial(&mut self, pointers: &[&str]) -> Result<JsonValue> {
+        let selected: Vec<Vec<String>> = pointers
+            .iter()
+            .filter_map(|pointer| Some(pointer_tokens(pointer)?.collect()))
+            .collect();
+        self.begin();
+        let result = self
+            .check_root()
+            .and_then(|()| {
+                self.parse_tree(&mut PartialTree {
+                    selected: &selected,
+                    path: Vec::new(),
+                })
+            })
+            .and_then(|value| self.expect_end().map(|()| value));
+        self.locate(result)
+    }
+
+    /// Parses the input like [`JsonParser::parse`], also counting the
+    /// objects, arrays, strings and numbers it contains and its nesting
+    /// depth.
+    ///
+    /// The counts are collected in the same pass, at the cost of a few
+    /// increments per value.
+    ///
+    /// ```rust
+    /// use arjp::JsonParser;
+    ///
+    /// let (_, stats) = JsonParser::new(r#"{"ids": [1, 2]}"#).parse_with_stats().unwrap();
+    /// assert_eq!(stats.number_count, 2);
+    /// assert_eq!(stats.max_depth, 2);
+    /// ```
+    pub fn parse_with_stats(&mut self) -> Result<(JsonValue, ParseStats)> {
+        let start = self.position + self.bom_len();
+        self.stats = Some(ParseStats::default());
+        let result = self.parse();
+        let mut stats = self.stats.take().unwrap_or_default();
+        stats.total_bytes = self.position - start;
+        Ok((result?, stats))
+    }
+
+    /// Parses the input like [`JsonParser::parse`], also reporting every
+    /// non-standard construct that a lenient option let through, in input
+    /// order.
+    ///
+    /// This lets tooling accept relaxed input while still warning about it.
+    /// The list is empty if the document is strict JSON.
+    ///
+    /// ```rust
+    /// use arjp::{JsonParser, Leniency};
+    ///
+    /// let mut parser = JsonParser::new("[1, 2,]").with_trailing_commas(true);
+    /// let (_, report) = parser.parse_with_report().unwrap();
+    /// assert_eq!(report, vec![Leniency::TrailingComma(5)]);
+    /// ```
+    pub fn parse_with_report(&mut self) -> Result<(JsonValue, Vec<Leniency>)> {
+        self.leniencies = Some(Vec::new());
+        let result = self.parse();
+        let report = self.leniencies.take().unwrap_or_default();
+        Ok((result?, report))
+    }
+
+    /// Records a lenient construct if `parse_with_report` is collecting them.
+    #[inline]
+    fn note(&mut self, leniency: Leniency) {
+        if let Some(leniencies) = &mut self.leniencies {
+            leniencies.push(leniency);
+        }
+    }
+
+    /// Records each control character in the string bytes from `start` up
+    /// to the current position, if `parse_with_report` is collecting them.
+    fn note_control_characters(&mut self, start: usize) {
+        if let Some(leniencies) = &mut self.leniencies {
+            let bytes = &self.input[start..self.position];
+            leniencies.extend(
+                (start..)
+                    .zip(bytes)
+                    .filter(|&(_, &b)| b < 0x20)
+                    .map(|(offset, _)| Leniency::ControlCharacter(offset)),
+            );
+        }
+    }
+
+    /// Parses the input like [`JsonParser::parse`], passing the text of
+    /// every number to `handler` and storing the value it returns instead.
+    ///
+    /// This plugs in number types the crate does not depend on, such as
+    /// `i128` or a big-integer library, with the result kept in whichever
+    /// variant suits it: a `String`, a `RawNumber`, or a `Number` for values
+    /// that fit. The text is a valid JSON number, exactly as written except
+    /// that JSON5 forms arrive rewritten as plain JSON (`0xFF` as `255`).
+    /// Numbers too large for an `f64` reach the handler too, while the `NaN`
+    /// and `Infinity` tokens do not. `with_arbitrary_precision` and
+    /// `with_decimal_numbers` have no effect here.
+    ///
+    /// ```rust
+    /// use arjp::{JsonParser, JsonValue};
+    ///
+    /// let mut parser = JsonParser::new("[1, 170141183460469231731687303715884105727]");
+    /// let value = parser
+    ///     .parse_with_number_handler(|text| match text.parse::<i128>() {
+    ///         Ok(n) => match i64::try_from(n) {
+    ///             Ok(n) => JsonValue::Integer(n),
+    ///             Err(_) => JsonValue::String(n.to_string()),
+    ///         },
+    ///         Err(_) => JsonValue::RawNumber(text.to_string()),
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(value[0], JsonValue::Integer(1));
+    /// assert_eq!(value[1].as_str(), Some("170141183460469231731687303715884105727"));
+    /// ```
+    pub fn parse_with_number_handler<F: Fn(&str) -> JsonValue>(
+        &mut self,
+        handler: F,
+    ) -> Result<JsonValue> {
+        // Numbers are first kept as their text, then handed over in one pass
+        // over the finished tree.
+        let arbitrary_precision = core::mem::replace(&mut self.arbitrary_precision, true);
+        let result = self.parse();
+        self.arbitrary_precision = arbitrary_precision;
+        let mut value = result?;
+        let mut pending = vec![&mut value];
+        while let Some(value) = pending.pop() {
+            match value {
+                JsonValue::RawNumber(text) => *value = handler(text),
+                JsonValue::Array(values) => pending.extend(values.iter_mut()),
+                JsonValue::Object(map) => pending.extend(map.values_mut()),
+                _ => {}
+            }
+        }
+        Ok(value)
+    }
+
+    /// Parses the input like [`JsonParser::parse`] into `scratch`, reusing
+    /// the arrays and objects of the value it already holds.
+    ///
+    /// The previous value is taken apart, and its emptied `Vec`s, maps,
+    /// strings and object keys are handed out again to the new one, outer
+    /// containers first. A loop parsing many similarly shaped messages into
+    /// the same value therefore allocates little after the first message.
+    /// Anything beyond what was recycled is allocated as usual, and recycled
+    /// allocations left over are freed. A recycled allocation keeps its
+    /// capacity, even where the new value needs less. On error `scratch` is
+    /// left as `Null`.
+    ///
+    /// ```rust
+    /// use arjp::{JsonParser, JsonValue};
+    ///
+    /// let mut scratch = JsonValue::Null;
+    /// for message in [r#"{"id": 1}"#, r#"{"id": 2}"#] {
+    ///     JsonParser::new(message).parse_into(&mut scratch).unwrap();
+    ///     assert!(scratch["id"].as_i64().is_some());
+    /// }
+    /// ```
+    pub fn parse_into(&mut self, scratch: &mut JsonValue) -> Result<()> {
+        let mut recycled = Recycled::default();
+        recycled.take_apart(core::mem::replace(scratch, JsonValue::Null));
+        self.recycled = Some(recycled);
+        let result = self.parse();
+        self.recycled = None;
+        *scratch = result?;
+        Ok(())
+    }
+
+    /// Scans the input as a stream of events instead of building a tree.
+    ///
+    /// Only the innermost scalar is ever materialized, so memory use depends
+    /// on nesting depth rather than document size. All parser options apply
+    /// as they do to [`JsonParser::parse`]. After the last event the input
+    /// must be exhausted; trailing data or any syntax error is yielded as a
+    /// final `Err`, after which the iterator returns `None`.
+    ///
+    /// ```rust
+    /// use arjp::{JsonEvent, JsonParser};
+    ///
+    /// let mut parser = JsonParser::new(r#"{"ids": [1, 2]}"#);
+    /// let ids = parser
+    ///     .events()
+    ///     .filter(|event| matches!(event, Ok(JsonEvent::Value(_))))
+    ///     .count();
+    /// assert_eq!(ids, 2);
+    /// ```
+    pub fn events(&mut self) -> Events<'_, 'a> {
+        self.begin();
+        Events {
+            parser: self,
+            stack: Vec::new(),
+            state: EventState::Value,
+        }
+    }
+
+    /// Parses a top-level array one element at a time, without holding the
+    /// whole array in memory.
+    ///
+    /// Fails straight away unless the input starts with `[`. The iterator
+    /// then yields each element as it is parsed, and after the closing `]`
+    /// checks that only whitespace follows. A syntax error, in an element or
+    /// between two, is yielded as a final `Err`, after which the iterator
+    /// returns `None`. All parser options apply, with each element one
+    /// level deep; `max_total_values` counts across the whole array.
+    ///
+    /// ```rust
+    /// use arjp::JsonParser;
+    ///
+    /// let mut parser = JsonParser::new(r#"[{"id": 1}, {"id": 2}]"#);
+    /// let mut total = 0;
+    /// for record in parser.array_elements().unwrap() {
+    ///     total += record.unwrap()["id"].as_i64().unwrap();
+    /// }
+    /// assert_eq!(total, 3);
+    /// ```
+    pub fn array_elements(&mut self) -> Result<ArrayElements<'_, 'a>> {
+        self.begin();
+        self.skip_whitespace()?;
+        let result = match self.peek_char() {
+            Some(('[', _)) => self.count_value(),
+            Some((c, _)) => Err(self.error(
+                ParseErrorKind::UnexpectedChar,
+                &format!("Expected '[' at the start of the array, got '{}'", c),
+            )),
+            None => Err(self.error(ParseErrorKind::UnexpectedEof, "Unexpected end of input")),
+        };
+        self.locate(result)?;
+        self.next_char();
+        Ok(ArrayElements {
+            parser: self,
+            state: ElementState::First,
+        })
+    }
+
+    /// Parses the input, recovering from errors to report as many problems
+    /// as possible in one pass.
+    ///
+    /// After an error the parser skips ahead to the next comma or closing
+    /// bracket or brace at the same nesting level and carries on. Array
+    /// elements and object values that could not be parsed become `Null`;
+    /// object members whose key could not be parsed are dropped. The
+    /// returned value is `None` only if nothing usable was found at the top
+    /// level. Errors are returned in input order, and the list is empty
+    /// exactly when [`JsonParser::parse`] would have succeeded.
+    pub fn parse_recovering(&mut self) -> (Option<JsonValue>, Vec<ParseError>) {
+        self.begin();
+        if let Err(err) = self.check_root() {
+            return (None, vec![err.locate(self.input)]);
+        }
+        let mut errors = Vec::new();
+        let value = self.recover_value(&mut errors);
+        if let Err(err) = self.expect_end() {
+            errors.push(err);
+        }
+        let errors = errors
+            .into_iter()
+            .map(|err| err.locate(self.input))
+            .collect();
+        (value, errors)
+    }
+
+    /// Prepares to parse a new top-level value: resets the value count and,
+    /// at the start of the input, skips a byte order mark if allowed.
+    fn begin(&mut self) {
+        self.value_count = 0;
+        self.budget_start = self.position;
+        if self.bom_len() > 0 {
+            self.position = UTF8_BOM.len();
+            self.note(Leniency::ByteOrderMark(0));
+        }
+    }
+
+    /// Returns the length of the byte order mark `begin` would skip at the
+    /// current position: the mark itself at the start of the input if
+    /// allowed, and 0 otherwise.
+    fn bom_len(&self) -> usize {
+        if self.position == 0 && self.allow_bom && self.input.starts_with(UTF8_BOM) {
+            UTF8_BOM.len()
+        } else {
+            0
+        }
+    }
+
+    /// Skips whitespace before the top-level value, failing if it is a
+    /// scalar and `require_top_level_aggregate` is set.
+    fn check_root(&mut self) -> Result<()> {
+        self.skip_whitespace()?;
+        match self.peek_char() {
+            Some((c, _)) if self.require_top_level_aggregate && c != '[' && c != '{' => Err(self
+                .error(
+                    ParseErrorKind::UnexpectedChar,
+                    &format!("Expected object or array at the top level, got '{}'", c),
+                )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails unless only whitespace remains after the top-level value. The
+    /// error points at the first extra character and names it.
+    fn expect_end(&mut self) -> Result<()> {
+        self.check_budget()?;
+        self.value_end = self.position;
+        self.skip_whitespace()?;
+        if let Some((c, _)) = self.peek_char() {
+            return Err(self.error(
+                ParseErrorKind::TrailingData,
+                &format!("Extra characters after JSON value, starting with '{}'", c),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Counts one more value towards `max_total_values` and `step_budget`.
+    fn count_value(&mut self) -> Result<()> {
+        self.value_count += 1;
+        match self.max_total_values {
+            Some(max) if self.value_count > max => Err(self.error(
+                ParseErrorKind::ValueCountLimitExceeded,
+                &format!("Document contains more than {} values", max),
+            )),
+            _ => self.check_budget(),
+        }
+    }
+
+    /// Fails if the values produced and bytes consumed so far exceed
+    /// `step_budget`.
+    fn check_budget(&self) -> Result<()> {
+        match self.step_budget {
+            Some(budget) if self.value_count + (self.position - self.budget_start) > budget => {
+                Err(self.error(
+                    ParseErrorKind::BudgetExceeded,
+                    &format!("Parsing exceeded the budget of {} steps", budget),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Parses any JSON value (null, boolean, string, number, array, or object).
     fn parse_value(&mut self) -> Result<JsonValue> {
-        self.skip_whitespace();
-        match self.remaining.chars().next() {
-            Some('n') => self.parse_null(),
-            Some('t') | Some('f') => self.parse_boolean(),
-            Some('"') => self.parse_string(),
-            Some('[') => self.parse_array(),
-            Some('{') => self.parse_object(),
-            Some('0'..='9') | Some('-') => self.parse_number(),
-            Some(_) => Err(ParseError::new("Unexpected character")),
-            None => Err(ParseError::new("Unexpected end of input")),
+        self.skip_whitespace()?;
+        self.count_value()?;
+        match self.peek() {
+            Some(b'n') => self.parse_null(),
+            Some(b't') | Some(b'f') => self.parse_boolean(),
+            Some(b'N') if self.case_insensitive_literals && !self.rest().starts_with(b"NaN") => {
+                self.parse_null()
+            }
+            Some(b'T') | Some(b'F') if self.case_insensitive_literals => self.parse_boolean(),
+            Some(b) if self.is_quote(b) => self.parse_string(),
+            Some(b'[' | b'{') => self.parse_container(),
+            Some(b'N') | Some(b'I') if self.allow_non_finite_numbers => self.parse_non_finite(),
+            Some(b'-') if self.allow_non_finite_numbers && self.rest().starts_with(b"-I") => {
+                self.parse_non_finite()
+            }
+            Some(b'0'..=b'9') | Some(b'-') => self.parse_number(),
+            Some(b'+') | Some(b'.') if self.allow_json5_numbers => self.parse_number(),
+            Some(b'+') | Some(b'.') => Err(self.error(
+                ParseErrorKind::InvalidNumber,
+                "Numbers must start with a digit or '-'",
+            )),
+            Some(_) => {
+                let c = self
+                    .peek_char()
+                    .map_or(char::REPLACEMENT_CHARACTER, |(c, _)| c);
+                Err(self.error(
+                    ParseErrorKind::UnexpectedChar,
+                    &format!("Expected value, got '{}'", c),
+                ))
+            }
+            None => Err(self.error(ParseErrorKind::UnexpectedEof, "Unexpected end of input")),
         }
+        .inspect(|value| {
+            if let Some(stats) = &mut self.stats {
+                match value {
+                    JsonValue::Null
+                    | JsonValue::Boolean(_)
+                    | JsonValue::Array(_)
+                    | JsonValue::Object(_) => {}
+                    JsonValue::String(_) => stats.string_count += 1,
+                    _ => stats.number_count += 1,
+                }
+            }
+        })
     }
 
-    /// Parses the JSON null value.
-    fn parse_null(&mut self) -> Result<JsonValue> {
-        if self.remaining.starts_with("null") {
-            self.position += 4;
-            self.remaining = &self.input[self.position..];
-            Ok(JsonValue::Null)
+    /// Runs a parser one nesting level deeper, enforcing `max_depth`.
+    fn parse_nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.descend()?;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Checks that one more level of nesting is within `max_depth` and
+    /// enters it.
+    fn descend(&mut self) -> Result<()> {
+        if self.depth >= self.max_depth {
+            return Err(self.error(
+                ParseErrorKind::DepthLimitExceeded,
+                &format!("Nesting depth exceeds the limit of {}", self.max_depth),
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Consumes `literal` and returns `value`, or fails without consuming
+    /// anything.
+    ///
+    /// ASCII case is ignored when `case_insensitive_literals` is set. Input
+    /// that ends partway through the literal, such as `nul`, fails with
+    /// `ParseErrorKind::UnexpectedEof`; any other mismatch with
+    /// `ParseErrorKind::InvalidLiteral`.
+    fn consume_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue> {
+        let rest = self.rest();
+        let next = &rest[..rest.len().min(literal.len())];
+        let expected = &literal.as_bytes()[..next.len()];
+        let matches = if self.case_insensitive_literals {
+            next.eq_ignore_ascii_case(expected)
         } else {
-            Err(ParseError::new("Invalid null value"))
+            next == expected
+        };
+        if !matches {
+            return Err(self.error(
+                ParseErrorKind::InvalidLiteral,
+                &format!("Invalid {} value", literal),
+            ));
+        }
+        if next.len() < literal.len() {
+            return Err(self.error(
+                ParseErrorKind::UnexpectedEof,
+                &format!("Unexpected end of input in {} value", literal),
+            ));
         }
+        if next != literal.as_bytes() {
+            self.note(Leniency::CaseInsensitiveLiteral(self.position));
+        }
+        self.position += literal.len();
+        Ok(value)
+    }
+
+    /// Parses the JSON null value.
+    fn parse_null(&mut self) -> Result<JsonValue> {
+        self.consume_literal("null", JsonValue::Null)
     }
 
     /// Parses a JSON boolean value (true or false).
     fn parse_boolean(&mut self) -> Result<JsonValue> {
-        match self.remaining {
-            s if s.starts_with("true") => {
-                self.position += 4;
-                self.remaining = &self.input[self.position..];
-                Ok(JsonValue::Boolean(true))
+        match self.peek() {
+            Some(b't' | b'T') => self.consume_literal("true", JsonValue::Boolean(true)),
+            _ => self.consume_literal("false", JsonValue::Boolean(false)),
+        }
+    }
+
+    /// Parses one of the `NaN`, `Infinity` or `-Infinity` tokens.
+    fn parse_non_finite(&mut self) -> Result<JsonValue> {
+        for (literal, value) in [
+            ("NaN", f64::NAN),
+            ("Infinity", f64::INFINITY),
+            ("-Infinity", f64::NEG_INFINITY),
+        ] {
+            if self.rest().starts_with(literal.as_bytes()) {
+                self.note(Leniency::NonFiniteNumber(self.position));
+                self.position += literal.len();
+                return Ok(JsonValue::Number(value));
             }
-            s if s.starts_with("false") => {
-                self.position += 5;
-                self.remaining = &self.input[self.position..];
-                Ok(JsonValue::Boolean(false))
+            if literal.as_bytes().starts_with(self.rest()) {
+                return Err(self.error(
+                    ParseErrorKind::UnexpectedEof,
+                    &format!("Unexpected end of input in {} value", literal),
+                ));
             }
-            _ => Err(ParseError::new("Invalid boolean value")),
         }
+        Err(self.error(ParseErrorKind::InvalidLiteral, "Invalid non-finite number"))
     }
 
-    /// Parses a JSON string with optimized character handling.
+    /// Parses a JSON string value.
     fn parse_string(&mut self) -> Result<JsonValue> {
+        self.parse_str().map(JsonValue::String)
+    }
+
+    /// Parses a string literal with optimized character handling.
+    ///
+    /// A string without escapes is copied out of the input in one step,
+    /// allocated at its exact length. Otherwise runs of unescaped bytes are
+    /// copied into the result in one step each. The string ends at the same
+    /// kind of quote it started with.
+    fn parse_str(&mut self) -> Result<String> {
+        if let Some(s) = self.parse_plain_str()? {
+            return Ok(self.own_str(s));
+        }
+        let quote = self.position;
+        let close = self.input[quote];
+        if close == b'\'' {
+            self.note(Leniency::SingleQuotedString(quote));
+        }
         self.next_char(); // Skip opening quote
         let mut result = String::with_capacity(16);
+        let mut start = self.position;
 
-        while let Some(c) = self.remaining.chars().next() {
-            match c {
-                '"' => {
+        while let Some(b) = self.peek() {
+            match b {
+                b if b == close => {
+                    self.push_segment(&mut result, start)?;
+                    self.check_string_length(&result, quote)?;
                     self.next_char();
-                    return Ok(JsonValue::String(result));
+                    return Ok(result);
                 }
-                '\\' => {
+                b'\\' => {
+                    self.push_segment(&mut result, start)?;
+                    self.check_string_length(&result, quote)?;
+                    let escape = self.position;
                     self.next_char();
                     match self.next_char() {
                         Some('"') => result.push('"'),
+                        Some('\'') if self.allow_single_quotes => {
+                            self.note(Leniency::EscapedSingleQuote(escape));
+                            result.push('\'')
+                        }
                         Some('\\') => result.push('\\'),
                         Some('/') => result.push('/'),
                         Some('b') => result.push('\u{0008}'),
@@ -117,248 +1290,2592 @@ impl<'a> JsonParser<'a> {
                         Some('n') => result.push('\n'),
                         Some('r') => result.push('\r'),
                         Some('t') => result.push('\t'),
-                        Some('u') => {
-                            let mut code = 0u16;
-                            for i in (0..4).rev() {
-                                let c = self
-                                    .next_char()
-                                    .ok_or(ParseError::new("Incomplete unicode escape sequence"))?;
-                                if let Some(digit) = c.to_digit(16) {
-                                    code |= (digit as u16) << (i * 4);
-                                } else {
-                                    return Err(ParseError::new("Invalid unicode escape sequence"));
-                                }
-                            }
-                            result.push(char::from_u32(code as u32).ok_or_else(|| {
-                                ParseError::new(&format!("Invalid unicode code point: {}", code))
-                            })?);
-                        }
+                        Some('u') => result.push(self.parse_unicode_escape()?),
                         Some(c) => {
-                            return Err(ParseError::new(&format!(
-                                "Invalid escape sequence: \\{}",
-                                c
-                            )))
+                            return Err(self.error(
+                                ParseErrorKind::InvalidEscape,
+                                &format!("Invalid escape sequence: \\{}", c),
+                            ))
+                        }
+                        None => {
+                            return Err(self.error(
+                                ParseErrorKind::UnterminatedString,
+                                "Unterminated string after escape",
+                            ))
                         }
-                        None => return Err(ParseError::new("Unterminated string after escape")),
                     }
+                    start = self.position;
+                }
+                b if self.is_string_special(b, close) => {
+                    return Err(self.error(
+                        ParseErrorKind::InvalidControlChar,
+                        &format!("Unescaped control character U+{:04X} in string", b),
+                    ))
                 }
                 _ => {
-                    result.push(c);
-                    self.next_char();
+                    // Skip the whole run of plain bytes up to the next quote,
+                    // backslash or rejected control character at once.
+                    let rest = self.rest();
+                    let run = self.position;
+                    self.position += rest
+                        .iter()
+                        .position(|&b| self.is_string_special(b, close))
+                        .unwrap_or(rest.len());
+                    self.note_control_characters(run);
                 }
             }
         }
-        Err(ParseError::new("Unterminated string"))
+        Err(self.error(ParseErrorKind::UnterminatedString, "Unterminated string"))
     }
 
-    /// Parses a JSON number with optimized string construction.
-    fn parse_number(&mut self) -> Result<JsonValue> {
-        let mut num_str = String::with_capacity(16);
-        let mut has_digits = false;
-
-        if self.remaining.starts_with('-') {
-            num_str.push('-');
-            self.next_char();
-        }
-        while let Some(c) = self.remaining.chars().next() {
-            if c.is_ascii_digit() {
-                num_str.push(c);
-                has_digits = true;
-                self.next_char();
-            } else {
-                break;
+    /// Copies `s` into a string recycled by `parse_into`, or else a new one
+    /// allocated at its exact length.
+    fn own_str(&mut self, s: &str) -> String {
+        match self.recycled.as_mut().and_then(|r| r.strings.pop()) {
+            Some(mut owned) => {
+                owned.clear();
+                owned.push_str(s);
+                owned
             }
+            None => String::from(s),
         }
-        if !has_digits {
-            return Err(ParseError::new("Number must contain at least one digit"));
+    }
+
+    /// Parses a string literal, borrowing it from the input when it contains
+    /// no escape sequences.
+    fn parse_str_borrowed(&mut self) -> Result<Cow<'a, str>> {
+        match self.parse_plain_str()? {
+            Some(s) => Ok(Cow::Borrowed(s)),
+            None => self.parse_str().map(Cow::Owned),
         }
-        if self.remaining.starts_with('.') {
-            num_str.push('.');
-            self.next_char();
-            has_digits = false;
-            while let Some(c) = self.remaining.chars().next() {
-                if c.is_ascii_digit() {
-                    num_str.push(c);
-                    has_digits = true;
-                    self.next_char();
-                } else {
-                    break;
+    }
+
+    /// Parses a string literal that contains no escape sequences or rejected
+    /// control characters, returning it as a slice of the input. Returns
+    /// `None` without consuming anything for any other string.
+    fn parse_plain_str(&mut self) -> Result<Option<&'a str>> {
+        let quote = self.position;
+        let close = self.input[quote];
+        let rest = &self.input[quote + 1..];
+        match rest.iter().position(|&b| self.is_string_special(b, close)) {
+            Some(len) if rest[len] == close => {
+                let start = quote + 1;
+                self.position = start + len;
+                let s = match self.slice_str(start) {
+                    Err(_) if self.utf8_lossy => {
+                        // Leave the replacement to `parse_str`.
+                        self.position = quote;
+                        return Ok(None);
+                    }
+                    result => result?,
+                };
+                self.check_string_length(s, quote)?;
+                if close == b'\'' {
+                    self.note(Leniency::SingleQuotedString(quote));
                 }
+                self.note_control_characters(start);
+                self.position += 1;
+                Ok(Some(s))
             }
-            if !has_digits {
-                return Err(ParseError::new(
-                    "Decimal point must be followed by at least one digit",
-                ));
-            }
+            _ => Ok(None),
         }
-        if self.remaining.starts_with('e') || self.remaining.starts_with('E') {
-            num_str.push(self.remaining.chars().next().unwrap());
-            self.next_char();
-            if self.remaining.starts_with('+') || self.remaining.starts_with('-') {
-                num_str.push(self.remaining.chars().next().unwrap());
-                self.next_char();
-            }
-            has_digits = false;
-            while let Some(c) = self.remaining.chars().next() {
-                if c.is_ascii_digit() {
-                    num_str.push(c);
-                    has_digits = true;
-                    self.next_char();
-                } else {
-                    break;
-                }
-            }
-            if !has_digits {
-                return Err(ParseError::new(
-                    "Exponent must be followed by at least one digit",
-                ));
-            }
+    }
+
+    /// Fails if a string that started at `quote` has grown past
+    /// `max_string_length`.
+    fn check_string_length(&self, result: &str, quote: usize) -> Result<()> {
+        match self.max_string_length {
+            Some(max) if result.len() > max => Err(ParseError::new(
+                ParseErrorKind::StringLengthLimitExceeded,
+                &format!("String exceeds the limit of {} bytes", max),
+            )
+            .with_offset(quote)),
+            _ => Ok(()),
         }
-        num_str
-            .parse::<f64>()
-            .map(JsonValue::Number)
-            .map_err(|e| ParseError::new(&format!("Invalid number: {}", e)))
     }
 
-    /// Parses a JSON array with pre-allocated capacity.
-    fn parse_array(&mut self) -> Result<JsonValue> {
-        self.next_char();
-        self.skip_whitespace();
-        let mut values = Vec::with_capacity(4);
+    /// Appends the raw string bytes from `start` up to the current position,
+    /// replacing invalid UTF-8 if `utf8_lossy` is set.
+    fn push_segment(&self, out: &mut String, start: usize) -> Result<()> {
+        out.push_str(&self.slice_text(start)?);
+        Ok(())
+    }
 
-        if self.remaining.starts_with(']') {
-            self.next_char();
-            return Ok(JsonValue::Array(values));
+    /// Like `slice_str`, but replaces invalid UTF-8 with U+FFFD instead of
+    /// failing if `utf8_lossy` is set.
+    fn slice_text(&self, start: usize) -> Result<Cow<'a, str>> {
+        match self.slice_str(start) {
+            Err(_) if self.utf8_lossy => {
+                Ok(String::from_utf8_lossy(&self.input[start..self.position]))
+            }
+            result => result.map(Cow::Borrowed),
         }
+    }
 
-        loop {
-            values.push(self.parse_value()?);
-            self.skip_whitespace();
-            match self.remaining.chars().next() {
-                Some(']') => {
-                    self.next_char();
-                    return Ok(JsonValue::Array(values));
-                }
-                Some(',') => {
-                    self.next_char();
-                    self.skip_whitespace();
-                }
-                Some(c) => {
-                    return Err(ParseError::new(&format!(
-                        "Expected comma or closing bracket, got '{}'",
-                        c
-                    )))
-                }
-                None => return Err(ParseError::new("Unterminated array")),
-            }
+    /// Returns the raw string bytes from `start` up to the current position,
+    /// validating them as UTF-8 unless the input is already known to be text.
+    fn slice_str(&self, start: usize) -> Result<&'a str> {
+        match self.text {
+            Some(text) => Ok(&text[start..self.position]),
+            None => core::str::from_utf8(&self.input[start..self.position]).map_err(|e| {
+                ParseError::new(ParseErrorKind::InvalidUtf8, "Invalid UTF-8 in string")
+                    .with_offset(start + e.valid_up_to())
+            }),
         }
     }
 
-    /// Parses a JSON object with pre-allocated capacity.
-    fn parse_object(&mut self) -> Result<JsonValue> {
-        self.next_char();
-        self.skip_whitespace();
-        let mut map = HashMap::with_capacity(4);
+    /// Reads the four hex digits of a `\uXXXX` escape.
+    fn parse_hex4(&mut self) -> Result<u16> {
+        let mut code = 0u16;
+        for i in (0..4).rev() {
+            let c = self.next_char().ok_or_else(|| {
+                self.error(
+                    ParseErrorKind::UnexpectedEof,
+                    "Incomplete unicode escape sequence",
+                )
+            })?;
+            if let Some(digit) = c.to_digit(16) {
+                code |= (digit as u16) << (i * 4);
+            } else {
+                return Err(self.error(
+                    ParseErrorKind::InvalidEscape,
+                    "Invalid unicode escape sequence",
+                ));
+            }
+        }
+        Ok(code)
+    }
+
+    /// Decodes a `\uXXXX` escape whose `\u` has already been consumed.
+    ///
+    /// A high surrogate must be immediately followed by a `\uXXXX` low
+    /// surrogate; the pair is combined into a single astral-plane character.
+    fn parse_unicode_escape(&mut self) -> Result<char> {
+        let code = self.parse_hex4()? as u32;
+        let code = match code {
+            0xD800..=0xDBFF => {
+                if !self.rest().starts_with(b"\\u") {
+                    let kind = if b"\\u".starts_with(self.rest()) {
+                        ParseErrorKind::UnexpectedEof
+                    } else {
+                        ParseErrorKind::InvalidUnicode
+                    };
+                    return Err(
+                        self.error(kind, &format!("Unpaired high surrogate: \\u{:04X}", code))
+                    );
+                }
+                self.next_char();
+                self.next_char();
+                let low = self.parse_hex4()? as u32;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error(
+                        ParseErrorKind::InvalidUnicode,
+                        &format!(
+                            "Invalid low surrogate \\u{:04X} after high surrogate \\u{:04X}",
+                            low, code
+                        ),
+                    ));
+                }
+                0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => {
+                return Err(self.error(
+                    ParseErrorKind::InvalidUnicode,
+                    &format!("Unpaired low surrogate: \\u{:04X}", code),
+                ))
+            }
+            _ => code,
+        };
+        char::from_u32(code).ok_or_else(|| {
+            self.error(
+                ParseErrorKind::InvalidUnicode,
+                &format!("Invalid unicode code point: {}", code),
+            )
+        })
+    }
+
+    /// Parses a JSON number with optimized string construction.
+    ///
+    /// Literals without a fraction or exponent that fit in an `i64` become
+    /// `JsonValue::Integer`, and larger ones that fit in a `u64` become
+    /// `JsonValue::Unsigned`; everything else (including `-0`, to keep its
+    /// sign) becomes `JsonValue::Number`, or `JsonValue::Decimal` with
+    /// `decimal_numbers`. With `arbitrary_precision` every literal becomes
+    /// `JsonValue::RawNumber` instead.
+    ///
+    /// JSON5 forms are rewritten into `num_str` as plain JSON: the `+` sign
+    /// is dropped, a missing digit around the decimal point becomes `0` and
+    /// a hexadecimal integer is written in decimal.
+    fn parse_number(&mut self) -> Result<JsonValue> {
+        let start = self.position;
+        let mut num_str = String::with_capacity(16);
+        let mut is_integer = true;
+        let mut is_hex = false;
+        let mut json5 = false;
+
+        match self.peek() {
+            Some(b'-') => {
+                num_str.push('-');
+                self.next_char();
+            }
+            Some(b'+') if self.allow_json5_numbers => {
+                json5 = true;
+                self.next_char();
+            }
+            _ => {}
+        }
+        let has_integer_digits = matches!(self.peek(), Some(b'0'..=b'9'));
+        match self.peek() {
+            Some(b'0') => {
+                self.next_char();
+                if self.allow_json5_numbers && matches!(self.peek(), Some(b'x' | b'X')) {
+                    self.next_char();
+                    self.push_hex_digits(&mut num_str)?;
+                    is_hex = true;
+                    json5 = true;
+                } else {
+                    num_str.push('0');
+                    if matches!(self.peek(), Some(b'0'..=b'9')) {
+                        return Err(self.error(
+                            ParseErrorKind::InvalidNumber,
+                            "Leading zeros are not allowed",
+                        ));
+                    }
+                }
+            }
+            Some(b'1'..=b'9') => {
+                while let Some(b @ b'0'..=b'9') = self.peek() {
+                    num_str.push(b as char);
+                    self.next_char();
+                }
+            }
+            Some(b'.') if self.allow_json5_numbers => num_str.push('0'),
+            _ => {
+                return Err(self.error_unless_eof(
+                    ParseErrorKind::InvalidNumber,
+                    "Number must contain at least one digit",
+                ))
+            }
+        }
+        if !is_hex && self.peek() == Some(b'.') {
+            is_integer = false;
+            num_str.push('.');
+            self.next_char();
+            let mut has_digits = false;
+            while let Some(b) = self.peek() {
+                if b.is_ascii_digit() {
+                    num_str.push(b as char);
+                    has_digits = true;
+                    self.next_char();
+                } else {
+                    break;
+                }
+            }
+            if !has_digits {
+                if !(self.allow_json5_numbers && has_integer_digits) {
+                    return Err(self.error_unless_eof(
+                        ParseErrorKind::InvalidNumber,
+                        "Decimal point must be followed by at least one digit",
+                    ));
+                }
+                num_str.push('0');
+            }
+            json5 |= !has_digits || !has_integer_digits;
+        }
+        if let (false, Some(e @ (b'e' | b'E'))) = (is_hex, self.peek()) {
+            is_integer = false;
+            num_str.push(e as char);
+            self.next_char();
+            if let Some(sign @ (b'+' | b'-')) = self.peek() {
+                num_str.push(sign as char);
+                self.next_char();
+            }
+            let mut has_digits = false;
+            while let Some(b) = self.peek() {
+                if b.is_ascii_digit() {
+                    num_str.push(b as char);
+                    has_digits = true;
+                    self.next_char();
+                } else {
+                    break;
+                }
+            }
+            if !has_digits {
+                return Err(self.error_unless_eof(
+                    ParseErrorKind::InvalidNumber,
+                    "Exponent must be followed by at least one digit",
+                ));
+            }
+        }
+        if json5 {
+            self.note(Leniency::Json5Number(start));
+        }
+        self.number_span = Some(start..self.position);
+        if self.arbitrary_precision {
+            return Ok(JsonValue::RawNumber(num_str));
+        }
+        if is_integer && num_str != "-0" {
+            if let Ok(i) = num_str.parse::<i64>() {
+                return Ok(JsonValue::Integer(i));
+            }
+            if let Ok(u) = num_str.parse::<u64>() {
+                return Ok(JsonValue::Unsigned(u));
+            }
+        }
+        #[cfg(feature = "decimal")]
+        if self.decimal_numbers && !is_integer {
+            return parse_decimal(&num_str)
+                .map(JsonValue::Decimal)
+                .ok_or_else(|| {
+                    ParseError::new(
+                        ParseErrorKind::InvalidNumber,
+                        &format!(
+                            "Number {} cannot be represented exactly as a decimal",
+                            num_str
+                        ),
+                    )
+                    .with_offset(start)
+                });
+        }
+        let f = num_str.parse::<f64>().map_err(|e| {
+            self.error(
+                ParseErrorKind::InvalidNumber,
+                &format!("Invalid number: {}", e),
+            )
+        })?;
+        if f.is_infinite() {
+            if !self.allow_non_finite_numbers {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidNumber,
+                    &format!("Number {} is out of range", num_str),
+                )
+                .with_offset(start));
+            }
+            self.note(Leniency::NonFiniteNumber(start));
+        }
+        if f == 0.0 && self.reject_number_underflow && has_nonzero_mantissa(&num_str) {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                &format!("Number {} is too small and would round to zero", num_str),
+            )
+            .with_offset(start));
+        }
+        Ok(JsonValue::Number(f))
+    }
 
-        if self.remaining.starts_with('}') {
+    /// Consumes the digits of a JSON5 hexadecimal integer after its `0x`
+    /// prefix and appends its value to `num_str` in decimal.
+    fn push_hex_digits(&mut self, num_str: &mut String) -> Result<()> {
+        let start = self.position;
+        let digits = self.rest().iter().take_while(|b| b.is_ascii_hexdigit());
+        let mut value = 0u64;
+        let mut len = 0;
+        for &b in digits {
+            let digit = u64::from((b as char).to_digit(16).unwrap_or(0));
+            value = value
+                .checked_mul(16)
+                .and_then(|v| v.checked_add(digit))
+                .ok_or_else(|| {
+                    ParseError::new(
+                        ParseErrorKind::InvalidNumber,
+                        "Hexadecimal number is out of range",
+                    )
+                    .with_offset(start)
+                })?;
+            len += 1;
+        }
+        if len == 0 {
+            return Err(self.error_unless_eof(
+                ParseErrorKind::InvalidNumber,
+                "Hexadecimal number must contain at least one digit",
+            ));
+        }
+        self.position += len;
+        num_str.push_str(&value.to_string());
+        Ok(())
+    }
+
+    /// Consumes the opening bracket or brace of a container, returning
+    /// `true` if `close` immediately follows and the container is empty.
+    fn open_container(&mut self, close: u8) -> Result<bool> {
+        self.next_char();
+        self.skip_whitespace()?;
+        if self.peek() == Some(close) {
             self.next_char();
-            return Ok(JsonValue::Object(map));
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Consumes the comma after an array element or object member, or the
+    /// `close` character ending the container, returning `true` for the
+    /// latter. A trailing comma before `close` is accepted when enabled.
+    fn parse_separator(&mut self, close: u8) -> Result<bool> {
+        self.skip_whitespace()?;
+        let (closing, container) = if close == b']' {
+            ("bracket", "array")
+        } else {
+            ("brace", "object")
+        };
+        match self.peek_char() {
+            Some((c, _)) if c == close as char => {
+                self.next_char();
+                Ok(true)
+            }
+            Some((',', _)) => {
+                let comma = self.position;
+                self.next_char();
+                self.skip_whitespace()?;
+                if self.allow_trailing_commas && self.peek() == Some(close) {
+                    self.note(Leniency::TrailingComma(comma));
+                    self.next_char();
+                    return Ok(true);
+                }
+                Ok(false)
+            }
+            Some((c, _)) => Err(self.error(
+                ParseErrorKind::UnexpectedChar,
+                &format!("Expected comma or closing {}, got '{}'", closing, c),
+            )),
+            None => Err(self.error(
+                ParseErrorKind::UnexpectedEof,
+                &format!("Unterminated {}", container),
+            )),
+        }
+    }
+
+    /// Parses an object key and the colon after it, returning the key along
+    /// with the byte range it occupies.
+    fn parse_key(&mut self) -> Result<(Range<usize>, Cow<'a, str>)> {
+        self.skip_whitespace()?;
+        let start = self.position;
+        let key = match self.peek() {
+            Some(b) if self.is_quote(b) => self.parse_str_borrowed()?,
+            Some(b) if self.allow_unquoted_keys && is_identifier_start(b) => {
+                self.note(Leniency::UnquotedKey(start));
+                self.parse_identifier()?
+            }
+            Some(_) => {
+                return Err(self.error(ParseErrorKind::InvalidKey, "Object keys must be strings"))
+            }
+            None => return Err(self.error(ParseErrorKind::UnexpectedEof, "Unterminated object")),
+        };
+        let span = start..self.position;
+        self.skip_whitespace()?;
+        if self.peek() != Some(b':') {
+            return Err(self.error_unless_eof(
+                ParseErrorKind::UnexpectedChar,
+                "Expected colon after key in object",
+            ));
         }
+        self.next_char();
+        Ok((span, key))
+    }
+
+    /// Parses an unquoted object key, borrowing it from the input.
+    fn parse_identifier(&mut self) -> Result<Cow<'a, str>> {
+        let start = self.position;
+        self.position += self.rest()[1..]
+            .iter()
+            .take_while(|&&b| is_identifier_start(b) || b.is_ascii_digit())
+            .count()
+            + 1;
+        let key = self.slice_str(start)?;
+        self.check_string_length(key, start)?;
+        Ok(Cow::Borrowed(key))
+    }
 
+    /// Builds the error for a repeated object key starting at `offset`.
+    fn duplicate_key_error(&self, key: &str, offset: usize) -> ParseError {
+        ParseError::new(
+            ParseErrorKind::DuplicateKey,
+            &format!("Duplicate object key: {:?}", key),
+        )
+        .with_offset(offset)
+    }
+
+    /// Parses an array or object, including everything nested inside it.
+    ///
+    /// Containers still being filled are kept on an explicit stack rather
+    /// than the call stack, so nesting depth costs heap memory only.
+    fn parse_container(&mut self) -> Result<JsonValue> {
+        let mut stack = Vec::new();
+        let mut in_member = false;
+        self.parse_frames(&mut stack, &mut in_member)
+            .map_err(|err| err.with_path(error_path(&stack, in_member)))
+    }
+
+    /// Runs the container loop of `parse_container` over `stack`, keeping
+    /// `in_member` set while a member of the innermost container is being
+    /// parsed, so that a failure can be located by `error_path`.
+    fn parse_frames(&mut self, stack: &mut Vec<Frame>, in_member: &mut bool) -> Result<JsonValue> {
+        let mut complete = self.open_frame(stack)?;
         loop {
-            let key = match self.parse_value()? {
-                JsonValue::String(s) => s,
-                _ => return Err(ParseError::new("Object keys must be strings")),
+            let value = match complete.take() {
+                Some(value) => value,
+                None => {
+                    // Parse the next element or member of the innermost
+                    // container.
+                    if let Some(Frame::Object {
+                        key, key_offset, ..
+                    }) = stack.last_mut()
+                    {
+                        let (span, parsed) = self.parse_key()?;
+                        *key = match parsed {
+                            Cow::Borrowed(s) => self.own_str(s),
+                            Cow::Owned(s) => s,
+                        };
+                        *key_offset = span.start;
+                    }
+                    *in_member = true;
+                    self.skip_whitespace()?;
+                    if let Some(b'[' | b'{') = self.peek() {
+                        self.count_value()?;
+                        complete = self.open_frame(stack)?;
+                        *in_member = complete.is_some();
+                        continue;
+                    }
+                    self.parse_value()?
+                }
+            };
+            let Some(frame) = stack.last_mut() else {
+                return Ok(value);
             };
-            self.skip_whitespace();
-            if !self.remaining.starts_with(':') {
-                return Err(ParseError::new("Expected colon after key in object"));
+            let close = match frame {
+                Frame::Array(values) => {
+                    values.push(value);
+                    b']'
+                }
+                Frame::Object {
+                    map,
+                    key,
+                    key_offset,
+                } => {
+                    if self.reject_duplicate_keys && map.contains_key(key.as_str()) {
+                        return Err(self.duplicate_key_error(key, *key_offset));
+                    }
+                    map.insert(core::mem::take(key), value);
+                    b'}'
+                }
+            };
+            *in_member = false;
+            if self.parse_separator(close)? {
+                complete = stack.pop().map(Frame::into_value);
+                *in_member = true;
             }
-            self.next_char();
-            map.insert(key, self.parse_value()?);
-            self.skip_whitespace();
-            match self.remaining.chars().next() {
-                Some('}') => {
-                    self.next_char();
-                    return Ok(JsonValue::Object(map));
+        }
+    }
+
+    /// Consumes the opening character of a container and pushes a frame for
+    /// it, or returns the container right away if it is empty.
+    fn open_frame(&mut self, stack: &mut Vec<Frame>) -> Result<Option<JsonValue>> {
+        if self.depth + stack.len() >= self.max_depth {
+            return Err(self.error(
+                ParseErrorKind::DepthLimitExceeded,
+                &format!("Nesting depth exceeds the limit of {}", self.max_depth),
+            ));
+        }
+        let is_array = self.peek() == Some(b'[');
+        let recycled = self.recycled.as_mut();
+        let frame = if is_array {
+            Frame::Array(
+                recycled
+                    .and_then(|r| r.arrays.pop())
+                    .unwrap_or_else(|| Vec::with_capacity(4)),
+            )
+        } else {
+            Frame::Object {
+                map: recycled
+                    .and_then(|r| r.maps.pop())
+                    .unwrap_or_else(|| map_with_capacity(4)),
+                key: String::new(),
+                key_offset: 0,
+            }
+        };
+        let close = if is_array { b']' } else { b'}' };
+        if let Some(stats) = &mut self.stats {
+            match frame {
+                Frame::Array(_) => stats.array_count += 1,
+                Frame::Object { .. } => stats.object_count += 1,
+            }
+            stats.max_depth = stats.max_depth.max(self.depth + stack.len() + 1);
+        }
+        if self.open_container(close)? {
+            return Ok(Some(frame.into_value()));
+        }
+        stack.push(frame);
+        Ok(None)
+    }
+
+    /// Parses any value into the representation built by `builder`, for the
+    /// parsing methods other than `parse`.
+    ///
+    /// As in `parse_container`, containers still being filled are kept on an
+    /// explicit stack rather than the call stack, so nesting depth costs heap
+    /// memory only.
+    fn parse_tree<B: TreeBuilder<'a>>(&mut self, builder: &mut B) -> Result<B::Value> {
+        let depth = self.depth;
+        let result = self.tree_frames(builder);
+        self.depth = depth;
+        result
+    }
+
+    /// Runs the container loop of `parse_tree`, one nesting level deeper
+    /// per open container.
+    fn tree_frames<B: TreeBuilder<'a>>(&mut self, builder: &mut B) -> Result<B::Value> {
+        let mut stack: Vec<TreeFrame<'a, B>> = Vec::new();
+        loop {
+            // Start the next element or member of the innermost container.
+            match stack.last_mut() {
+                Some(TreeFrame::Array { array, .. }) => builder.enter_element(array),
+                Some(TreeFrame::Object { key, .. }) => {
+                    let (span, parsed) = self.parse_key()?;
+                    builder.enter_member(&parsed);
+                    *key = Some((span, parsed));
+                }
+                None => {}
+            }
+            self.skip_whitespace()?;
+            let mut value = match builder.leaf(self)? {
+                Some(value) => value,
+                None => {
+                    self.count_value()?;
+                    self.descend()?;
+                    let start = self.position;
+                    let frame = if self.peek() == Some(b'[') {
+                        TreeFrame::Array {
+                            array: builder.new_array(),
+                            start,
+                        }
+                    } else {
+                        TreeFrame::Object {
+                            object: builder.new_object(),
+                            start,
+                            key: None,
+                        }
+                    };
+                    if !self.open_container(frame.close())? {
+                        stack.push(frame);
+                        continue;
+                    }
+                    self.depth -= 1;
+                    frame.finish(builder, self.position)
+                }
+            };
+            // Add the value to its container, and every container it
+            // completes to the next one out.
+            loop {
+                let close = match stack.last_mut() {
+                    None => return Ok(value),
+                    Some(TreeFrame::Array { array, .. }) => {
+                        builder.push(array, value);
+                        b']'
+                    }
+                    Some(TreeFrame::Object { object, key, .. }) => {
+                        let (span, key) = key.take().expect("a member has a key");
+                        builder.insert(self, object, span, key, value)?;
+                        b'}'
+                    }
+                };
+                if !self.parse_separator(close)? {
+                    break;
+                }
+                let frame = stack.pop().expect("the stack is not empty");
+                self.depth -= 1;
+                value = frame.finish(builder, self.position);
+            }
+        }
+    }
+
+    /// Checks the syntax of any value without building it, for the raw
+    /// parts of `parse_partial`.
+    fn skip_value(&mut self) -> Result<()> {
+        self.skip_whitespace()?;
+        self.parse_tree(&mut SkipTree)
+    }
+
+    /// Parses any value for `parse_recovering`, recording errors instead of
+    /// returning them. Returns `None` if the value had to be skipped.
+    ///
+    /// Containers being filled are kept on an explicit stack, as in
+    /// `parse_container`. Errors inside a container never end it early, so
+    /// the loop always runs until the stack is empty again.
+    fn recover_value(&mut self, errors: &mut Vec<ParseError>) -> Option<JsonValue> {
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut step = Recovery::Value;
+        loop {
+            step = match step {
+                Recovery::Value => self.recover_start(&mut stack, errors),
+                Recovery::Member { after_comma } => {
+                    let frame = stack.last_mut().expect("a member has a container");
+                    let close = frame.close();
+                    if self.recover_container_end(close, after_comma, errors) {
+                        self.depth -= 1;
+                        Recovery::Complete(stack.pop().map(Frame::into_value))
+                    } else if let Frame::Object {
+                        key, key_offset, ..
+                    } = frame
+                    {
+                        match self.parse_key() {
+                            Ok((span, parsed)) => {
+                                *key = parsed.into_owned();
+                                *key_offset = span.start;
+                                Recovery::Value
+                            }
+                            Err(err) => {
+                                errors.push(err);
+                                self.skip_to_boundary();
+                                Recovery::Separator
+                            }
+                        }
+                    } else {
+                        Recovery::Value
+                    }
+                }
+                Recovery::Complete(value) => {
+                    let Some(frame) = stack.last_mut() else {
+                        return value;
+                    };
+                    let value = value.unwrap_or(JsonValue::Null);
+                    match frame {
+                        Frame::Array(values) => values.push(value),
+                        Frame::Object {
+                            map,
+                            key,
+                            key_offset,
+                        } => {
+                            if self.reject_duplicate_keys && map.contains_key(key.as_str()) {
+                                errors.push(self.duplicate_key_error(key, *key_offset));
+                            }
+                            map.insert(core::mem::take(key), value);
+                        }
+                    }
+                    Recovery::Separator
+                }
+                Recovery::Separator => {
+                    let close = stack.last().expect("a separator has a container").close();
+                    if self.recover_separator(close, errors) {
+                        Recovery::Member { after_comma: true }
+                    } else {
+                        self.depth -= 1;
+                        Recovery::Complete(stack.pop().map(Frame::into_value))
+                    }
                 }
-                Some(',') => {
+            };
+        }
+    }
+
+    /// Parses a scalar for `recover_value`, or opens the container starting
+    /// at the current position and pushes a frame for it. A value that fails
+    /// is skipped up to the next boundary.
+    fn recover_start(&mut self, stack: &mut Vec<Frame>, errors: &mut Vec<ParseError>) -> Recovery {
+        if let Err(err) = self.skip_whitespace() {
+            // An unterminated comment runs to the end of the input.
+            errors.push(err);
+            self.position = self.input.len();
+            return Recovery::Complete(None);
+        }
+        let start = self.position;
+        let result = match self.peek() {
+            Some(open @ (b'[' | b'{')) => {
+                self.count_value().and_then(|()| self.descend()).map(|()| {
                     self.next_char();
-                    self.skip_whitespace();
+                    stack.push(if open == b'[' {
+                        Frame::Array(Vec::with_capacity(4))
+                    } else {
+                        Frame::Object {
+                            map: map_with_capacity(4),
+                            key: String::new(),
+                            key_offset: 0,
+                        }
+                    });
+                    Recovery::Member { after_comma: false }
+                })
+            }
+            _ => self
+                .parse_value()
+                .map(|value| Recovery::Complete(Some(value))),
+        };
+        match result {
+            Ok(step) => step,
+            Err(err) => {
+                errors.push(err);
+                // Restart from the beginning of the value, so that a string
+                // that failed halfway through is skipped as a whole.
+                self.position = start;
+                self.skip_to_boundary();
+                Recovery::Complete(None)
+            }
+        }
+    }
+
+    /// Checks for the end of a container before an element or member,
+    /// consuming `close` if present. Returns `true` if the container ended,
+    /// properly, at the end of the input or at another closing character.
+    fn recover_container_end(
+        &mut self,
+        close: u8,
+        after_comma: bool,
+        errors: &mut Vec<ParseError>,
+    ) -> bool {
+        if let Err(err) = self.skip_whitespace() {
+            errors.push(err);
+            self.position = self.input.len();
+            return true;
+        }
+        match self.peek() {
+            Some(c) if c == close => {
+                if after_comma && !self.allow_trailing_commas {
+                    errors.push(self.error(
+                        ParseErrorKind::UnexpectedChar,
+                        "Trailing comma before closing bracket or brace",
+                    ));
+                }
+                self.next_char();
+                true
+            }
+            None => {
+                let container = if close == b']' { "array" } else { "object" };
+                errors.push(self.error(
+                    ParseErrorKind::UnexpectedEof,
+                    &format!("Unterminated {}", container),
+                ));
+                true
+            }
+            Some(c @ (b']' | b'}')) => {
+                // Leave a mismatched closing character for an enclosing
+                // container, treating this one as unterminated.
+                errors.push(self.error(
+                    ParseErrorKind::UnexpectedChar,
+                    &format!("Expected value, got '{}'", c as char),
+                ));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consumes the separator after an element or member for
+    /// `parse_recovering`. Returns `true` if a comma was consumed and the
+    /// container continues.
+    ///
+    /// A closing character that does not match `close` is left in place for
+    /// an enclosing container, treating this one as unterminated.
+    fn recover_separator(&mut self, close: u8, errors: &mut Vec<ParseError>) -> bool {
+        loop {
+            match self.parse_separator(close) {
+                Ok(closed) => return !closed,
+                Err(err) => {
+                    let at_end = err.kind() == ParseErrorKind::UnexpectedEof;
+                    errors.push(err);
+                    if at_end || matches!(self.peek(), Some(b']' | b'}')) {
+                        return false;
+                    }
+                    self.skip_to_boundary();
                 }
-                Some(c) => {
-                    return Err(ParseError::new(&format!(
-                        "Expected comma or closing brace, got '{}'",
-                        c
-                    )))
+            }
+        }
+    }
+
+    /// Skips forward to the next comma or closing bracket or brace that is
+    /// not nested inside a skipped container or string, without consuming
+    /// it.
+    fn skip_to_boundary(&mut self) {
+        let mut depth = 0usize;
+        while let Some(b) = self.peek() {
+            match b {
+                b',' | b']' | b'}' if depth == 0 => return,
+                b'[' | b'{' => depth += 1,
+                b']' | b'}' => depth -= 1,
+                quote if self.is_quote(quote) => {
+                    self.position += 1;
+                    while let Some(b) = self.peek() {
+                        self.position += if b == b'\\' { 2 } else { 1 };
+                        if b == quote {
+                            break;
+                        }
+                    }
+                    self.position = self.position.min(self.input.len());
+                    continue;
                 }
-                None => return Err(ParseError::new("Unterminated object")),
+                _ => {}
+            }
+            self.position += 1;
+        }
+    }
+}
+
+/// A container being filled by `JsonParser::parse_container`.
+enum Frame {
+    Array(Vec<JsonValue>),
+    /// An object along with the key of the member currently being parsed.
+    Object {
+        map: Map,
+        key: String,
+        key_offset: usize,
+    },
+}
+
+impl Frame {
+    /// Returns the character that closes the container.
+    fn close(&self) -> u8 {
+        match self {
+            Frame::Array(_) => b']',
+            Frame::Object { .. } => b'}',
+        }
+    }
+
+    fn into_value(self) -> JsonValue {
+        match self {
+            Frame::Array(values) => JsonValue::Array(values),
+            Frame::Object { map, .. } => JsonValue::Object(map),
+        }
+    }
+}
+
+/// The next step of `JsonParser::recover_value`.
+enum Recovery {
+    /// Parse a value, or open the container it starts.
+    Value,
+    /// Start the next element or member of the innermost container, or end
+    /// it.
+    Member { after_comma: bool },
+    /// Add a finished value, or `None` for one that was skipped, to the
+    /// innermost container, or return it at the top level.
+    Complete(Option<JsonValue>),
+    /// Consume the separator after an element or member.
+    Separator,
+}
+
+/// How `JsonParser::parse_tree` builds one representation of a document.
+trait TreeBuilder<'a> {
+    type Value;
+    type Array;
+    type Object;
+
+    /// Parses the value at the current position, past any whitespace, or
+    /// returns `None` without consuming anything to have the array or object
+    /// there descended into.
+    fn leaf(&mut self, parser: &mut JsonParser<'a>) -> Result<Option<Self::Value>>;
+
+    fn new_array(&mut self) -> Self::Array;
+
+    fn new_object(&mut self) -> Self::Object;
+
+    /// Called before the next element of `array` is parsed.
+    fn enter_element(&mut self, _array: &Self::Array) {}
+
+    /// Called before the value of the member named `key` is parsed.
+    fn enter_member(&mut self, _key: &str) {}
+
+    fn push(&mut self, array: &mut Self::Array, value: Self::Value);
+
+    /// Adds a member whose key occupies `key_span`, failing on a repeated
+    /// key if `reject_duplicate_keys` is set.
+    fn insert(
+        &mut self,
+        parser: &JsonParser<'a>,
+        object: &mut Self::Object,
+        key_span: Range<usize>,
+        key: Cow<'a, str>,
+        value: Self::Value,
+    ) -> Result<()>;
+
+    fn finish_array(&mut self, array: Self::Array, span: Range<usize>) -> Self::Value;
+
+    fn finish_object(&mut self, object: Self::Object, span: Range<usize>) -> Self::Value;
+}
+
+/// A container being filled by `JsonParser::parse_tree`.
+enum TreeFrame<'a, B: TreeBuilder<'a>> {
+    Array {
+        array: B::Array,
+        start: usize,
+    },
+    /// An object along with the key of the member currently being parsed.
+    Object {
+        object: B::Object,
+        start: usize,
+        key: Option<(Range<usize>, Cow<'a, str>)>,
+    },
+}
+
+impl<'a, B: TreeBuilder<'a>> TreeFrame<'a, B> {
+    /// Returns the character that closes the container.
+    fn close(&self) -> u8 {
+        match self {
+            TreeFrame::Array { .. } => b']',
+            TreeFrame::Object { .. } => b'}',
+        }
+    }
+
+    /// Completes the container, which ends just before `end`.
+    fn finish(self, builder: &mut B, end: usize) -> B::Value {
+        match self {
+            TreeFrame::Array { array, start } => builder.finish_array(array, start..end),
+            TreeFrame::Object { object, start, .. } => builder.finish_object(object, start..end),
+        }
+    }
+}
+
+/// Builds the `BorrowedValue` of `JsonParser::parse_borrowed`. Scalars other
+/// than strings are parsed as for `parse` and converted.
+struct BorrowedTree;
+
+impl<'a> TreeBuilder<'a> for BorrowedTree {
+    type Value = BorrowedValue<'a>;
+    type Array = Vec<BorrowedValue<'a>>;
+    type Object = BorrowedMap<'a>;
+
+    fn leaf(&mut self, parser: &mut JsonParser<'a>) -> Result<Option<Self::Value>> {
+        match parser.peek() {
+            Some(b) if parser.is_quote(b) => {
+                parser.count_value()?;
+                parser
+                    .parse_str_borrowed()
+                    .map(|s| Some(BorrowedValue::String(s)))
             }
+            Some(b'[' | b'{') => Ok(None),
+            _ => parser.parse_value().map(|value| Some(value.into())),
+        }
+    }
+
+    fn new_array(&mut self) -> Self::Array {
+        Vec::with_capacity(4)
+    }
+
+    fn new_object(&mut self) -> Self::Object {
+        borrowed_map_with_capacity(4)
+    }
+
+    fn push(&mut self, array: &mut Self::Array, value: Self::Value) {
+        array.push(value);
+    }
+
+    fn insert(
+        &mut self,
+        parser: &JsonParser<'a>,
+        object: &mut Self::Object,
+        key_span: Range<usize>,
+        key: Cow<'a, str>,
+        value: Self::Value,
+    ) -> Result<()> {
+        if parser.reject_duplicate_keys && object.contains_key(&key) {
+            return Err(parser.duplicate_key_error(&key, key_span.start));
+        }
+        object.insert(key, value);
+        Ok(())
+    }
+
+    fn finish_array(&mut self, array: Self::Array, _span: Range<usize>) -> Self::Value {
+        BorrowedValue::Array(array)
+    }
+
+    fn finish_object(&mut self, object: Self::Object, _span: Range<usize>) -> Self::Value {
+        BorrowedValue::Object(object)
+    }
+}
+
+/// Builds the `SpannedValue` of `JsonParser::parse_with_spans`, keeping
+/// object members in source order. Scalars are parsed as for `parse`.
+struct SpannedTree;
+
+impl<'a> TreeBuilder<'a> for SpannedTree {
+    type Value = SpannedValue;
+    type Array = Vec<SpannedValue>;
+    type Object = Vec<SpannedMember>;
+
+    fn leaf(&mut self, parser: &mut JsonParser<'a>) -> Result<Option<Self::Value>> {
+        if let Some(b'[' | b'{') = parser.peek() {
+            return Ok(None);
+        }
+        let start = parser.position;
+        let value = parser.parse_value()?;
+        Ok(Some(SpannedValue {
+            span: start..parser.position,
+            kind: SpannedKind::Scalar(value),
+        }))
+    }
+
+    fn new_array(&mut self) -> Self::Array {
+        Vec::with_capacity(4)
+    }
+
+    fn new_object(&mut self) -> Self::Object {
+        Vec::with_capacity(4)
+    }
+
+    fn push(&mut self, array: &mut Self::Array, value: Self::Value) {
+        array.push(value);
+    }
+
+    fn insert(
+        &mut self,
+        parser: &JsonParser<'a>,
+        object: &mut Self::Object,
+        key_span: Range<usize>,
+        key: Cow<'a, str>,
+        value: Self::Value,
+    ) -> Result<()> {
+        if parser.reject_duplicate_keys && object.iter().any(|m| m.key == key) {
+            return Err(parser.duplicate_key_error(&key, key_span.start));
         }
+        object.push(SpannedMember {
+            key: key.into_owned(),
+            key_span,
+            value,
+        });
+        Ok(())
+    }
+
+    fn finish_array(&mut self, array: Self::Array, span: Range<usize>) -> Self::Value {
+        SpannedValue {
+            span,
+            kind: SpannedKind::Array(array),
+        }
+    }
+
+    fn finish_object(&mut self, object: Self::Object, span: Range<usize>) -> Self::Value {
+        SpannedValue {
+            span,
+            kind: SpannedKind::Object(object),
+        }
+    }
+}
+
+/// Builds the value of `JsonParser::parse_partial`, where `path` holds the
+/// keys and indices leading to the value being parsed. Values at or below a
+/// `selected` pointer are parsed in full, containers above one are descended
+/// into, and anything else becomes `JsonValue::Raw`.
+struct PartialTree<'s> {
+    selected: &'s [Vec<String>],
+    path: Vec<String>,
+}
+
+impl<'a> TreeBuilder<'a> for PartialTree<'_> {
+    type Value = JsonValue;
+    type Array = Vec<JsonValue>;
+    type Object = Map;
+
+    fn leaf(&mut self, parser: &mut JsonParser<'a>) -> Result<Option<Self::Value>> {
+        let path = &self.path;
+        if self
+            .selected
+            .iter()
+            .any(|pointer| path.starts_with(pointer))
+        {
+            return parser.parse_value().map(Some);
+        }
+        let on_the_way = self
+            .selected
+            .iter()
+            .any(|pointer| pointer.starts_with(path));
+        match parser.peek() {
+            Some(b'[' | b'{') if on_the_way => Ok(None),
+            _ if on_the_way => parser.parse_value().map(Some),
+            _ => {
+                let start = parser.position;
+                parser.skip_value()?;
+                Ok(Some(JsonValue::Raw(parser.slice_text(start)?.into_owned())))
+            }
+        }
+    }
+
+    fn new_array(&mut self) -> Self::Array {
+        Vec::new()
+    }
+
+    fn new_object(&mut self) -> Self::Object {
+        map_with_capacity(0)
+    }
+
+    fn enter_element(&mut self, array: &Self::Array) {
+        self.path.push(array.len().to_string());
+    }
+
+    fn enter_member(&mut self, key: &str) {
+        self.path.push(key.to_string());
+    }
+
+    fn push(&mut self, array: &mut Self::Array, value: Self::Value) {
+        self.path.pop();
+        array.push(value);
+    }
+
+    fn insert(
+        &mut self,
+        parser: &JsonParser<'a>,
+        object: &mut Self::Object,
+        key_span: Range<usize>,
+        key: Cow<'a, str>,
+        value: Self::Value,
+    ) -> Result<()> {
+        self.path.pop();
+        if parser.reject_duplicate_keys && object.contains_key(key.as_ref()) {
+            return Err(parser.duplicate_key_error(&key, key_span.start));
+        }
+        object.insert(key.into_owned(), value);
+        Ok(())
+    }
+
+    fn finish_array(&mut self, array: Self::Array, _span: Range<usize>) -> Self::Value {
+        JsonValue::Array(array)
+    }
+
+    fn finish_object(&mut self, object: Self::Object, _span: Range<usize>) -> Self::Value {
+        JsonValue::Object(object)
+    }
+}
+
+/// Checks the syntax of a value without building anything, for
+/// `JsonParser::skip_value`.
+struct SkipTree;
+
+impl<'a> TreeBuilder<'a> for SkipTree {
+    type Value = ();
+    type Array = ();
+    type Object = ();
+
+    fn leaf(&mut self, parser: &mut JsonParser<'a>) -> Result<Option<()>> {
+        match parser.peek() {
+            Some(b) if parser.is_quote(b) => {
+                parser.count_value()?;
+                parser.parse_str_borrowed().map(|_| Some(()))
+            }
+            Some(b'[' | b'{') => Ok(None),
+            _ => parser.parse_value().map(|_| Some(())),
+        }
+    }
+
+    fn new_array(&mut self) {}
+
+    fn new_object(&mut self) {}
+
+    fn push(&mut self, _array: &mut (), _value: ()) {}
+
+    fn insert(
+        &mut self,
+        _parser: &JsonParser<'a>,
+        _object: &mut (),
+        _key_span: Range<usize>,
+        _key: Cow<'a, str>,
+        _value: (),
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn finish_array(&mut self, _array: (), _span: Range<usize>) {}
+
+    fn finish_object(&mut self, _object: (), _span: Range<usize>) {}
+}
+
+/// Builds the JSONPath-style location of the value `parse_container` was
+/// parsing: the current member of every enclosing container, and of the
+/// innermost one too if `in_member`.
+fn error_path(stack: &[Frame], in_member: bool) -> String {
+    let mut path = String::from("$");
+    let len = if in_member {
+        stack.len()
+    } else {
+        stack.len().saturating_sub(1)
+    };
+    for frame in &stack[..len] {
+        match frame {
+            Frame::Array(values) => path.push_str(&format!("[{}]", values.len())),
+            Frame::Object { key, .. } => {
+                let mut bytes = key.bytes();
+                let plain = bytes
+                    .next()
+                    .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+                    && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_');
+                if plain {
+                    path.push('.');
+                    path.push_str(key);
+                } else {
+                    path.push_str("['");
+                    for c in key.chars() {
+                        if c == '\\' || c == '\'' {
+                            path.push('\\');
+                        }
+                        path.push(c);
+                    }
+                    path.push_str("']");
+                }
+            }
+        }
+    }
+    path
+}
+
+/// Emptied containers of a previous value, handed out again by
+/// `JsonParser::open_frame` during [`JsonParser::parse_into`].
+#[derive(Clone, Default)]
+struct Recycled {
+    arrays: Vec<Vec<JsonValue>>,
+    maps: Vec<Map>,
+    strings: Vec<String>,
+}
+
+impl Recycled {
+    /// Empties every container in `value` and keeps it, along with every
+    /// string and object key, ordered so that the outermost containers are
+    /// popped first.
+    fn take_apart(&mut self, value: JsonValue) {
+        // The kept containers double as the work list, so that values of
+        // any depth are taken apart without recursion or extra allocation.
+        self.keep(value);
+        let (mut arrays, mut maps) = (0, 0);
+        while arrays < self.arrays.len() || maps < self.maps.len() {
+            if arrays < self.arrays.len() {
+                let mut values = core::mem::take(&mut self.arrays[arrays]);
+                values.drain(..).for_each(|value| self.keep(value));
+                self.arrays[arrays] = values;
+                arrays += 1;
+            } else {
+                let mut map = core::mem::take(&mut self.maps[maps]);
+                drain_map(&mut map, |(key, value)| {
+                    self.strings.push(key);
+                    self.keep(value);
+                });
+                self.maps[maps] = map;
+                maps += 1;
+            }
+        }
+        self.arrays.reverse();
+        self.maps.reverse();
+    }
+
+    /// Keeps the allocation of `value`, if it has one.
+    fn keep(&mut self, value: JsonValue) {
+        match value {
+            JsonValue::Array(values) => self.arrays.push(values),
+            JsonValue::Object(map) => self.maps.push(map),
+            JsonValue::String(s) | JsonValue::RawNumber(s) => self.strings.push(s),
+            _ => {}
+        }
+    }
+}
+
+/// Iterator over the elements of a top-level array, created by
+/// [`JsonParser::array_elements`].
+pub struct ArrayElements<'p, 'a> {
+    parser: &'p mut JsonParser<'a>,
+    state: ElementState,
+}
+
+/// What `ArrayElements` expects to parse next.
+enum ElementState {
+    /// The first element, or the closing bracket of an empty array.
+    First,
+    /// A comma and the next element, or the closing bracket.
+    Separator,
+    /// The array has ended or an error was returned.
+    Done,
+}
+
+impl ArrayElements<'_, '_> {
+    /// Parses the next element, or returns `None` once the array is closed
+    /// and the input exhausted.
+    fn parse_element(&mut self) -> Result<Option<JsonValue>> {
+        let closed = match self.state {
+            ElementState::Done => return Ok(None),
+            ElementState::First => {
+                self.parser.skip_whitespace()?;
+                let empty = self.parser.peek() == Some(b']');
+                if empty {
+                    self.parser.next_char();
+                }
+                empty
+            }
+            ElementState::Separator => self.parser.parse_separator(b']')?,
+        };
+        if closed {
+            self.state = ElementState::Done;
+            self.parser.expect_end()?;
+            return Ok(None);
+        }
+        self.state = ElementState::Separator;
+        self.parser.parse_nested(JsonParser::parse_value).map(Some)
+    }
+}
+
+impl Iterator for ArrayElements<'_, '_> {
+    type Item = Result<JsonValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.parse_element();
+        if result.is_err() {
+            self.state = ElementState::Done;
+        }
+        self.parser.locate(result).transpose()
+    }
+}
+
+/// Iterator over the events of a document, created by [`JsonParser::events`].
+pub struct Events<'p, 'a> {
+    parser: &'p mut JsonParser<'a>,
+    stack: Vec<OpenContainer>,
+    state: EventState,
+}
+
+/// An array or object whose end event has not been emitted yet.
+struct OpenContainer {
+    close: u8,
+    /// Keys seen so far, tracked only when rejecting duplicate keys.
+    keys: Vec<String>,
+}
+
+/// What `Events` expects to scan next.
+enum EventState {
+    /// A value, at the top level or after an object key or array comma.
+    Value,
+    /// An object key.
+    Key,
+    /// The first element or member of a container, or its closing character.
+    First,
+    /// A comma or closing character after a value, or the end of input.
+    Separator,
+    /// The document has ended or an error was returned.
+    Done,
+}
+
+impl<'a> Events<'_, 'a> {
+    /// Returns the source text of the last number scanned, as
+    /// [`JsonParser::number_source`] does.
+    pub fn number_source(&self) -> Option<&'a str> {
+        self.parser.number_source()
+    }
+
+    /// Scans the next event, or returns `None` once the document is complete.
+    fn scan(&mut self) -> Result<Option<JsonEvent<'a>>> {
+        loop {
+            match self.state {
+                EventState::Done => return Ok(None),
+                EventState::Value => return self.scan_value().map(Some),
+                EventState::Key => {
+                    let (span, key) = self.parser.parse_key()?;
+                    if self.parser.reject_duplicate_keys {
+                        let container = self.stack.last_mut().expect("keys are inside objects");
+                        if container.keys.iter().any(|seen| *seen == key) {
+                            return Err(self.parser.duplicate_key_error(&key, span.start));
+                        }
+                        container.keys.push(key.clone().into_owned());
+                    }
+                    self.state = EventState::Value;
+                    return Ok(Some(JsonEvent::Key(key)));
+                }
+                EventState::First => {
+                    let close = self.stack.last().expect("containers are open").close;
+                    self.parser.skip_whitespace()?;
+                    if self.parser.peek() == Some(close) {
+                        self.parser.next_char();
+                        return Ok(Some(self.close_container()));
+                    }
+                    self.state = if close == b'}' {
+                        EventState::Key
+                    } else {
+                        EventState::Value
+                    };
+                }
+                EventState::Separator => {
+                    let Some(container) = self.stack.last() else {
+                        self.state = EventState::Done;
+                        self.parser.expect_end()?;
+                        return Ok(None);
+                    };
+                    let close = container.close;
+                    if self.parser.parse_separator(close)? {
+                        return Ok(Some(self.close_container()));
+                    }
+                    self.state = if close == b'}' {
+                        EventState::Key
+                    } else {
+                        EventState::Value
+                    };
+                }
+            }
+        }
+    }
+
+    /// Scans a scalar, or the opening character of a container.
+    fn scan_value(&mut self) -> Result<JsonEvent<'a>> {
+        self.parser.skip_whitespace()?;
+        let (close, event) = match self.parser.peek() {
+            Some(b'[') => (b']', JsonEvent::StartArray),
+            Some(b'{') => (b'}', JsonEvent::StartObject),
+            _ => {
+                if self.stack.is_empty() {
+                    self.parser.check_root()?;
+                }
+                let value = self.parser.parse_value()?;
+                self.state = EventState::Separator;
+                return Ok(JsonEvent::Value(value));
+            }
+        };
+        self.parser.count_value()?;
+        if self.stack.len() >= self.parser.max_depth {
+            return Err(self.parser.error(
+                ParseErrorKind::DepthLimitExceeded,
+                &format!(
+                    "Nesting depth exceeds the limit of {}",
+                    self.parser.max_depth
+                ),
+            ));
+        }
+        self.parser.next_char();
+        self.stack.push(OpenContainer {
+            close,
+            keys: Vec::new(),
+        });
+        self.state = EventState::First;
+        Ok(event)
+    }
+
+    /// Pops the innermost container after its closing character was
+    /// consumed, returning its end event.
+    fn close_container(&mut self) -> JsonEvent<'a> {
+        self.state = EventState::Separator;
+        match self.stack.pop().map(|container| container.close) {
+            Some(b']') => JsonEvent::EndArray,
+            _ => JsonEvent::EndObject,
+        }
+    }
+}
+
+impl<'a> Iterator for Events<'_, 'a> {
+    type Item = Result<JsonEvent<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.scan();
+        if result.is_err() {
+            self.state = EventState::Done;
+        }
+        self.parser.locate(result).transpose()
+    }
+}
+
+/// Returns whether the digits of a number literal before any exponent
+/// include a non-zero one.
+fn has_nonzero_mantissa(literal: &str) -> bool {
+    literal
+        .bytes()
+        .take_while(|&b| b != b'e' && b != b'E')
+        .any(|b| matches!(b, b'1'..=b'9'))
+}
+
+/// Parses a number literal into a `Decimal`, returning `None` if it cannot be
+/// held without rounding.
+#[cfg(feature = "decimal")]
+fn parse_decimal(literal: &str) -> Option<rust_decimal::Decimal> {
+    use rust_decimal::Decimal;
+    match literal.split_once(['e', 'E']) {
+        // `from_scientific` rounds a mantissa with too many digits, so check
+        // that it is exact first.
+        Some((mantissa, _)) => {
+            Decimal::from_str_exact(mantissa).ok()?;
+            Decimal::from_scientific(literal).ok()
+        }
+        None => Decimal::from_str_exact(literal).ok(),
+    }
+}
+
+/// Returns whether `b` may start an unquoted object key.
+fn is_identifier_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$'
+}
+
+/// Returns the length of the UTF-8 sequence introduced by `first`, or 1 for a
+/// byte that cannot start a sequence.
+#[inline]
+fn utf8_char_width(first: u8) -> usize {
+    match first {
+        0x00..=0x7F => 1,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+    use crate::value::Map;
+
+    #[test]
+    fn test_parse_null() {
+        let mut parser = JsonParser::new("null");
+        assert_eq!(parser.parse_null().unwrap(), JsonValue::Null);
+    }
+
+    #[test]
+    fn test_parse_boolean() {
+        let mut parser_true = JsonParser::new("true");
+        assert_eq!(
+            parser_true.parse_boolean().unwrap(),
+            JsonValue::Boolean(true)
+        );
+
+        let mut parser_false = JsonParser::new("false");
+        assert_eq!(
+            parser_false.parse_boolean().unwrap(),
+            JsonValue::Boolean(false)
+        );
+    }
+
+    #[test]
+    fn test_parse_string() {
+        let mut parser = JsonParser::new("\"hello\"");
+        assert_eq!(
+            parser.parse_string().unwrap(),
+            JsonValue::String("hello".to_string())
+        );
+
+        let mut parser_escaped = JsonParser::new("\"hello \\\"world\\\"\"");
+        assert_eq!(
+            parser_escaped.parse_string().unwrap(),
+            JsonValue::String("hello \"world\"".to_string())
+        );
+
+        let mut parser_unicode = JsonParser::new("\"\\u263A\"");
+        assert_eq!(
+            parser_unicode.parse_string().unwrap(),
+            JsonValue::String("☺".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_surrogate_pairs() {
+        let mut parser = JsonParser::new("\"\\uD83D\\uDE00\"");
+        assert_eq!(
+            parser.parse_string().unwrap(),
+            JsonValue::String("😀".to_string())
+        );
+
+        let mut parser_max = JsonParser::new("\"\\udbff\\udfff\"");
+        assert_eq!(
+            parser_max.parse_string().unwrap(),
+            JsonValue::String("\u{10FFFF}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_surrogates() {
+        for input in [
+            "\"\\uD83D\"",
+            "\"\\uD83Dx\"",
+            "\"\\uD83D\\n\"",
+            "\"\\uD83D\\u0041\"",
+            "\"\\uD83D\\uD83D\"",
+            "\"\\uDE00\"",
+        ] {
+            let mut parser = JsonParser::new(input);
+            let err = parser.parse_string().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::InvalidUnicode, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let mut parser_int = JsonParser::new("123");
+        assert_eq!(parser_int.parse_number().unwrap(), JsonValue::Integer(123));
+
+        let mut parser_float = JsonParser::new("-456.789");
+        assert_eq!(
+            parser_float.parse_number().unwrap(),
+            JsonValue::Number(-456.789)
+        );
+
+        let mut parser_exp = JsonParser::new("1.23e-4");
+        assert_eq!(
+            parser_exp.parse_number().unwrap(),
+            JsonValue::Number(1.23e-4)
+        );
+
+        let mut parser_max = JsonParser::new("9223372036854775807");
+        assert_eq!(
+            parser_max.parse_number().unwrap(),
+            JsonValue::Integer(i64::MAX)
+        );
+
+        let mut parser_min = JsonParser::new("-9223372036854775808");
+        assert_eq!(
+            parser_min.parse_number().unwrap(),
+            JsonValue::Integer(i64::MIN)
+        );
+
+        let mut parser_unsigned = JsonParser::new("9223372036854775808");
+        assert_eq!(
+            parser_unsigned.parse_number().unwrap(),
+            JsonValue::Unsigned(9223372036854775808)
+        );
+
+        let mut parser_overflow = JsonParser::new("18446744073709551616");
+        assert_eq!(
+            parser_overflow.parse_number().unwrap(),
+            JsonValue::Number(18446744073709551616.0)
+        );
+
+        let mut parser_negative_overflow = JsonParser::new("-9223372036854775809");
+        assert_eq!(
+            parser_negative_overflow.parse_number().unwrap(),
+            JsonValue::Number(-9223372036854775809.0)
+        );
+
+        let mut parser_neg_zero = JsonParser::new("-0");
+        match parser_neg_zero.parse_number().unwrap() {
+            JsonValue::Number(f) => assert!(f == 0.0 && f.is_sign_negative()),
+            other => panic!("expected negative zero, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_max_string_length() {
+        let mut parser = JsonParser::new(r#"["abcd", "a\u00e9c"]"#).with_max_string_length(4);
+        assert!(parser.parse().is_ok());
+
+        for (input, offset) in [
+            (r#"["abcde"]"#, 1),
+            (r#"{"k": 1, "abcde": 2}"#, 9),
+            (r#""ab\nde""#, 0),
+        ] {
+            let mut parser = JsonParser::new(input).with_max_string_length(4);
+            let err = parser.parse().unwrap_err();
+            assert_eq!(
+                err.kind(),
+                ParseErrorKind::StringLengthLimitExceeded,
+                "{}",
+                input
+            );
+            assert_eq!(err.offset(), Some(offset), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_control_characters() {
+        for (input, offset) in [
+            ("\"a\tb\"", 2),
+            ("[\"line\nbreak\"]", 6),
+            ("{\"k\u{1}\": 1}", 3),
+            ("\"esc\\n\u{1f}\"", 6),
+        ] {
+            let err = JsonParser::new(input).parse().unwrap_err();
+            assert_eq!(
+                err.kind(),
+                ParseErrorKind::InvalidControlChar,
+                "{:?}",
+                input
+            );
+            assert_eq!(err.offset(), Some(offset), "{:?}", input);
+            let err = JsonParser::new(input).parse_borrowed().unwrap_err();
+            assert_eq!(err.offset(), Some(offset), "{:?}", input);
+        }
+        assert_eq!(
+            JsonParser::new("\"a\tb\"").parse().unwrap_err().to_string(),
+            "Unescaped control character U+0009 in string at line 1 column 3"
+        );
+
+        let mut parser =
+            JsonParser::new("[\"a\tb\", \"line\nbreak\"]").allow_control_characters(true);
+        assert_eq!(
+            parser.parse().unwrap(),
+            JsonValue::Array(vec![
+                JsonValue::String("a\tb".into()),
+                JsonValue::String("line\nbreak".into()),
+            ])
+        );
+        // DEL and above are not control characters in JSON's sense.
+        assert!(JsonParser::new("\"\u{7f}\"").parse().is_ok());
+    }
+
+    #[test]
+    fn test_byte_order_mark() {
+        let input = "\u{FEFF}{\"a\": 1}";
+        let mut expected = Map::new();
+        expected.insert("a".to_string(), JsonValue::Integer(1));
+        assert_eq!(
+            JsonParser::new(input).parse().unwrap(),
+            JsonValue::Object(expected)
+        );
+        assert!(JsonParser::from_bytes(input.as_bytes()).parse().is_ok());
+        assert!(JsonParser::new(input).parse_borrowed().is_ok());
+        assert_eq!(JsonParser::new(input).events().count(), 4);
+
+        let err = JsonParser::new(input).allow_bom(false).parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::UnexpectedChar);
+        assert_eq!(err.offset(), Some(0));
+
+        // Only a mark at the very start is skipped.
+        let err = JsonParser::new(" \u{FEFF}1").parse().unwrap_err();
+        assert_eq!(err.offset(), Some(1));
+        let mut parser = JsonParser::new("1 \u{FEFF}2");
+        parser.parse_one().unwrap();
+        assert!(parser.parse_one().is_err());
+    }
+
+    #[test]
+    fn test_max_total_values() {
+        let mut parser = JsonParser::new(r#"{"a": [1, 2], "b": null}"#).with_max_total_values(5);
+        assert!(parser.parse().is_ok());
+
+        let mut parser = JsonParser::new(r#"{"a": [1, 2], "b": null}"#).with_max_total_values(4);
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::ValueCountLimitExceeded);
+        assert_eq!(err.offset(), Some(19));
+
+        let input = format!("[{}0]", "0,".repeat(1_000_000));
+        let mut parser = JsonParser::new(&input).with_max_total_values(1000);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::ValueCountLimitExceeded
+        );
+    }
+
+    #[test]
+    fn test_step_budget() {
+        let input = r#"{"name": "Ann", "tags": ["a", "b"], "n": 1}"#;
+        assert!(JsonParser::new(input).with_step_budget(100).parse().is_ok());
+        // 43 bytes and 6 values.
+        assert!(JsonParser::new(input).with_step_budget(49).parse().is_ok());
+        let err = JsonParser::new(input)
+            .with_step_budget(48)
+            .parse()
+            .unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::BudgetExceeded);
+
+        // Pathological shapes stop early instead of running to the end.
+        let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        let err = JsonParser::new(&nested)
+            .with_max_depth(usize::MAX)
+            .with_step_budget(10_000)
+            .parse()
+            .unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::BudgetExceeded);
+        assert!(err.offset().unwrap() <= 10_000);
+
+        let padded = format!("[{}1]", " ".repeat(1_000_000));
+        let err = JsonParser::new(&padded)
+            .with_step_budget(1000)
+            .parse_borrowed()
+            .unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::BudgetExceeded);
+
+        // A long scalar is caught once complete.
+        let long = format!("\"{}\"", "x".repeat(10_000));
+        let mut parser = JsonParser::new(&long).with_step_budget(1000);
+        assert_eq!(
+            parser.parse_one().unwrap_err().kind(),
+            ParseErrorKind::BudgetExceeded
+        );
+
+        // The budget applies to each top-level value separately.
+        let mut parser = JsonParser::new("[1, 2] [3, 4]").with_step_budget(10);
+        assert!(parser.parse_one().is_ok());
+        assert!(parser.parse_one().is_ok());
+    }
+
+    #[test]
+    fn test_parse_borrowed() {
+        let input = r#"{"plain": "text", "esc\u0061ped": "a\nb", "list": [1, 2.5, null, "x"]}"#;
+        let value = JsonParser::new(input).parse_borrowed().unwrap();
+        let map = match &value {
+            BorrowedValue::Object(map) => map,
+            other => panic!("expected object, got {:?}", other),
+        };
+        assert!(matches!(
+            map["plain"],
+            BorrowedValue::String(Cow::Borrowed("text"))
+        ));
+        assert!(matches!(&map["escaped"], BorrowedValue::String(Cow::Owned(s)) if s == "a\nb"));
+        assert!(map.keys().any(|k| matches!(k, Cow::Borrowed("plain"))));
+        assert!(map.keys().any(|k| matches!(k, Cow::Owned(_))));
+        assert_eq!(value.into_owned(), JsonParser::new(input).parse().unwrap());
+
+        let value = JsonParser::from_bytes(b"[\"caf\xC3\xA9\"]")
+            .parse_borrowed()
+            .unwrap();
+        assert_eq!(
+            value,
+            BorrowedValue::Array(vec![BorrowedValue::String("café".into())])
+        );
+    }
+
+    #[test]
+    fn test_parse_borrowed_errors() {
+        let cases: [(&[u8], ParseErrorKind); 5] = [
+            (b"[\"ab\xFF\"]", ParseErrorKind::InvalidUtf8),
+            (b"{\"a\": 1, \"a\": 2}", ParseErrorKind::DuplicateKey),
+            (b"[\"abcdef\"]", ParseErrorKind::StringLengthLimitExceeded),
+            (b"[1, 2,]", ParseErrorKind::UnexpectedChar),
+            (b"\"x\" 1", ParseErrorKind::TrailingData),
+        ];
+        for (input, kind) in cases {
+            let mut parser = JsonParser::from_bytes(input)
+                .reject_duplicate_keys(true)
+                .with_max_string_length(5);
+            assert_eq!(parser.parse_borrowed().unwrap_err().kind(), kind);
+        }
+
+        let mut parser = JsonParser::new("[[[1]]]").with_max_depth(2);
+        assert_eq!(
+            parser.parse_borrowed().unwrap_err().kind(),
+            ParseErrorKind::DepthLimitExceeded
+        );
+    }
+
+    #[test]
+    fn test_parse_one() {
+        let mut parser = JsonParser::new(r#"{"a":1}{"b":2} 3"#);
+        assert_eq!(parser.parse_one().unwrap()["a"], JsonValue::Integer(1));
+        assert_eq!(parser.position(), 7);
+        assert_eq!(parser.parse_one().unwrap()["b"], JsonValue::Integer(2));
+        assert_eq!(parser.position(), 14);
+        assert_eq!(parser.parse_one().unwrap(), JsonValue::Integer(3));
+        assert_eq!(parser.position(), 16);
+        assert_eq!(
+            parser.parse_one().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_restore() {
+        // Speculatively read a strict value, falling back to a bare word.
+        let mut parser = JsonParser::new("[1, 2] [3, bad] 4");
+        assert_eq!(
+            parser.parse_one().unwrap(),
+            JsonValue::Array(vec![1.into(), 2.into()])
+        );
+
+        parser.skip_whitespace().unwrap();
+        let checkpoint = parser.checkpoint();
+        assert_eq!(checkpoint.position(), 7);
+        let err = parser.parse_one().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::UnexpectedChar);
+        assert_eq!(parser.position(), 11);
+
+        parser.restore(checkpoint);
+        assert_eq!(parser.position(), 7);
+        assert_eq!(parser.remaining(), "[3, bad] 4");
+        parser.position += parser.remaining().find(']').unwrap() + 1;
+        assert_eq!(parser.parse_one().unwrap(), JsonValue::Integer(4));
+
+        // A clone parses independently of the original.
+        let mut parser = JsonParser::new("1 2").allow_comments(true);
+        parser.parse_one().unwrap();
+        let mut copy = parser.clone();
+        assert_eq!(copy.parse_one().unwrap(), JsonValue::Integer(2));
+        assert_eq!(parser.position(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "checkpoint is beyond the end of the input")]
+    fn test_restore_foreign_checkpoint() {
+        let mut long = JsonParser::new("[1, 2, 3]");
+        long.parse().unwrap();
+        JsonParser::new("1").restore(long.checkpoint());
+    }
+
+    #[test]
+    fn test_restore_checkpoint_inside_character() {
+        let mut other = JsonParser::new("12 3");
+        other.parse_one().unwrap();
+        let input = "\"€\" 1";
+        let mut parser = JsonParser::new(input);
+        parser.restore(other.checkpoint());
+        assert_eq!(parser.remaining(), "");
+        assert_eq!(parser.remaining_bytes(), &input.as_bytes()[2..]);
+        assert!(parser.parse_one().is_err());
+    }
+
+    #[test]
+    fn test_remaining() {
+        let mut parser = JsonParser::new("{\"len\": 3} abc");
+        assert_eq!(parser.remaining(), "{\"len\": 3} abc");
+        assert_eq!(parser.parse_one().unwrap()["len"], JsonValue::Integer(3));
+        assert_eq!(parser.position(), 10);
+        assert_eq!(parser.remaining(), " abc");
+        assert_eq!(parser.remaining_bytes(), b" abc");
+
+        // A value followed by binary data.
+        let mut parser = JsonParser::from_bytes(b"[1]\n\x00\xFF\x01");
+        assert_eq!(
+            parser.parse_one().unwrap(),
+            JsonValue::Array(vec![1.into()])
+        );
+        assert_eq!(parser.remaining_bytes(), b"\n\x00\xFF\x01");
+        assert_eq!(parser.remaining(), "\n\u{0}");
+
+        let mut parser = JsonParser::new("  \u{e9}");
+        assert!(parser.parse_one().is_err());
+        assert_eq!(parser.remaining(), "\u{e9}");
+    }
+
+    #[test]
+    fn test_arbitrary_precision() {
+        let input = "[0.1,1e400,-0,123456789012345678901234567890,2.50E-3]";
+        let mut parser = JsonParser::new(input).with_arbitrary_precision(true);
+        let value = parser.parse().unwrap();
+        assert_eq!(value[0], JsonValue::RawNumber("0.1".to_string()));
+        assert_eq!(value[1], JsonValue::RawNumber("1e400".to_string()));
+        assert_eq!(value.to_string(), input);
+
+        let mut parser = JsonParser::new("01").with_arbitrary_precision(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidNumber
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_numbers() {
+        use core::str::FromStr;
+        use rust_decimal::Decimal;
+
+        let input = "[0.1,0.2,19.99,-0.50,2.5E-3,1.2e3,7]";
+        let mut parser = JsonParser::new(input).with_decimal_numbers(true);
+        let value = parser.parse().unwrap();
+        assert_eq!(
+            value[0],
+            JsonValue::Decimal(Decimal::from_str("0.1").unwrap())
+        );
+        assert_eq!(value[6], JsonValue::Integer(7));
+        assert_eq!(value.to_string(), "[0.1,0.2,19.99,-0.50,0.0025,1200,7]");
+
+        let (a, b) = match (&value[0], &value[1]) {
+            (JsonValue::Decimal(a), JsonValue::Decimal(b)) => (*a, *b),
+            other => panic!("expected decimals, got {:?}", other),
+        };
+        assert_eq!(a + b, Decimal::from_str("0.3").unwrap());
+        assert_eq!(value[2].as_f64(), Some(19.99));
+        assert_eq!(value[5].as_i64(), Some(1200));
+        assert_eq!(value[0].as_i64(), None);
+        assert_eq!(value[4].to_string_canonical(), "0.0025");
+
+        for input in ["1e100", "0.12345678901234567890123456789012", "1.5e-40"] {
+            let mut parser = JsonParser::new(input).with_decimal_numbers(true);
+            let err = parser.parse().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::InvalidNumber, "{}", input);
+            assert_eq!(err.offset(), Some(0));
+        }
+
+        let mut parser = JsonParser::new("0.1")
+            .with_decimal_numbers(true)
+            .with_arbitrary_precision(true);
+        assert_eq!(
+            parser.parse().unwrap(),
+            JsonValue::RawNumber("0.1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_consumed_bytes() {
+        let input = "{\"a\": [1, 2]}   \n\t";
+        let mut parser = JsonParser::new(input);
+        assert_eq!(parser.consumed_bytes(), 0);
+        parser.parse().unwrap();
+        assert_eq!(parser.consumed_bytes(), input.trim_end().len());
+        assert_eq!(parser.position(), input.len());
+
+        let mut parser = JsonParser::new(input);
+        parser.parse_borrowed().unwrap();
+        assert_eq!(parser.consumed_bytes(), input.trim_end().len());
+
+        let mut parser = JsonParser::new("  1  [2] ");
+        parser.parse_one().unwrap();
+        assert_eq!(parser.consumed_bytes(), 3);
+        parser.parse_one().unwrap();
+        assert_eq!(parser.consumed_bytes(), 8);
+    }
+
+    #[test]
+    fn test_error_path() {
+        let input = r#"{
+            "users": [
+                {"name": "Ann", "email": "ann@example.com"},
+                {"name": "Bob", "tags": ["a", "b"]},
+                {"name": "Cy", "prefs": {"theme": "dark"}},
+                {"name": "Di", "email": "di\q@example.com", "age": 3}
+            ]
+        }"#;
+        let err = parse_json(input).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidEscape);
+        assert_eq!(err.path(), "$.users[3].email");
+        assert_eq!(err.line(), Some(6));
+
+        let cases = [
+            ("tru", "$"),
+            ("[1] x", "$"),
+            ("[1, 2, tru]", "$[2]"),
+            ("[[], [[1, x]]]", "$[1][0][1]"),
+            ("[1 2]", "$"),
+            ("[1,", "$[1]"),
+            (r#"{"a": {"b": [0, {"c": nul}]}}"#, "$.a.b[1].c"),
+            (r#"{"a": 1 "b": 2}"#, "$"),
+            (r#"{"a": {"b": 1, 2}}"#, "$.a"),
+            (r#"{"first name": [x]}"#, "$['first name'][0]"),
+            (r#"{"it's": x}"#, r"$['it\'s']"),
+            (r#"{"": x}"#, "$['']"),
+            (r#"{"_id9": x}"#, "$._id9"),
+            (r#"{"9a": x}"#, "$['9a']"),
+        ];
+        for (input, path) in cases {
+            let err = parse_json(input).unwrap_err();
+            assert_eq!(err.path(), path, "{}", input);
+        }
+
+        let mut parser = JsonParser::new(r#"{"a": 1, "a": 2}"#).reject_duplicate_keys(true);
+        assert_eq!(parser.parse().unwrap_err().path(), "$.a");
+        let mut parser = JsonParser::new("[[[1]]]").with_max_depth(2);
+        assert_eq!(parser.parse().unwrap_err().path(), "$[0][0]");
+        let mut parser = JsonParser::new("[1, [2, 3]]").with_max_total_values(3);
+        assert_eq!(parser.parse().unwrap_err().path(), "$[1][0]");
+
+        // Other entry points do not record paths.
+        let mut parser = JsonParser::new("[1, x]");
+        assert_eq!(parser.parse_borrowed().unwrap_err().path(), "");
+    }
+
+    #[test]
+    fn test_number_source() {
+        let mut parser = JsonParser::new("1.200");
+        assert_eq!(parser.number_source(), None);
+        let value = parser.parse().unwrap();
+        assert_eq!(value.as_f64(), Some(1.2));
+        assert_eq!(value.to_string(), "1.2");
+        assert_eq!(parser.number_source(), Some("1.200"));
+
+        let mut parser = JsonParser::from_bytes(b"[1E+2, -0.50, \"3.0\"]");
+        parser.parse().unwrap();
+        assert_eq!(parser.number_source(), Some("-0.50"));
+
+        let mut parser = JsonParser::new("[0x1F]").allow_json5_numbers(true);
+        assert_eq!(parser.parse().unwrap()[0], JsonValue::Integer(31));
+        assert_eq!(parser.number_source(), Some("0x1F"));
+
+        let mut parser = JsonParser::new(r#"{"price": 10.50, "qty": 2}"#);
+        let mut events = parser.events();
+        let mut sources = Vec::new();
+        while let Some(event) = events.next() {
+            if let JsonEvent::Value(value) = event.unwrap() {
+                if value.as_f64().is_some() {
+                    sources.push(events.number_source().unwrap());
+                }
+            }
+        }
+        assert_eq!(sources, ["10.50", "2"]);
+
+        // Arbitrary precision keeps the text of every number in the tree.
+        let mut parser = JsonParser::new("[1.200, 3]").with_arbitrary_precision(true);
+        let value = parser.parse().unwrap();
+        assert_eq!(value[0], JsonValue::RawNumber("1.200".into()));
+        assert_eq!(value[0].as_f64(), Some(1.2));
+    }
+
+    #[test]
+    fn test_parse_with_number_handler() {
+        let input =
+            r#"{"id": 12345678901234567890123, "price": 10.50, "tags": ["1", [-0, 1e400]]}"#;
+        let value = JsonParser::new(input)
+            .parse_with_number_handler(|text| JsonValue::String(text.to_string()))
+            .unwrap();
+        assert_eq!(
+            value,
+            parse_json(
+                r#"{"id": "12345678901234567890123", "price": "10.50", "tags": ["1", ["-0", "1e400"]]}"#
+            )
+            .unwrap()
+        );
+
+        // The handler's result is not handled again, and options still apply.
+        let mut parser = JsonParser::new("[NaN, 0x10, 2.]")
+            .allow_non_finite_numbers(true)
+            .allow_json5_numbers(true);
+        let value = parser
+            .parse_with_number_handler(|text| JsonValue::RawNumber(format!("{}0", text)))
+            .unwrap();
+        assert!(value[0].as_f64().unwrap().is_nan());
+        assert_eq!(value[1], JsonValue::RawNumber("160".into()));
+        assert_eq!(value[2], JsonValue::RawNumber("2.00".into()));
+        assert!(!parser.arbitrary_precision);
+
+        let mut parser = JsonParser::new("[1, 2");
+        assert!(parser
+            .parse_with_number_handler(|_| JsonValue::Null)
+            .is_err());
+        assert!(!parser.arbitrary_precision);
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        let input = r#"{
+            "id": 42,
+            "meta": {"tags": ["a", "b"], "owner": {"name": "Ann"}},
+            "payload": [1, {"deep": "\u00e9"}, 2.50] ,
+            "items": [{"sku": "x", "qty": 1}, {"sku": "y", "qty": 2}]
+        }"#;
+        let source = |text: &str| {
+            let start = input.find(text).unwrap();
+            JsonValue::Raw(input[start..start + text.len()].to_string())
+        };
+        let value = JsonParser::new(input)
+            .parse_partial(&["/id", "/meta/owner", "/items/1/sku"])
+            .unwrap();
+        assert_eq!(value["id"], JsonValue::Integer(42));
+        assert_eq!(value["meta"]["owner"]["name"], JsonValue::from("Ann"));
+        assert_eq!(value["meta"]["tags"], source(r#"["a", "b"]"#));
+        assert_eq!(value["payload"], source(r#"[1, {"deep": "\u00e9"}, 2.50]"#));
+        assert_eq!(value["items"][0], source(r#"{"sku": "x", "qty": 1}"#));
+        assert_eq!(value["items"][1]["sku"], JsonValue::from("y"));
+        assert_eq!(value["items"][1]["qty"], source("2"));
+
+        // Raw text is forwarded unchanged and parses back to the full value.
+        let text = value.to_string();
+        assert!(text.contains(r#""payload":[1, {"deep": "\u00e9"}, 2.50]"#));
+        assert_eq!(parse_json(&text).unwrap(), parse_json(input).unwrap());
+
+        let whole = JsonParser::new(input).parse_partial(&[""]).unwrap();
+        assert_eq!(whole, parse_json(input).unwrap());
+        let none = JsonParser::new(input).parse_partial(&[]).unwrap();
+        assert_eq!(none, JsonValue::Raw(input.trim().to_string()));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_parse_partial_errors() {
+        for input in [r#"{"a": 1, "b": [1, 2}"#, r#"{"a": 1, "b": "\x"}"#, "[1] x"] {
+            assert!(
+                JsonParser::new(input).parse_partial(&["/a"]).is_err(),
+                "{}",
+                input
+            );
+        }
+        let error = JsonParser::new(r#"{"a": 1, "b": {"c": [tru]}}"#)
+            .parse_partial(&["/a"])
+            .unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::InvalidLiteral);
+        let error = JsonParser::new("[[[[1]]]]")
+            .with_max_depth(3)
+            .parse_partial(&[])
+            .unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::DepthLimitExceeded);
+    }
 
     #[test]
-    fn test_parse_null() {
-        let mut parser = JsonParser::new("null");
-        assert_eq!(parser.parse_null().unwrap(), JsonValue::Null);
+    fn test_array_elements() {
+        let mut parser = JsonParser::new(r#" [{"id": 1}, [true, null], "three"] "#);
+        let elements: Vec<JsonValue> = parser
+            .array_elements()
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            elements,
+            [
+                parse_json(r#"{"id": 1}"#).unwrap(),
+                parse_json("[true, null]").unwrap(),
+                JsonValue::from("three"),
+            ]
+        );
+
+        let mut parser = JsonParser::new("[]");
+        assert_eq!(parser.array_elements().unwrap().count(), 0);
     }
 
     #[test]
-    fn test_parse_boolean() {
-        let mut parser_true = JsonParser::new("true");
+    fn test_array_elements_errors() {
+        let mut parser = JsonParser::new("[1, {\"a\" 2}, 3]");
+        let mut elements = parser.array_elements().unwrap();
+        assert_eq!(elements.next().unwrap().unwrap(), JsonValue::Integer(1));
+        let err = elements.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::UnexpectedChar);
+        assert_eq!(err.offset(), Some(9));
+        assert!(elements.next().is_none());
+
+        let results: Vec<_> = JsonParser::new("[1, 2 3]")
+            .array_elements()
+            .unwrap()
+            .collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[2].is_err());
+        let results: Vec<_> = JsonParser::new("[1] x").array_elements().unwrap().collect();
         assert_eq!(
-            parser_true.parse_boolean().unwrap(),
-            JsonValue::Boolean(true)
+            results[1].as_ref().unwrap_err().kind(),
+            ParseErrorKind::TrailingData
         );
+        let results: Vec<_> = JsonParser::new("[1,").array_elements().unwrap().collect();
+        assert!(results[1].as_ref().unwrap_err().is_eof());
 
-        let mut parser_false = JsonParser::new("false");
+        for (input, kind) in [
+            ("{\"a\": 1}", ParseErrorKind::UnexpectedChar),
+            ("  ", ParseErrorKind::UnexpectedEof),
+        ] {
+            let err = JsonParser::new(input).array_elements().err().unwrap();
+            assert_eq!(err.kind(), kind, "{}", input);
+        }
+
+        let mut parser = JsonParser::new("[[1], [[2]]]").with_max_depth(2);
+        let results: Vec<_> = parser.array_elements().unwrap().collect();
+        assert!(results[0].is_ok());
         assert_eq!(
-            parser_false.parse_boolean().unwrap(),
-            JsonValue::Boolean(false)
+            results[1].as_ref().unwrap_err().kind(),
+            ParseErrorKind::DepthLimitExceeded
         );
     }
 
     #[test]
-    fn test_parse_string() {
-        let mut parser = JsonParser::new("\"hello\"");
+    fn test_parse_into() {
+        let mut scratch = JsonValue::Null;
+        let first = r#"{"ids": [1, 2, 3], "meta": {"ok": true, "tags": {}}}"#;
+        JsonParser::new(first).parse_into(&mut scratch).unwrap();
+        assert_eq!(scratch, JsonParser::new(first).parse().unwrap());
+
+        let ids = scratch["ids"].as_array().unwrap().as_ptr();
+        let second = r#"[[4, 5], {"a": null}]"#;
+        JsonParser::new(second).parse_into(&mut scratch).unwrap();
+        assert_eq!(scratch, JsonParser::new(second).parse().unwrap());
+        // The first array opened reuses the first array taken apart.
+        assert_eq!(scratch.as_array().unwrap().as_ptr(), ids);
+
+        let mut parser = JsonParser::new("[1, oops]");
+        assert!(parser.parse_into(&mut scratch).is_err());
+        assert_eq!(scratch, JsonValue::Null);
+        JsonParser::new("7").parse_into(&mut scratch).unwrap();
+        assert_eq!(scratch, JsonValue::Integer(7));
+    }
+
+    #[test]
+    fn test_truncated_literals() {
+        for input in [
+            "n",
+            "nul",
+            "t",
+            "tru",
+            "f",
+            "fals",
+            "[true, fals",
+            "{\"a\": nu",
+        ] {
+            let err = JsonParser::new(input).parse().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::UnexpectedEof, "{}", input);
+            assert!(err.is_eof());
+        }
+        let err = JsonParser::new("[tru").parse().unwrap_err();
+        assert_eq!(err.offset(), Some(1));
+        assert_eq!(err.message(), "Unexpected end of input in true value");
+
+        for input in ["nulx", "trux", "fals]", "nul ", "[tru]", "nuLL"] {
+            let err = JsonParser::new(input).parse().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::InvalidLiteral, "{}", input);
+        }
+        let mut parser = JsonParser::new("fal").case_insensitive_literals(true);
+        assert!(parser.parse().unwrap_err().is_eof());
+        let mut parser = JsonParser::new("FAL").case_insensitive_literals(true);
+        assert!(parser.parse().unwrap_err().is_eof());
+    }
+
+    #[test]
+    fn test_multibyte_after_partial_literal() {
+        // A multi-byte character where a literal breaks off must be reported,
+        // not sliced through, in every lenient mode that matches literals.
+        for input in [
+            "nu€",
+            "[tru€]",
+            "fals€",
+            "{\"a\": n€}",
+            "NU€",
+            "tRü",
+            "Na€",
+            "-Inf€",
+            "Infinity€",
+            "{a€: 1}",
+            "[null€]",
+        ] {
+            let mut parser = JsonParser::new(input)
+                .case_insensitive_literals(true)
+                .allow_non_finite_numbers(true)
+                .allow_unquoted_keys(true);
+            let err = parser.parse().unwrap_err();
+            let offset = err.offset().unwrap();
+            assert!(input.is_char_boundary(offset), "{}", input);
+            assert!(!err.snippet(input).is_empty());
+            assert!(JsonParser::from_bytes(input.as_bytes()).parse().is_err());
+        }
+        let err = JsonParser::new("[nu€]").parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidLiteral);
+        assert_eq!(err.offset(), Some(1));
+    }
+
+    #[test]
+    fn test_case_insensitive_literals() {
+        let input = "[True, FALSE, nULL, tRuE, null]";
         assert_eq!(
-            parser.parse_string().unwrap(),
-            JsonValue::String("hello".to_string())
+            JsonParser::new(input).parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
         );
+        for (input, kind) in [
+            ("tRUE", ParseErrorKind::InvalidLiteral),
+            ("nULL", ParseErrorKind::InvalidLiteral),
+            ("False", ParseErrorKind::UnexpectedChar),
+        ] {
+            assert_eq!(JsonParser::new(input).parse().unwrap_err().kind(), kind);
+        }
 
-        let mut parser_escaped = JsonParser::new("\"hello \\\"world\\\"\"");
+        let lenient = |input| JsonParser::new(input).case_insensitive_literals(true);
         assert_eq!(
-            parser_escaped.parse_string().unwrap(),
-            JsonValue::String("hello \"world\"".to_string())
+            lenient(input).parse().unwrap(),
+            JsonValue::Array(vec![
+                JsonValue::Boolean(true),
+                JsonValue::Boolean(false),
+                JsonValue::Null,
+                JsonValue::Boolean(true),
+                JsonValue::Null,
+            ])
+        );
+        let mut parser = lenient("NULL 1");
+        assert_eq!(parser.parse_one().unwrap(), JsonValue::Null);
+        assert_eq!(parser.remaining(), " 1");
+        assert_eq!(
+            lenient("Nulx").parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidLiteral
+        );
+        assert_eq!(
+            lenient("Trueish").parse().unwrap_err().kind(),
+            ParseErrorKind::TrailingData
         );
 
-        let mut parser_unicode = JsonParser::new("\"\\u263A\"");
+        // `NaN` keeps its meaning, and stays an error unless enabled.
         assert_eq!(
-            parser_unicode.parse_string().unwrap(),
-            JsonValue::String("☺".to_string())
+            lenient("NaN").parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
         );
+        let value = lenient("[NaN, Null]")
+            .allow_non_finite_numbers(true)
+            .parse()
+            .unwrap();
+        assert!(value[0].as_f64().unwrap().is_nan());
+        assert_eq!(value[1], JsonValue::Null);
     }
 
     #[test]
-    fn test_parse_number() {
-        let mut parser_int = JsonParser::new("123");
-        assert_eq!(parser_int.parse_number().unwrap(), JsonValue::Number(123.0));
+    fn test_number_grammar() {
+        for input in ["0", "0.5", "-0", "10", "0e5", "-0.0"] {
+            let mut parser = JsonParser::new(input);
+            assert!(parser.parse().is_ok(), "{}", input);
+        }
+        for input in [
+            "01", "007", "-01", "00.5", "+5", ".5", "[5.]", "[-]", "-.5", "1.e3",
+        ] {
+            let mut parser = JsonParser::new(input);
+            assert_eq!(
+                parser.parse().unwrap_err().kind(),
+                ParseErrorKind::InvalidNumber,
+                "{}",
+                input
+            );
+        }
+    }
 
-        let mut parser_float = JsonParser::new("-456.789");
+    #[test]
+    fn test_exponent_grammar() {
+        for (input, expected) in [
+            ("1e0", JsonValue::Number(1.0)),
+            ("1E+5", JsonValue::Number(1e5)),
+            ("1e-3", JsonValue::Number(1e-3)),
+            ("-2.5E2", JsonValue::Number(-250.0)),
+            ("0e0", JsonValue::Number(0.0)),
+        ] {
+            assert_eq!(
+                JsonParser::new(input).parse().unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+        // At the end of the input more digits could still follow, so a
+        // missing exponent there is reported as truncation.
+        for input in ["1e", "1e+", "1e-", "1.2e", "1.2E-"] {
+            let err = JsonParser::new(input).parse().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::UnexpectedEof, "{}", input);
+            assert_eq!(
+                err.message(),
+                "Exponent must be followed by at least one digit"
+            );
+            let err = JsonParser::new(&format!("[{}]", input))
+                .parse()
+                .unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::InvalidNumber, "{}", input);
+            assert_eq!(err.offset(), Some(input.len() + 1), "{}", input);
+        }
+        for input in ["1e+-1", "1ee1", "1e1.5", "1e 1"] {
+            assert!(JsonParser::new(input).parse().is_err(), "{}", input);
+        }
+        for input in [".", "-.", "[.]", "[-.]", "-.e1", "e1"] {
+            let err = JsonParser::new(input).parse().unwrap_err();
+            assert_ne!(err.kind(), ParseErrorKind::TrailingData, "{}", input);
+        }
         assert_eq!(
-            parser_float.parse_number().unwrap(),
-            JsonValue::Number(-456.789)
+            JsonParser::new("[-.]").parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidNumber
         );
+    }
 
-        let mut parser_exp = JsonParser::new("1.23e-4");
+    #[test]
+    fn test_json5_numbers() {
+        let json5 = |input| JsonParser::new(input).allow_json5_numbers(true);
+        let cases = [
+            ("0xFF", JsonValue::Integer(255)),
+            ("0XaB", JsonValue::Integer(171)),
+            ("-0x10", JsonValue::Integer(-16)),
+            ("+0x1e5", JsonValue::Integer(0x1e5)),
+            ("0xFFFFFFFFFFFFFFFF", JsonValue::Unsigned(u64::MAX)),
+            ("0x8000000000000000", JsonValue::Unsigned(1 << 63)),
+            ("-0x8000000000000000", JsonValue::Integer(i64::MIN)),
+            (".5", JsonValue::Number(0.5)),
+            ("-.25", JsonValue::Number(-0.25)),
+            ("5.", JsonValue::Number(5.0)),
+            ("5.e2", JsonValue::Number(500.0)),
+            (".5E1", JsonValue::Number(5.0)),
+            ("+7", JsonValue::Integer(7)),
+            ("+1.5", JsonValue::Number(1.5)),
+            ("+.5", JsonValue::Number(0.5)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(json5(input).parse().unwrap(), expected, "{}", input);
+            assert!(JsonParser::new(input).parse().is_err(), "{}", input);
+        }
         assert_eq!(
-            parser_exp.parse_number().unwrap(),
-            JsonValue::Number(1.23e-4)
+            json5("[0x1F, .5, 5., +1]").parse().unwrap(),
+            parse_json("[31, 0.5, 5.0, 1]").unwrap()
+        );
+
+        // The forms are rewritten as plain JSON for arbitrary precision.
+        let value = json5("[0xFF, .5, 5., +1e3]")
+            .with_arbitrary_precision(true)
+            .parse()
+            .unwrap();
+        assert_eq!(value.to_string(), "[255,0.5,5.0,1e3]");
+
+        for (input, kind) in [
+            (".", ParseErrorKind::UnexpectedEof),
+            ("[.]", ParseErrorKind::InvalidNumber),
+            ("+", ParseErrorKind::UnexpectedEof),
+            ("0x", ParseErrorKind::UnexpectedEof),
+            ("[0xG]", ParseErrorKind::InvalidNumber),
+            ("0x1.5", ParseErrorKind::TrailingData),
+            ("0x10000000000000000", ParseErrorKind::InvalidNumber),
+            ("++1", ParseErrorKind::InvalidNumber),
+            ("+-1", ParseErrorKind::InvalidNumber),
+            ("00x1", ParseErrorKind::InvalidNumber),
+        ] {
+            assert_eq!(json5(input).parse().unwrap_err().kind(), kind, "{}", input);
+        }
+
+        let (_, report) = json5("[1, 0x1, 2.5, .5]").parse_with_report().unwrap();
+        assert_eq!(
+            report,
+            vec![Leniency::Json5Number(4), Leniency::Json5Number(14)]
         );
     }
 
@@ -366,15 +3883,15 @@ mod tests {
     fn test_parse_array() {
         let mut parser_empty = JsonParser::new("[]");
         assert_eq!(
-            parser_empty.parse_array().unwrap(),
+            parser_empty.parse_container().unwrap(),
             JsonValue::Array(vec![])
         );
 
         let mut parser = JsonParser::new("[1, \"test\"]");
         assert_eq!(
-            parser.parse_array().unwrap(),
+            parser.parse_container().unwrap(),
             JsonValue::Array(vec![
-                JsonValue::Number(1.0),
+                JsonValue::Integer(1),
                 JsonValue::String("test".to_string())
             ])
         );
@@ -384,13 +3901,592 @@ mod tests {
     fn test_parse_object() {
         let mut parser_empty = JsonParser::new("{}");
         assert_eq!(
-            parser_empty.parse_object().unwrap(),
-            JsonValue::Object(HashMap::new())
+            parser_empty.parse_container().unwrap(),
+            JsonValue::Object(Map::new())
         );
 
         let mut parser = JsonParser::new("{\"key\": \"value\"}");
-        let mut expected = HashMap::new();
+        let mut expected = Map::new();
         expected.insert("key".to_string(), JsonValue::String("value".to_string()));
-        assert_eq!(parser.parse_object().unwrap(), JsonValue::Object(expected));
+        assert_eq!(
+            parser.parse_container().unwrap(),
+            JsonValue::Object(expected)
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let mut parser = JsonParser::new("[[1]]").with_max_depth(2);
+        assert!(parser.parse().is_ok());
+
+        let mut parser = JsonParser::new("[[[1]]]").with_max_depth(2);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::DepthLimitExceeded
+        );
+
+        let mut parser = JsonParser::new("{\"a\": {\"b\": {}}}").with_max_depth(2);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::DepthLimitExceeded
+        );
+
+        let mut parser = JsonParser::new("[[], [], {\"a\": 1}]").with_max_depth(2);
+        assert!(parser.parse().is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let mut parser = JsonParser::new("{\"a\": 1, \"a\": 2}");
+        let mut expected = Map::new();
+        expected.insert("a".to_string(), JsonValue::Integer(2));
+        assert_eq!(parser.parse().unwrap(), JsonValue::Object(expected));
+
+        let mut parser = JsonParser::new("{\"a\": 1, \"a\": 2}").reject_duplicate_keys(true);
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::DuplicateKey);
+        assert_eq!(err.offset(), Some(9));
+    }
+
+    #[test]
+    fn test_error_offsets() {
+        let mut parser = JsonParser::new("[1, 2 3]");
+        assert_eq!(parser.parse().unwrap_err().offset(), Some(6));
+
+        let mut parser = JsonParser::new("\"abc");
+        assert_eq!(parser.parse().unwrap_err().offset(), Some(4));
+    }
+
+    #[test]
+    fn test_parse_with_stats() {
+        let input = r#" {"name": "Ann", "tags": ["a", "b"], "scores": [[1, 2.5], []], "ok": true, "x": null} "#;
+        let (value, stats) = JsonParser::new(input).parse_with_stats().unwrap();
+        assert_eq!(value, parse_json(input).unwrap());
+        assert_eq!(
+            stats,
+            ParseStats {
+                object_count: 1,
+                array_count: 4,
+                string_count: 3,
+                number_count: 2,
+                max_depth: 3,
+                total_bytes: input.len(),
+            }
+        );
+
+        let (_, stats) = JsonParser::new("42").parse_with_stats().unwrap();
+        assert_eq!(stats.number_count, 1);
+        assert_eq!(stats.max_depth, 0);
+
+        // A byte order mark is skipped rather than parsed.
+        let (_, stats) = JsonParser::new("\u{feff}[1] ").parse_with_stats().unwrap();
+        assert_eq!(stats.total_bytes, 4);
+
+        let mut parser = JsonParser::new("[1, x]");
+        assert!(parser.parse_with_stats().is_err());
+        assert!(parser.stats.is_none());
+    }
+
+    #[test]
+    fn test_parse_with_report() {
+        let lenient = |input| {
+            JsonParser::new(input)
+                .with_trailing_commas(true)
+                .allow_comments(true)
+                .allow_single_quotes(true)
+                .allow_unquoted_keys(true)
+                .allow_non_finite_numbers(true)
+                .allow_control_characters(true)
+                .case_insensitive_literals(true)
+        };
+        let cases = [
+            ("\u{feff}[1]", Leniency::ByteOrderMark(0)),
+            ("[1] // done", Leniency::Comment(4)),
+            ("[/* one */ 1]", Leniency::Comment(1)),
+            ("[1, 2 ,]", Leniency::TrailingComma(6)),
+            ("{\"a\": 1,}", Leniency::TrailingComma(7)),
+            ("['x']", Leniency::SingleQuotedString(1)),
+            ("{'a': 1}", Leniency::SingleQuotedString(1)),
+            ("[\"it\\'s\"]", Leniency::EscapedSingleQuote(4)),
+            ("{a: 1}", Leniency::UnquotedKey(1)),
+            ("[NaN]", Leniency::NonFiniteNumber(1)),
+            ("[0, -Infinity]", Leniency::NonFiniteNumber(4)),
+            ("[1e400]", Leniency::NonFiniteNumber(1)),
+            ("\"a\tb\"", Leniency::ControlCharacter(2)),
+            ("\"\\n\nb\"", Leniency::ControlCharacter(3)),
+            ("[True]", Leniency::CaseInsensitiveLiteral(1)),
+            ("[1, NULL]", Leniency::CaseInsensitiveLiteral(4)),
+        ];
+        for (input, leniency) in cases {
+            let (value, report) = lenient(input).parse_with_report().unwrap();
+            assert_eq!(report, vec![leniency], "{}", input);
+            assert_eq!(value, lenient(input).parse().unwrap());
+        }
+
+        let input = "{a: ['x', 1,], \"b\": true} // end";
+        let (_, report) = lenient(input).parse_with_report().unwrap();
+        assert_eq!(
+            report,
+            vec![
+                Leniency::UnquotedKey(1),
+                Leniency::SingleQuotedString(5),
+                Leniency::TrailingComma(11),
+                Leniency::Comment(26),
+            ]
+        );
+        assert_eq!(report[3].offset(), 26);
+
+        // Strict documents report nothing, even under lenient options.
+        let (_, report) = lenient(r#"{"a": [1, "x\ty", null]}"#)
+            .parse_with_report()
+            .unwrap();
+        assert!(report.is_empty());
+
+        let mut parser = lenient("[1,, 2]");
+        assert!(parser.parse_with_report().is_err());
+        assert!(parser.leniencies.is_none());
+    }
+
+    #[test]
+    fn test_require_top_level_aggregate() {
+        for input in ["42", "\"hi\"", "null", "{}", " [1] "] {
+            assert!(JsonParser::new(input).parse().is_ok(), "{}", input);
+        }
+
+        for input in ["{}", " [1] ", "{\"a\": 1}"] {
+            let mut parser = JsonParser::new(input).require_top_level_aggregate(true);
+            assert!(parser.parse().is_ok(), "{}", input);
+        }
+        for input in ["42", " \"hi\"", "true"] {
+            let err = JsonParser::new(input)
+                .require_top_level_aggregate(true)
+                .parse()
+                .unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::UnexpectedChar, "{}", input);
+            assert_eq!(err.offset(), Some(input.len() - input.trim_start().len()));
+        }
+        assert_eq!(
+            JsonParser::new("42")
+                .require_top_level_aggregate(true)
+                .parse()
+                .unwrap_err()
+                .to_string(),
+            "Expected object or array at the top level, got '4' at line 1 column 1"
+        );
+
+        // Every entry point applies the rule.
+        let strict = || JsonParser::new("42").require_top_level_aggregate(true);
+        assert!(strict().parse_borrowed().is_err());
+        assert!(strict().parse_with_spans().is_err());
+        assert!(strict().events().next().unwrap().is_err());
+        let (value, errors) = strict().parse_recovering();
+        assert_eq!((value, errors.len()), (None, 1));
+        let events: Vec<_> = JsonParser::new("[1]")
+            .require_top_level_aggregate(true)
+            .events()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(events.len(), 3);
+    }
+
+    #[test]
+    fn test_trailing_data_offset() {
+        let mut parser = JsonParser::new("{} garbage");
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::TrailingData);
+        assert_eq!(err.offset(), Some(3));
+        assert_eq!(
+            err.to_string(),
+            "Extra characters after JSON value, starting with 'g' at line 1 column 4"
+        );
+
+        let mut parser = JsonParser::new("[1]\n  \u{e9}");
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.offset(), Some(6));
+        assert!(err.message().ends_with("starting with '\u{e9}'"));
+        assert_eq!((err.line(), err.column()), (Some(2), Some(3)));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let mut parser = JsonParser::from_bytes(b"{\"caf\xC3\xA9\": [1, \"\xE2\x98\xBA\"]}");
+        let mut expected = Map::new();
+        expected.insert(
+            "café".to_string(),
+            JsonValue::Array(vec![
+                JsonValue::Integer(1),
+                JsonValue::String("☺".to_string()),
+            ]),
+        );
+        assert_eq!(parser.parse().unwrap(), JsonValue::Object(expected));
+    }
+
+    #[test]
+    fn test_from_bytes_invalid_utf8() {
+        let mut parser = JsonParser::from_bytes(b"[\"ok\", \"ab\xFFcd\"]");
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidUtf8);
+        assert_eq!(err.offset(), Some(10));
+
+        // A truncated multi-byte sequence right before the closing quote.
+        let mut parser = JsonParser::from_bytes(b"\"\xE2\x98\"");
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidUtf8
+        );
+
+        let mut parser = JsonParser::from_bytes(b"[1, \xFF]");
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_utf8_lossy() {
+        let input = b"{\"k\xFFey\": [\"ab\xFFcd\", \"x\\n\xE2\x98\", \"\xC3\xA9\"]}";
+        let err = JsonParser::from_bytes(input).parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidUtf8);
+
+        let value = JsonParser::from_bytes(input)
+            .utf8_lossy(true)
+            .parse()
+            .unwrap();
+        let strings = &value["k\u{FFFD}ey"];
+        assert_eq!(strings[0], JsonValue::from("ab\u{FFFD}cd"));
+        assert_eq!(strings[1], JsonValue::from("x\n\u{FFFD}"));
+        assert_eq!(strings[2], JsonValue::from("\u{e9}"));
+
+        let borrowed = JsonParser::from_bytes(input)
+            .utf8_lossy(true)
+            .parse_borrowed()
+            .unwrap();
+        assert_eq!(borrowed.into_owned(), value);
+
+        // Invalid bytes outside strings are still rejected.
+        let mut parser = JsonParser::from_bytes(b"[1, \xFF]").utf8_lossy(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
+    }
+
+    #[test]
+    fn test_skip_whitespace() {
+        let mut parser = JsonParser::new(" \t\r\n x");
+        parser.skip_whitespace().unwrap();
+        assert_eq!(parser.position, 5);
+        assert_eq!(parser.rest(), b"x");
+
+        // Only the four JSON whitespace characters are skipped.
+        let mut parser = JsonParser::new("\u{00A0}1");
+        parser.skip_whitespace().unwrap();
+        assert_eq!(parser.position, 0);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
+    }
+
+    #[test]
+    fn test_non_finite_numbers() {
+        for input in ["NaN", "Infinity", "-Infinity"] {
+            let mut parser = JsonParser::new(input);
+            assert!(parser.parse().is_err(), "{}", input);
+        }
+
+        let mut parser =
+            JsonParser::new("[NaN, Infinity, -Infinity, -1]").allow_non_finite_numbers(true);
+        let value = parser.parse().unwrap();
+        let values = value.as_array().unwrap();
+        assert!(values[0].as_f64().unwrap().is_nan());
+        assert_eq!(values[1], JsonValue::Number(f64::INFINITY));
+        assert_eq!(values[2], JsonValue::Number(f64::NEG_INFINITY));
+        assert_eq!(values[3], JsonValue::Integer(-1));
+
+        let mut parser = JsonParser::new("Inf").allow_non_finite_numbers(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedEof
+        );
+        let mut parser = JsonParser::new("[Inf]").allow_non_finite_numbers(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidLiteral
+        );
+    }
+
+    #[test]
+    fn test_number_overflow() {
+        for (input, offset) in [
+            ("1e400", 0),
+            ("[-1e400]", 1),
+            ("[1, 17976931348623159e292]", 4),
+        ] {
+            let err = JsonParser::new(input).parse().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::InvalidNumber, "{}", input);
+            assert_eq!(err.offset(), Some(offset), "{}", input);
+        }
+        assert_eq!(
+            JsonParser::new("-1e400").parse().unwrap_err().to_string(),
+            "Number -1e400 is out of range at line 1 column 1"
+        );
+        assert_eq!(
+            JsonParser::new("1.7976931348623157e308").parse().unwrap(),
+            JsonValue::Number(f64::MAX)
+        );
+
+        let mut parser = JsonParser::new("[1e400, -1e400]").allow_non_finite_numbers(true);
+        assert_eq!(
+            parser.parse().unwrap(),
+            JsonValue::Array(vec![
+                JsonValue::Number(f64::INFINITY),
+                JsonValue::Number(f64::NEG_INFINITY),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_number_underflow() {
+        assert_eq!(
+            JsonParser::new("1e-400").parse().unwrap(),
+            JsonValue::Number(0.0)
+        );
+
+        let err = JsonParser::new("[1e-400]")
+            .reject_number_underflow(true)
+            .parse()
+            .unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidNumber);
+        assert_eq!(err.offset(), Some(1));
+        assert_eq!(
+            err.to_string(),
+            "Number 1e-400 is too small and would round to zero at line 1 column 2"
+        );
+
+        for input in ["0.0", "-0e-400", "0.000e5", "5e-324"] {
+            let mut parser = JsonParser::new(input).reject_number_underflow(true);
+            assert!(parser.parse().is_ok(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_recovering() {
+        let input = r#"{"a": [1, tru, 3], "b": {"c" 4, "d": 5}, "e": "ok"}"#;
+        let (value, errors) = JsonParser::new(input).parse_recovering();
+        let kinds: Vec<_> = errors.iter().map(|e| e.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                ParseErrorKind::InvalidLiteral,
+                ParseErrorKind::UnexpectedChar
+            ]
+        );
+        assert_eq!(errors[0].offset(), Some(10));
+        assert_eq!(errors[1].offset(), Some(29));
+        let mut expected = Map::new();
+        expected.insert("d".into(), JsonValue::Integer(5));
+        let mut root = Map::new();
+        root.insert(
+            "a".into(),
+            JsonValue::Array(vec![
+                JsonValue::Integer(1),
+                JsonValue::Null,
+                JsonValue::Integer(3),
+            ]),
+        );
+        root.insert("b".into(), JsonValue::Object(expected));
+        root.insert("e".into(), JsonValue::String("ok".into()));
+        assert_eq!(value, Some(JsonValue::Object(root)));
+    }
+
+    #[test]
+    fn test_parse_recovering_edge_cases() {
+        let (value, errors) = JsonParser::new("[1, 2]").parse_recovering();
+        assert_eq!(value, Some(parse_json("[1, 2]").unwrap()));
+        assert!(errors.is_empty());
+
+        let cases: [(&str, Option<&str>, &[ParseErrorKind]); 7] = [
+            ("", None, &[ParseErrorKind::UnexpectedEof]),
+            ("[1, 2", Some("[1, 2]"), &[ParseErrorKind::UnexpectedEof]),
+            ("[1,]", Some("[1]"), &[ParseErrorKind::UnexpectedChar]),
+            (
+                "[\"a\tb\", {\"k\": [}, 3]",
+                Some("[null, {\"k\": []}, 3]"),
+                &[
+                    ParseErrorKind::InvalidControlChar,
+                    ParseErrorKind::UnexpectedChar,
+                ],
+            ),
+            (
+                "[1 2, 3]",
+                Some("[1, 3]"),
+                &[ParseErrorKind::UnexpectedChar],
+            ),
+            (
+                "{1: 2, \"x\": 3} 4",
+                Some("{\"x\": 3}"),
+                &[ParseErrorKind::InvalidKey, ParseErrorKind::TrailingData],
+            ),
+            (
+                "[[[[1]]], 2]",
+                Some("[[[null]], 2]"),
+                &[ParseErrorKind::DepthLimitExceeded],
+            ),
+        ];
+        for (input, expected, kinds) in cases {
+            let (value, errors) = JsonParser::new(input).with_max_depth(3).parse_recovering();
+            assert_eq!(
+                value,
+                expected.map(|expected| parse_json(expected).unwrap()),
+                "{}",
+                input
+            );
+            let actual: Vec<_> = errors.iter().map(|e| e.kind()).collect();
+            assert_eq!(actual, kinds, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        for input in ["[1, 2,]", "{\"a\": 1,}", "[{\"a\": [1,],},]"] {
+            let mut parser = JsonParser::new(input);
+            assert!(parser.parse().is_err(), "{}", input);
+
+            let mut parser = JsonParser::new(input).with_trailing_commas(true);
+            assert!(parser.parse().is_ok(), "{}", input);
+        }
+
+        for input in ["[,]", "{,}", "[1,,]", "[1, 2,,]"] {
+            let mut parser = JsonParser::new(input).with_trailing_commas(true);
+            assert!(parser.parse().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_single_quotes() {
+        let input = r#"{'key': 'value', "mixed": ['a', "b"], 'say "hi"': 'it\'s \u0041\n'}"#;
+        let mut parser = JsonParser::new(input);
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidKey);
+        assert_eq!(err.offset(), Some(1));
+
+        let expected =
+            parse_json(r#"{"key": "value", "mixed": ["a", "b"], "say \"hi\"": "it's A\n"}"#)
+                .unwrap();
+        let mut parser = JsonParser::new(input).allow_single_quotes(true);
+        assert_eq!(parser.parse().unwrap(), expected);
+        let mut parser = JsonParser::new(input).allow_single_quotes(true);
+        assert_eq!(parser.parse_borrowed().unwrap().into_owned(), expected);
+
+        // `\'` is only an escape when single quotes are allowed.
+        let mut parser = JsonParser::new(r#""it\'s""#);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidEscape
+        );
+        let mut parser = JsonParser::new("'abc").allow_single_quotes(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnterminatedString
+        );
+    }
+
+    #[test]
+    fn test_unquoted_keys() {
+        let input = r#"{name: "John", _id: 1, $ref: "x", camelCase2: true, "quoted": null}"#;
+        let mut parser = JsonParser::new(input);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidKey
+        );
+
+        let mut parser = JsonParser::new(input).allow_unquoted_keys(true);
+        let expected = parse_json(
+            r#"{"name": "John", "_id": 1, "$ref": "x", "camelCase2": true, "quoted": null}"#,
+        )
+        .unwrap();
+        assert_eq!(parser.parse().unwrap(), expected);
+
+        let cases = [
+            ("{my key: 1}", ParseErrorKind::UnexpectedChar, 4),
+            ("{a-b: 1}", ParseErrorKind::UnexpectedChar, 2),
+            ("{a.b: 1}", ParseErrorKind::UnexpectedChar, 2),
+            ("{1a: 1}", ParseErrorKind::InvalidKey, 1),
+            ("{-a: 1}", ParseErrorKind::InvalidKey, 1),
+            ("{caf\u{e9}: 1}", ParseErrorKind::UnexpectedChar, 4),
+        ];
+        for (input, kind, offset) in cases {
+            let mut parser = JsonParser::new(input).allow_unquoted_keys(true);
+            let err = parser.parse().unwrap_err();
+            assert_eq!(err.kind(), kind, "{}", input);
+            assert_eq!(err.offset(), Some(offset), "{}", input);
+        }
+
+        let input = "{ab: 1}";
+        let value = JsonParser::new(input)
+            .allow_unquoted_keys(true)
+            .parse_with_spans()
+            .unwrap();
+        match &value.kind {
+            SpannedKind::Object(members) => assert_eq!(members[0].key_span, 1..3),
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_comments() {
+        let input = "// leading\n{/* a */\"a\" /* b */: // c\n [1, /**/ 2 /* ** / */]} // end";
+        let mut parser = JsonParser::new(input);
+        assert!(parser.parse().is_err());
+
+        let mut parser = JsonParser::new(input).allow_comments(true);
+        let mut expected = Map::new();
+        expected.insert(
+            "a".to_string(),
+            JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Integer(2)]),
+        );
+        assert_eq!(parser.parse().unwrap(), JsonValue::Object(expected));
+    }
+
+    #[test]
+    fn test_comments_edge_cases() {
+        // Comment markers inside strings are plain text.
+        let mut parser = JsonParser::new("\"// not /* a comment */\"").allow_comments(true);
+        assert_eq!(
+            parser.parse().unwrap(),
+            JsonValue::String("// not /* a comment */".to_string())
+        );
+
+        // Block comments do not nest: the first `*/` closes the comment.
+        let mut parser = JsonParser::new("1 /* a /* b */ c */").allow_comments(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::TrailingData
+        );
+
+        let mut parser = JsonParser::new("[1 /* never closed").allow_comments(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedEof
+        );
+
+        // An unclosed block comment is an error even after a complete
+        // value, reported where the comment opens.
+        for (input, offset) in [("1 /* x", 2), ("[1] /*", 4), ("/* x */ 1 /**", 10)] {
+            let err = JsonParser::new(input)
+                .allow_comments(true)
+                .parse()
+                .unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::UnexpectedEof, "{}", input);
+            assert_eq!(err.message(), "Unterminated block comment");
+            assert_eq!(err.offset(), Some(offset), "{}", input);
+        }
+
+        let mut parser = JsonParser::new("[1 / 2]").allow_comments(true);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
     }
 }
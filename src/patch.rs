@@ -0,0 +1,360 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::error::{ParseError, ParseErrorKind, Result};
+use crate::value::{array_index, pointer_tokens, JsonValue};
+
+/// Applies an RFC 6902 JSON Patch to `doc`.
+///
+/// `patch` must be an array of operation objects, each with an `op` of
+/// `add`, `remove`, `replace`, `move`, `copy` or `test` and a JSON Pointer
+/// `path` (plus `from` or `value` as the operation requires). Operations
+/// are applied in order. The patch is atomic: if any operation fails, `doc`
+/// is left unchanged and the error names the failing operation.
+///
+/// A malformed patch or a path that does not resolve fails with
+/// `ParseErrorKind::InvalidPatch`; a `test` whose value does not match fails
+/// with `ParseErrorKind::PatchTestFailed`. `test` compares numbers by value,
+/// so `1` matches `1.0`.
+///
+/// ```rust
+/// use arjp::{apply_patch, parse_json};
+///
+/// let mut doc = parse_json(r#"{"tags": ["a"]}"#).unwrap();
+/// let patch = parse_json(r#"[{"op": "add", "path": "/tags/-", "value": "b"}]"#).unwrap();
+/// apply_patch(&mut doc, &patch).unwrap();
+/// assert_eq!(doc, parse_json(r#"{"tags": ["a", "b"]}"#).unwrap());
+/// ```
+pub fn apply_patch(doc: &mut JsonValue, patch: &JsonValue) -> Result<()> {
+    let operations = patch
+        .as_array()
+        .ok_or_else(|| patch_error("Patch must be an array of operations"))?;
+    let mut patched = doc.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        apply_operation(&mut patched, operation)
+            .map_err(|err| ParseError::new(err.kind(), &format!("Operation {}: {}", index, err)))?;
+    }
+    *doc = patched;
+    Ok(())
+}
+
+/// Applies a single patch operation to `doc`.
+fn apply_operation(doc: &mut JsonValue, operation: &JsonValue) -> Result<()> {
+    let op = string_member(operation, "op")?;
+    let path = string_member(operation, "path")?;
+    match op {
+        "add" => add(doc, path, value_member(operation)?.clone()),
+        "remove" => remove(doc, path).map(|_| ()),
+        "replace" => {
+            let value = value_member(operation)?.clone();
+            *resolve(doc, path)? = value;
+            Ok(())
+        }
+        "move" => {
+            let from = string_member(operation, "from")?;
+            if from == path {
+                // A no-op, but `from` must still exist.
+                return resolve(doc, from).map(|_| ());
+            }
+            if path.starts_with(from) && path.as_bytes().get(from.len()) == Some(&b'/') {
+                return Err(patch_error(&format!(
+                    "Cannot move {:?} into its own child {:?}",
+                    from, path
+                )));
+            }
+            let value = remove(doc, from)?;
+            add(doc, path, value)
+        }
+        "copy" => {
+            let from = string_member(operation, "from")?;
+            let value = resolve(doc, from)?.clone();
+            add(doc, path, value)
+        }
+        "test" => {
+            // Numbers compare by value, so `1` matches `1.0` (RFC 6902 §4.6).
+            if resolve(doc, path)?.semantic_eq(value_member(operation)?) {
+                Ok(())
+            } else {
+                Err(ParseError::new(
+                    ParseErrorKind::PatchTestFailed,
+                    &format!("Test failed: value at {:?} does not match", path),
+                ))
+            }
+        }
+        other => Err(patch_error(&format!("Unknown operation {:?}", other))),
+    }
+}
+
+/// Builds a `ParseErrorKind::InvalidPatch` error.
+fn patch_error(message: &str) -> ParseError {
+    ParseError::new(ParseErrorKind::InvalidPatch, message)
+}
+
+/// Returns the string member `name` of an operation object.
+fn string_member<'v>(operation: &'v JsonValue, name: &str) -> Result<&'v str> {
+    operation
+        .get(name)
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| patch_error(&format!("Operation is missing string member {:?}", name)))
+}
+
+/// Returns the `value` member of an operation object.
+fn value_member(operation: &JsonValue) -> Result<&JsonValue> {
+    operation
+        .get("value")
+        .ok_or_else(|| patch_error("Operation is missing member \"value\""))
+}
+
+/// Resolves `path` to an existing value.
+fn resolve<'v>(doc: &'v mut JsonValue, path: &str) -> Result<&'v mut JsonValue> {
+    doc.pointer_mut(path)
+        .ok_or_else(|| patch_error(&format!("Path not found: {:?}", path)))
+}
+
+/// Splits a non-root `path` into the pointer to its parent and its
+/// unescaped last token. Returns `None` for the root pointer.
+fn split_path(path: &str) -> Result<Option<(&str, String)>> {
+    if path.is_empty() {
+        return Ok(None);
+    }
+    let slash = path
+        .rfind('/')
+        .ok_or_else(|| patch_error(&format!("Invalid path: {:?}", path)))?;
+    let token = pointer_tokens(&path[slash..])
+        .and_then(|mut tokens| tokens.next())
+        .ok_or_else(|| patch_error(&format!("Invalid path: {:?}", path)))?;
+    Ok(Some((&path[..slash], token)))
+}
+
+/// Adds `value` at `path`: replacing the root, setting an object member, or
+/// inserting into an array (`-` appends).
+fn add(doc: &mut JsonValue, path: &str, value: JsonValue) -> Result<()> {
+    let Some((parent, token)) = split_path(path)? else {
+        *doc = value;
+        return Ok(());
+    };
+    match resolve(doc, parent)? {
+        JsonValue::Object(map) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        JsonValue::Array(values) => {
+            let index = if token == "-" {
+                values.len()
+            } else {
+                array_index(&token)
+                    .filter(|&index| index <= values.len())
+                    .ok_or_else(|| patch_error(&format!("Invalid array index in {:?}", path)))?
+            };
+            values.insert(index, value);
+            Ok(())
+        }
+        _ => Err(patch_error(&format!(
+            "Parent of {:?} is not an object or array",
+            path
+        ))),
+    }
+}
+
+/// Removes and returns the value at `path`.
+fn remove(doc: &mut JsonValue, path: &str) -> Result<JsonValue> {
+    let (parent, token) =
+        split_path(path)?.ok_or_else(|| patch_error("Cannot remove the document root"))?;
+    let target = resolve(doc, parent)?;
+    let removed = match target {
+        JsonValue::Object(_) => target.remove(&token),
+        JsonValue::Array(values) => array_index(&token)
+            .filter(|&index| index < values.len())
+            .map(|index| values.remove(index)),
+        _ => None,
+    };
+    removed.ok_or_else(|| patch_error(&format!("Path not found: {:?}", path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+    use alloc::string::ToString;
+
+    fn patched(doc: &str, patch: &str) -> Result<JsonValue> {
+        let mut doc = parse_json(doc).unwrap();
+        apply_patch(&mut doc, &parse_json(patch).unwrap())?;
+        Ok(doc)
+    }
+
+    #[test]
+    fn test_add() {
+        let doc = r#"{"a": [1, 3]}"#;
+        let cases = [
+            (
+                r#"{"op": "add", "path": "/b", "value": 2}"#,
+                r#"{"a": [1, 3], "b": 2}"#,
+            ),
+            (
+                r#"{"op": "add", "path": "/a/1", "value": 2}"#,
+                r#"{"a": [1, 2, 3]}"#,
+            ),
+            (
+                r#"{"op": "add", "path": "/a/-", "value": 4}"#,
+                r#"{"a": [1, 3, 4]}"#,
+            ),
+            (
+                r#"{"op": "add", "path": "/a", "value": null}"#,
+                r#"{"a": null}"#,
+            ),
+            (r#"{"op": "add", "path": "", "value": true}"#, "true"),
+        ];
+        for (op, expected) in cases {
+            let patch = format!("[{}]", op);
+            assert_eq!(patched(doc, &patch).unwrap(), parse_json(expected).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_remove_and_replace() {
+        let doc = r#"{"a": [1, 2], "b~/c": 3}"#;
+        assert_eq!(
+            patched(doc, r#"[{"op": "remove", "path": "/a/0"}]"#).unwrap(),
+            parse_json(r#"{"a": [2], "b~/c": 3}"#).unwrap()
+        );
+        assert_eq!(
+            patched(doc, r#"[{"op": "remove", "path": "/b~0~1c"}]"#).unwrap(),
+            parse_json(r#"{"a": [1, 2]}"#).unwrap()
+        );
+        assert_eq!(
+            patched(doc, r#"[{"op": "replace", "path": "/a/1", "value": "x"}]"#).unwrap(),
+            parse_json(r#"{"a": [1, "x"], "b~/c": 3}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_move_and_copy() {
+        let doc = r#"{"a": {"b": 1}, "c": []}"#;
+        assert_eq!(
+            patched(doc, r#"[{"op": "move", "from": "/a/b", "path": "/c/0"}]"#).unwrap(),
+            parse_json(r#"{"a": {}, "c": [1]}"#).unwrap()
+        );
+        assert_eq!(
+            patched(doc, r#"[{"op": "copy", "from": "/a", "path": "/d"}]"#).unwrap(),
+            parse_json(r#"{"a": {"b": 1}, "c": [], "d": {"b": 1}}"#).unwrap()
+        );
+        let err = patched(doc, r#"[{"op": "move", "from": "/a", "path": "/a/x"}]"#).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidPatch);
+
+        // Moving a value onto itself changes nothing, but it must exist.
+        assert_eq!(
+            patched(doc, r#"[{"op": "move", "from": "/a", "path": "/a"}]"#).unwrap(),
+            parse_json(doc).unwrap()
+        );
+        let err =
+            patched(doc, r#"[{"op": "move", "from": "/nope", "path": "/nope"}]"#).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidPatch);
+        assert_eq!(err.to_string(), "Operation 0: Path not found: \"/nope\"");
+    }
+
+    #[test]
+    fn test_test_operation() {
+        let doc = r#"{"a": [1, "x"]}"#;
+        assert!(patched(doc, r#"[{"op": "test", "path": "/a/1", "value": "x"}]"#).is_ok());
+        let err = patched(doc, r#"[{"op": "test", "path": "/a/0", "value": 2}]"#).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::PatchTestFailed);
+        assert_eq!(
+            err.to_string(),
+            "Operation 0: Test failed: value at \"/a/0\" does not match"
+        );
+    }
+
+    #[test]
+    fn test_test_operation_compares_numbers_by_value() {
+        assert!(patched(
+            r#"{"a": 1}"#,
+            r#"[{"op": "test", "path": "/a", "value": 1.0}]"#
+        )
+        .is_ok());
+        assert!(patched(
+            r#"{"a": 1.0}"#,
+            r#"[{"op": "test", "path": "/a", "value": 1}]"#
+        )
+        .is_ok());
+        assert!(patched(
+            r#"{"a": [1, {"b": 2.50}]}"#,
+            r#"[{"op": "test", "path": "/a", "value": [1.0, {"b": 2.5}]}]"#
+        )
+        .is_ok());
+
+        let mut doc = crate::JsonParser::new(r#"{"a": 1.000}"#)
+            .with_arbitrary_precision(true)
+            .parse()
+            .unwrap();
+        let patch = parse_json(r#"[{"op": "test", "path": "/a", "value": 1}]"#).unwrap();
+        assert!(apply_patch(&mut doc, &patch).is_ok());
+
+        let err = patched(
+            r#"{"a": 1}"#,
+            r#"[{"op": "test", "path": "/a", "value": 1.5}]"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::PatchTestFailed);
+    }
+
+    #[test]
+    fn test_invalid_patches() {
+        let doc = r#"{"a": [1]}"#;
+        let cases = [
+            (r#"{}"#, "Patch must be an array of operations"),
+            (
+                r#"[{"path": "/a"}]"#,
+                "Operation 0: Operation is missing string member \"op\"",
+            ),
+            (
+                r#"[{"op": "jump", "path": "/a"}]"#,
+                "Operation 0: Unknown operation \"jump\"",
+            ),
+            (
+                r#"[{"op": "add", "path": "/a"}]"#,
+                "Operation 0: Operation is missing member \"value\"",
+            ),
+            (
+                r#"[{"op": "remove", "path": "/b"}]"#,
+                "Operation 0: Path not found: \"/b\"",
+            ),
+            (
+                r#"[{"op": "remove", "path": "/a/1"}]"#,
+                "Operation 0: Path not found: \"/a/1\"",
+            ),
+            (
+                r#"[{"op": "add", "path": "/a/5", "value": 0}]"#,
+                "Operation 0: Invalid array index in \"/a/5\"",
+            ),
+            (
+                r#"[{"op": "add", "path": "/x/y", "value": 0}]"#,
+                "Operation 0: Path not found: \"/x\"",
+            ),
+            (
+                r#"[{"op": "replace", "path": "a", "value": 0}]"#,
+                "Operation 0: Path not found: \"a\"",
+            ),
+        ];
+        for (patch, message) in cases {
+            let err = patched(doc, patch).unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::InvalidPatch);
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_failed_patch_leaves_document_unchanged() {
+        let mut doc = parse_json(r#"{"a": 1}"#).unwrap();
+        let original = doc.clone();
+        let patch = parse_json(
+            r#"[{"op": "add", "path": "/b", "value": 2},
+                {"op": "remove", "path": "/a"},
+                {"op": "test", "path": "/b", "value": 3}]"#,
+        )
+        .unwrap();
+        let err = apply_patch(&mut doc, &patch).unwrap_err();
+        assert!(err.to_string().starts_with("Operation 2: "));
+        assert_eq!(doc, original);
+    }
+}
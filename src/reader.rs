@@ -0,0 +1,828 @@
+use crate::error::{ParseError, ParseErrorKind, Result};
+use crate::parser::{DEFAULT_MAX_DEPTH, UTF8_BOM};
+use crate::value::{map_with_capacity, JsonValue};
+use std::io::Read;
+
+/// Default size of the internal read buffer.
+const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// JSON parser that pulls its input incrementally from a `std::io::Read`.
+///
+/// `JsonParser` borrows a complete in-memory document; this parser instead
+/// owns a reader and a fixed-size buffer, refilling it as tokens are consumed,
+/// so a large file never has to be loaded into memory as a whole. Tokens that
+/// straddle a buffer boundary are handled transparently. Create one with
+/// [`JsonParser::from_reader`](crate::JsonParser::from_reader) or
+/// [`ReaderParser::new`].
+///
+/// It parses strict JSON only. Of the `JsonParser` options it supports
+/// `with_max_depth`, `allow_control_characters` and `allow_bom`; lenient
+/// syntax, duplicate-key rejection, size limits and the other number modes
+/// are not available. To reuse a [`JsonParserBuilder`](crate::JsonParserBuilder)
+/// configuration, call its `build_reader`, which rejects any option outside
+/// that set instead of ignoring it.
+pub struct ReaderParser<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// Index of the next unread byte in `buffer`.
+    pos: usize,
+    /// Number of valid bytes in `buffer`.
+    len: usize,
+    /// Absolute input offset of `buffer[0]`.
+    consumed: usize,
+    /// One-based line number at `buffer[0]`.
+    line: usize,
+    /// Absolute input offset at which that line starts.
+    line_start: usize,
+    /// Offset up to which `line_of_string` has counted newlines, with the
+    /// line number and line start there.
+    mark: (usize, usize, usize),
+    depth: usize,
+    max_depth: usize,
+    allow_control_characters: bool,
+    allow_bom: bool,
+}
+
+impl<R: Read> ReaderParser<R> {
+    /// Creates a new parser reading from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self::with_buffer_size(reader, DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Creates a new parser reading from `reader` through a buffer of
+    /// `size` bytes (at least one).
+    pub fn with_buffer_size(reader: R, size: usize) -> Self {
+        ReaderParser {
+            reader,
+            buffer: vec![0; size.max(1)],
+            pos: 0,
+            len: 0,
+            consumed: 0,
+            line: 1,
+            line_start: 0,
+            mark: (0, 1, 0),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            allow_control_characters: false,
+            allow_bom: true,
+        }
+    }
+
+    /// Sets the maximum nesting depth of arrays and objects.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Controls whether raw control characters may appear unescaped inside
+    /// strings, as for [`JsonParser::allow_control_characters`](crate::JsonParser::allow_control_characters).
+    pub fn allow_control_characters(mut self, allow: bool) -> Self {
+        self.allow_control_characters = allow;
+        self
+    }
+
+    /// Controls whether a UTF-8 byte order mark at the start of the input is
+    /// skipped, as for [`JsonParser::allow_bom`](crate::JsonParser::allow_bom).
+    pub fn allow_bom(mut self, allow: bool) -> Self {
+        self.allow_bom = allow;
+        self
+    }
+
+    /// Builds an error of the given kind located at the current position.
+    fn error(&self, kind: ParseErrorKind, message: &str) -> ParseError {
+        ParseError::new(kind, message).with_offset(self.consumed + self.pos)
+    }
+
+    /// Builds an error of the given kind, or `UnexpectedEof` if the input
+    /// has run out, since more input could then still complete the value.
+    fn error_unless_eof(&mut self, kind: ParseErrorKind, message: &str) -> ParseError {
+        match self.peek() {
+            Ok(Some(_)) => self.error(kind, message),
+            Ok(None) => self.error(ParseErrorKind::UnexpectedEof, message),
+            Err(err) => err,
+        }
+    }
+
+    /// Returns the next byte without consuming it, refilling the buffer from
+    /// the reader when it runs dry. `None` means end of input.
+    #[inline]
+    fn peek(&mut self) -> Result<Option<u8>> {
+        if self.pos == self.len {
+            self.fill()?;
+        }
+        Ok(self.buffer[..self.len].get(self.pos).copied())
+    }
+
+    /// Adds the line and column to an error leaving `parse`, unless it
+    /// already has them.
+    ///
+    /// Only the current buffer is still available, but an offset before it
+    /// can only come from a token that straddles the boundary. The only
+    /// tokens that can contain a newline are strings, with
+    /// `allow_control_characters`, and `parse_string` locates its own errors;
+    /// any other offset is then still on the line the buffer starts on.
+    fn locate(&self, err: ParseError) -> ParseError {
+        let Some(offset) = err.offset().filter(|_| err.kind() != ParseErrorKind::Io) else {
+            return err;
+        };
+        if err.line().is_some() {
+            return err;
+        }
+        let (line, line_start) = self.line_at(offset);
+        err.with_location(line, offset.saturating_sub(line_start) + 1)
+    }
+
+    /// Returns the line number of `offset`, and the offset at which that
+    /// line starts, for an offset no later than the end of the buffer.
+    fn line_at(&self, offset: usize) -> (usize, usize) {
+        let before = &self.buffer[..offset.saturating_sub(self.consumed).min(self.len)];
+        match before.iter().rposition(|&b| b == b'\n') {
+            Some(i) => (
+                self.line + before.iter().filter(|&&b| b == b'\n').count(),
+                self.consumed + i + 1,
+            ),
+            None => (self.line, self.line_start),
+        }
+    }
+
+    /// Returns the line number of the string starting at `offset`, and the
+    /// offset at which that line starts.
+    ///
+    /// Strings start in the current buffer and in input order, so newlines
+    /// are only counted from the previous string's start, keeping this
+    /// linear over the whole input.
+    fn line_of_string(&mut self, offset: usize) -> (usize, usize) {
+        let (mut mark, mut line, mut line_start) = self.mark;
+        if mark < self.consumed {
+            (mark, line, line_start) = (self.consumed, self.line, self.line_start);
+        }
+        let from = mark - self.consumed;
+        let to = (offset - self.consumed).min(self.len);
+        for (i, &b) in self.buffer[from..to].iter().enumerate() {
+            if b == b'\n' {
+                line += 1;
+                line_start = mark + i + 1;
+            }
+        }
+        self.mark = (self.consumed + to, line, line_start);
+        (line, line_start)
+    }
+
+    /// Replaces the exhausted buffer with the next chunk from the reader.
+    fn fill(&mut self) -> Result<()> {
+        let chunk = &self.buffer[..self.len];
+        if let Some(i) = chunk.iter().rposition(|&b| b == b'\n') {
+            self.line += chunk.iter().filter(|&&b| b == b'\n').count();
+            self.line_start = self.consumed + i + 1;
+        }
+        self.consumed += self.len;
+        self.pos = 0;
+        self.len = 0;
+        loop {
+            match self.reader.read(&mut self.buffer) {
+                Ok(n) => {
+                    self.len = n;
+                    return Ok(());
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(ParseError::from(e).with_offset(self.consumed)),
+            }
+        }
+    }
+
+    /// Consumes and returns the next byte.
+    #[inline]
+    fn next_byte(&mut self) -> Result<Option<u8>> {
+        let b = self.peek()?;
+        if b.is_some() {
+            self.pos += 1;
+        }
+        Ok(b)
+    }
+
+    /// Skips JSON whitespace.
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Parses the complete input into a JsonValue.
+    pub fn parse(&mut self) -> Result<JsonValue> {
+        self.parse_document().map_err(|err| self.locate(err))
+    }
+
+    /// Parses the complete input, leaving errors unlocated.
+    fn parse_document(&mut self) -> Result<JsonValue> {
+        if self.allow_bom && self.consumed + self.pos == 0 {
+            self.skip_bom()?;
+        }
+        self.skip_whitespace()?;
+        let result = self.parse_value()?;
+        self.skip_whitespace()?;
+        if let Some(b) = self.peek()? {
+            return Err(self.error(
+                ParseErrorKind::TrailingData,
+                &format!(
+                    "Extra characters after JSON value, starting with '{}'",
+                    b as char
+                ),
+            ));
+        }
+        Ok(result)
+    }
+
+    /// Skips a byte order mark at the current position. No JSON value starts
+    /// with its first byte, so once that byte is seen the rest must follow.
+    fn skip_bom(&mut self) -> Result<()> {
+        if self.peek()? != Some(UTF8_BOM[0]) {
+            return Ok(());
+        }
+        for &expected in UTF8_BOM {
+            if self.peek()? != Some(expected) {
+                return Err(self.error(ParseErrorKind::UnexpectedChar, "Invalid byte order mark"));
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Parses any JSON value (null, boolean, string, number, array, or object).
+    fn parse_value(&mut self) -> Result<JsonValue> {
+        self.skip_whitespace()?;
+        match self.peek()? {
+            Some(b'n') => self.parse_literal("null", JsonValue::Null),
+            Some(b't') => self.parse_literal("true", JsonValue::Boolean(true)),
+            Some(b'f') => self.parse_literal("false", JsonValue::Boolean(false)),
+            Some(b'"') => self.parse_string().map(JsonValue::String),
+            Some(b'[') => self.parse_nested(Self::parse_array),
+            Some(b'{') => self.parse_nested(Self::parse_object),
+            Some(b'0'..=b'9') | Some(b'-') => self.parse_number(),
+            Some(b'+') | Some(b'.') => Err(self.error(
+                ParseErrorKind::InvalidNumber,
+                "Numbers must start with a digit or '-'",
+            )),
+            Some(b) => Err(self.error(
+                ParseErrorKind::UnexpectedChar,
+                &format!("Expected value, got '{}'", b as char),
+            )),
+            None => Err(self.error(ParseErrorKind::UnexpectedEof, "Unexpected end of input")),
+        }
+    }
+
+    /// Runs a container parser one nesting level deeper, enforcing `max_depth`.
+    fn parse_nested(&mut self, parse: fn(&mut Self) -> Result<JsonValue>) -> Result<JsonValue> {
+        if self.depth >= self.max_depth {
+            return Err(self.error(
+                ParseErrorKind::DepthLimitExceeded,
+                &format!("Nesting depth exceeds the limit of {}", self.max_depth),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Parses one of the `null`, `true` or `false` literals byte by byte.
+    ///
+    /// A mismatch or truncation is reported at the start of the literal, as
+    /// `JsonParser` does, rather than after the offending byte, which may be
+    /// the first byte of a multi-byte character.
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue> {
+        let start = self.consumed + self.pos;
+        for expected in literal.bytes() {
+            let (kind, message) = match self.next_byte()? {
+                Some(b) if b == expected => continue,
+                Some(_) => (ParseErrorKind::InvalidLiteral, "Invalid"),
+                None => (ParseErrorKind::UnexpectedEof, "Unexpected end of input in"),
+            };
+            return Err(
+                ParseError::new(kind, &format!("{} {} value", message, literal)).with_offset(start),
+            );
+        }
+        Ok(value)
+    }
+
+    /// Parses a JSON string, collecting raw bytes and validating each run
+    /// of them as UTF-8 when an escape or the closing quote ends it.
+    fn parse_string(&mut self) -> Result<String> {
+        self.next_byte()?; // Skip opening quote
+        let start = self.consumed + self.pos;
+        let start_line = self.line_of_string(start);
+        let mut bytes = Vec::with_capacity(16);
+        // Start of the current run of unescaped bytes, in `bytes` and in the
+        // input.
+        let mut run = (0, start);
+        // Input offsets of raw newlines, which `allow_control_characters`
+        // lets into the string.
+        let mut newlines = Vec::new();
+
+        loop {
+            match self.next_byte()? {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    Self::check_utf8(&bytes[run.0..], run.1, start_line, &newlines)?;
+                    let c = match self.next_byte()? {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{0008}',
+                        Some(b'f') => '\u{000C}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.parse_unicode_escape()?,
+                        Some(b) => {
+                            return Err(self.error(
+                                ParseErrorKind::InvalidEscape,
+                                &format!("Invalid escape sequence: \\{}", b as char),
+                            ))
+                        }
+                        None => {
+                            return Err(self.error(
+                                ParseErrorKind::UnterminatedString,
+                                "Unterminated string after escape",
+                            ))
+                        }
+                    };
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                    run = (bytes.len(), self.consumed + self.pos);
+                }
+                Some(b) if b < 0x20 && !self.allow_control_characters => {
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidControlChar,
+                        &format!("Unescaped control character U+{:04X} in string", b),
+                    )
+                    .with_offset(self.consumed + self.pos - 1))
+                }
+                Some(b) => {
+                    if b == b'\n' {
+                        newlines.push(self.consumed + self.pos - 1);
+                    }
+                    bytes.push(b);
+                }
+                None => {
+                    return Err(
+                        self.error(ParseErrorKind::UnterminatedString, "Unterminated string")
+                    )
+                }
+            }
+        }
+        Self::check_utf8(&bytes[run.0..], run.1, start_line, &newlines)?;
+        Ok(String::from_utf8(bytes).expect("every run and escape is valid UTF-8"))
+    }
+
+    /// Checks that a run of unescaped string bytes, starting at input offset
+    /// `offset`, is valid UTF-8.
+    ///
+    /// The run may begin before the current buffer and contain newlines, so
+    /// an error is located here from the line the string starts on and the
+    /// raw `newlines` seen in it.
+    fn check_utf8(
+        run: &[u8],
+        offset: usize,
+        (line, line_start): (usize, usize),
+        newlines: &[usize],
+    ) -> Result<()> {
+        let Err(e) = core::str::from_utf8(run) else {
+            return Ok(());
+        };
+        let offset = offset + e.valid_up_to();
+        let before = newlines.partition_point(|&n| n < offset);
+        let (line, line_start) = match before.checked_sub(1) {
+            Some(last) => (line + before, newlines[last] + 1),
+            None => (line, line_start),
+        };
+        Err(
+            ParseError::new(ParseErrorKind::InvalidUtf8, "Invalid UTF-8 in string")
+                .with_offset(offset)
+                .with_location(line, offset - line_start + 1),
+        )
+    }
+
+    /// Reads the four hex digits of a `\uXXXX` escape.
+    fn parse_hex4(&mut self) -> Result<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let b = self.next_byte()?.ok_or_else(|| {
+                self.error(
+                    ParseErrorKind::UnexpectedEof,
+                    "Incomplete unicode escape sequence",
+                )
+            })?;
+            let digit = (b as char).to_digit(16).ok_or_else(|| {
+                self.error(
+                    ParseErrorKind::InvalidEscape,
+                    "Invalid unicode escape sequence",
+                )
+            })?;
+            code = (code << 4) | digit;
+        }
+        Ok(code)
+    }
+
+    /// Decodes a `\uXXXX` escape (and a following low surrogate, if needed)
+    /// whose `\u` has already been consumed.
+    fn parse_unicode_escape(&mut self) -> Result<char> {
+        let code = self.parse_hex4()?;
+        let code = match code {
+            0xD800..=0xDBFF => {
+                // Reported just after the high surrogate, as `JsonParser`
+                // does, however much of the expected `\u` was read.
+                let after_high = self.consumed + self.pos;
+                let prefix = match self.next_byte()? {
+                    Some(b'\\') => self.next_byte()?.map(|b| b == b'u'),
+                    other => other.map(|_| false),
+                };
+                if prefix != Some(true) {
+                    let kind = match prefix {
+                        Some(_) => ParseErrorKind::InvalidUnicode,
+                        None => ParseErrorKind::UnexpectedEof,
+                    };
+                    return Err(ParseError::new(
+                        kind,
+                        &format!("Unpaired high surrogate: \\u{:04X}", code),
+                    )
+                    .with_offset(after_high));
+                }
+                let low = self.parse_hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error(
+                        ParseErrorKind::InvalidUnicode,
+                        &format!(
+                            "Invalid low surrogate \\u{:04X} after high surrogate \\u{:04X}",
+                            low, code
+                        ),
+                    ));
+                }
+                0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => {
+                return Err(self.error(
+                    ParseErrorKind::InvalidUnicode,
+                    &format!("Unpaired low surrogate: \\u{:04X}", code),
+                ))
+            }
+            _ => code,
+        };
+        char::from_u32(code).ok_or_else(|| {
+            self.error(
+                ParseErrorKind::InvalidUnicode,
+                &format!("Invalid unicode code point: {}", code),
+            )
+        })
+    }
+
+    /// Appends a run of ASCII digits to `num_str`, returning whether there
+    /// was at least one.
+    fn read_digits(&mut self, num_str: &mut String) -> Result<bool> {
+        let mut has_digits = false;
+        while let Some(b @ b'0'..=b'9') = self.peek()? {
+            num_str.push(b as char);
+            has_digits = true;
+            self.pos += 1;
+        }
+        Ok(has_digits)
+    }
+
+    /// Parses a JSON number, classifying it like `JsonParser` does.
+    fn parse_number(&mut self) -> Result<JsonValue> {
+        let mut num_str = String::with_capacity(16);
+        let mut is_integer = true;
+
+        if self.peek()? == Some(b'-') {
+            num_str.push('-');
+            self.pos += 1;
+        }
+        match self.peek()? {
+            Some(b'0') => {
+                num_str.push('0');
+                self.pos += 1;
+                if matches!(self.peek()?, Some(b'0'..=b'9')) {
+                    return Err(self.error(
+                        ParseErrorKind::InvalidNumber,
+                        "Leading zeros are not allowed",
+                    ));
+                }
+            }
+            Some(b'1'..=b'9') => {
+                self.read_digits(&mut num_str)?;
+            }
+            _ => {
+                return Err(self.error_unless_eof(
+                    ParseErrorKind::InvalidNumber,
+                    "Number must contain at least one digit",
+                ))
+            }
+        }
+        if self.peek()? == Some(b'.') {
+            is_integer = false;
+            num_str.push('.');
+            self.pos += 1;
+            if !self.read_digits(&mut num_str)? {
+                return Err(self.error_unless_eof(
+                    ParseErrorKind::InvalidNumber,
+                    "Decimal point must be followed by at least one digit",
+                ));
+            }
+        }
+        if let Some(e @ (b'e' | b'E')) = self.peek()? {
+            is_integer = false;
+            num_str.push(e as char);
+            self.pos += 1;
+            if let Some(sign @ (b'+' | b'-')) = self.peek()? {
+                num_str.push(sign as char);
+                self.pos += 1;
+            }
+            if !self.read_digits(&mut num_str)? {
+                return Err(self.error_unless_eof(
+                    ParseErrorKind::InvalidNumber,
+                    "Exponent must be followed by at least one digit",
+                ));
+            }
+        }
+        if is_integer && num_str != "-0" {
+            if let Ok(i) = num_str.parse::<i64>() {
+                return Ok(JsonValue::Integer(i));
+            }
+            if let Ok(u) = num_str.parse::<u64>() {
+                return Ok(JsonValue::Unsigned(u));
+            }
+        }
+        let f = num_str.parse::<f64>().map_err(|e| {
+            self.error(
+                ParseErrorKind::InvalidNumber,
+                &format!("Invalid number: {}", e),
+            )
+        })?;
+        if f.is_infinite() {
+            return Err(self.error(
+                ParseErrorKind::InvalidNumber,
+                &format!("Number {} is out of range", num_str),
+            ));
+        }
+        Ok(JsonValue::Number(f))
+    }
+
+    /// Parses a JSON array.
+    fn parse_array(&mut self) -> Result<JsonValue> {
+        self.pos += 1;
+        self.skip_whitespace()?;
+        let mut values = Vec::with_capacity(4);
+
+        if self.peek()? == Some(b']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace()?;
+            match self.next_byte()? {
+                Some(b']') => return Ok(JsonValue::Array(values)),
+                Some(b',') => {}
+                Some(b) => {
+                    self.pos -= 1;
+                    return Err(self.error(
+                        ParseErrorKind::UnexpectedChar,
+                        &format!("Expected comma or closing bracket, got '{}'", b as char),
+                    ));
+                }
+                None => return Err(self.error(ParseErrorKind::UnexpectedEof, "Unterminated array")),
+            }
+        }
+    }
+
+    /// Parses a JSON object.
+    fn parse_object(&mut self) -> Result<JsonValue> {
+        self.pos += 1;
+        self.skip_whitespace()?;
+        let mut map = map_with_capacity(4);
+
+        if self.peek()? == Some(b'}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(map));
+        }
+
+        loop {
+            self.skip_whitespace()?;
+            let key = match self.peek()? {
+                Some(b'"') => self.parse_string()?,
+                Some(_) => {
+                    return Err(
+                        self.error(ParseErrorKind::InvalidKey, "Object keys must be strings")
+                    )
+                }
+                None => {
+                    return Err(self.error(ParseErrorKind::UnexpectedEof, "Unterminated object"))
+                }
+            };
+            self.skip_whitespace()?;
+            if self.peek()? != Some(b':') {
+                return Err(self.error_unless_eof(
+                    ParseErrorKind::UnexpectedChar,
+                    "Expected colon after key in object",
+                ));
+            }
+            self.pos += 1;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+            self.skip_whitespace()?;
+            match self.next_byte()? {
+                Some(b'}') => return Ok(JsonValue::Object(map)),
+                Some(b',') => {}
+                Some(b) => {
+                    self.pos -= 1;
+                    return Err(self.error(
+                        ParseErrorKind::UnexpectedChar,
+                        &format!("Expected comma or closing brace, got '{}'", b as char),
+                    ));
+                }
+                None => {
+                    return Err(self.error(ParseErrorKind::UnexpectedEof, "Unterminated object"))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+
+    const DOCUMENT: &str = r#" {"name": "Zoë \"Z\" ☺ 😀", "id": 9007199254740993,
+        "scores": [1.5, -2e3, 0], "active": true, "spouse": null, "tags": [], "meta": {}} "#;
+
+    #[test]
+    fn test_parse_reader() {
+        let mut parser = ReaderParser::new(DOCUMENT.as_bytes());
+        assert_eq!(parser.parse().unwrap(), parse_json(DOCUMENT).unwrap());
+    }
+
+    #[test]
+    fn test_tokens_straddling_buffer_boundaries() {
+        let expected = parse_json(DOCUMENT).unwrap();
+        for size in 1..=16 {
+            let mut parser = ReaderParser::with_buffer_size(DOCUMENT.as_bytes(), size);
+            assert_eq!(parser.parse().unwrap(), expected, "buffer size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_reader_errors() {
+        let cases = [
+            ("[1, 2", ParseErrorKind::UnexpectedEof),
+            ("\"abc", ParseErrorKind::UnterminatedString),
+            ("nul", ParseErrorKind::UnexpectedEof),
+            ("nulx", ParseErrorKind::InvalidLiteral),
+            ("{1: 2}", ParseErrorKind::InvalidKey),
+            ("[1 2]", ParseErrorKind::UnexpectedChar),
+            ("1 2", ParseErrorKind::TrailingData),
+            ("-", ParseErrorKind::UnexpectedEof),
+            ("[-]", ParseErrorKind::InvalidNumber),
+            ("1.", ParseErrorKind::UnexpectedEof),
+            ("{\"a\"", ParseErrorKind::UnexpectedEof),
+            ("\"\\ud83d\\", ParseErrorKind::UnexpectedEof),
+            ("\"\\ud83dx\"", ParseErrorKind::InvalidUnicode),
+            ("01", ParseErrorKind::InvalidNumber),
+            ("[-007]", ParseErrorKind::InvalidNumber),
+            ("+5", ParseErrorKind::InvalidNumber),
+            (".5", ParseErrorKind::InvalidNumber),
+        ];
+        for (input, kind) in cases {
+            let mut parser = ReaderParser::with_buffer_size(input.as_bytes(), 2);
+            assert_eq!(parser.parse().unwrap_err().kind(), kind, "{}", input);
+        }
+
+        let mut parser = ReaderParser::with_buffer_size(&b"[1, 2 3]"[..], 3);
+        assert_eq!(parser.parse().unwrap_err().offset(), Some(6));
+
+        let mut parser = ReaderParser::with_buffer_size(&b"{} garbage"[..], 3);
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.offset(), Some(3));
+        assert_eq!(
+            err.to_string(),
+            "Extra characters after JSON value, starting with 'g' at line 1 column 4"
+        );
+    }
+
+    #[test]
+    fn test_reader_invalid_utf8() {
+        let mut parser = ReaderParser::with_buffer_size(&b"[\"ab\xFF\"]"[..], 2);
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidUtf8);
+        assert_eq!(err.offset(), Some(4));
+
+        // Offsets count source bytes past escapes, and lines count raw
+        // newlines in strings that began in an earlier buffer.
+        let inputs: [&[u8]; 3] = [
+            b"[\"\\n\\u00e9x\xFF\"]",
+            b"[\"a\nbc\nd\xFF\"]",
+            b"{\"k\": \"\n\\t\n\xC3\"}",
+        ];
+        for input in inputs {
+            let expected = crate::JsonParser::from_bytes(input)
+                .allow_control_characters(true)
+                .parse()
+                .unwrap_err();
+            for size in [1, 2, 3, 64] {
+                let mut parser =
+                    ReaderParser::with_buffer_size(input, size).allow_control_characters(true);
+                let err = parser.parse().unwrap_err();
+                assert_eq!(err.kind(), ParseErrorKind::InvalidUtf8);
+                assert_eq!(err.offset(), expected.offset(), "{:?}", input);
+                assert_eq!(err.line(), expected.line(), "{:?}", input);
+                assert_eq!(err.column(), expected.column(), "{:?}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reader_control_characters() {
+        let mut parser = ReaderParser::with_buffer_size(&b"[\"ab\tc\"]"[..], 2);
+        let err = parser.parse().unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidControlChar);
+        assert_eq!(err.offset(), Some(4));
+
+        let mut parser = ReaderParser::new(&b"\"ab\tc\""[..]).allow_control_characters(true);
+        assert_eq!(parser.parse().unwrap(), JsonValue::String("ab\tc".into()));
+    }
+
+    #[test]
+    fn test_reader_number_overflow() {
+        let mut parser = ReaderParser::new(&b"[1e400]"[..]);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::InvalidNumber
+        );
+    }
+
+    #[test]
+    fn test_reader_exponent_grammar() {
+        for input in ["1e0", "1E+5", "1e-3", "[1.2e3]"] {
+            let mut parser = ReaderParser::with_buffer_size(input.as_bytes(), 2);
+            assert!(parser.parse().is_ok(), "{}", input);
+        }
+        for input in ["1e", "1e+", "1e-", "1.2e"] {
+            let mut parser = ReaderParser::with_buffer_size(input.as_bytes(), 2);
+            let err = parser.parse().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::UnexpectedEof, "{}", input);
+            let array = format!("[{}]", input);
+            let mut parser = ReaderParser::with_buffer_size(array.as_bytes(), 2);
+            let err = parser.parse().unwrap_err();
+            assert_eq!(err.kind(), ParseErrorKind::InvalidNumber, "{}", input);
+        }
+        for input in [".", "-.", "[-.]"] {
+            let mut parser = ReaderParser::new(input.as_bytes());
+            assert_eq!(
+                parser.parse().unwrap_err().kind(),
+                ParseErrorKind::InvalidNumber,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_reader_byte_order_mark() {
+        let input = b"\xEF\xBB\xBF[1]";
+        for size in 1..=4 {
+            let mut parser = ReaderParser::with_buffer_size(&input[..], size);
+            assert_eq!(parser.parse().unwrap(), parse_json("[1]").unwrap());
+        }
+
+        let mut parser = ReaderParser::new(&input[..]).allow_bom(false);
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::UnexpectedChar
+        );
+        let mut parser = ReaderParser::new(&b"\xEF\xBB[1]"[..]);
+        assert_eq!(parser.parse().unwrap_err().offset(), Some(2));
+    }
+
+    #[test]
+    fn test_reader_multibyte_after_partial_literal() {
+        for (input, offset) in [("nu€", 0), ("[tru€]", 1), ("{\"a\": fals€}", 6)] {
+            for size in [1, 2, 8] {
+                let mut parser = ReaderParser::with_buffer_size(input.as_bytes(), size);
+                let err = parser.parse().unwrap_err();
+                assert_eq!(err.kind(), ParseErrorKind::InvalidLiteral, "{}", input);
+                assert_eq!(err.offset(), Some(offset), "{}", input);
+                assert_eq!(err.column(), Some(offset + 1), "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reader_max_depth() {
+        let input = "[".repeat(1000);
+        let mut parser = ReaderParser::new(input.as_bytes());
+        assert_eq!(
+            parser.parse().unwrap_err().kind(),
+            ParseErrorKind::DepthLimitExceeded
+        );
+    }
+}
@@ -0,0 +1,850 @@
+use crate::parser::JsonParser;
+use crate::value::JsonValue;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+/// Options controlling how a `JsonValue` is written as text.
+///
+/// The defaults produce compact, strictly valid JSON, which is also what the
+/// `Display` implementation emits.
+#[derive(Debug, Clone, Default)]
+pub struct SerializeOptions {
+    indent: Option<String>,
+    allow_non_finite_numbers: bool,
+    canonical: bool,
+    trim_integer_floats: bool,
+    float_notation: FloatNotation,
+    ascii_only: bool,
+    escape_forward_slashes: bool,
+}
+
+/// How `JsonValue::Number` floats are written by a [`SerializeOptions`].
+///
+/// Every notation writes the shortest digits that parse back to the same
+/// `f64`; they differ only in where the decimal point goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatNotation {
+    /// Plain decimals for moderate magnitudes and an exponent below `1e-4`
+    /// or from `1e16`, as in `0.5`, `30.0` and `1e300`.
+    #[default]
+    Shortest,
+    /// Always plain decimals, as in `0.0000001` and `1000000000000000000.0`.
+    /// Extreme magnitudes can produce hundreds of digits.
+    Fixed,
+    /// Always an exponent, as in `5e-1`, `3e1` and `1.5e300`.
+    Scientific,
+}
+
+impl SerializeOptions {
+    /// Creates options for compact, strictly valid JSON output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes multi-line output, repeating `indent` once per nesting level.
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = Some(indent.to_string());
+        self
+    }
+
+    /// Controls how NaN and the infinities are written.
+    ///
+    /// Strict JSON cannot represent them, so by default they are written as
+    /// `null`. When enabled they are written as the `NaN`, `Infinity` and
+    /// `-Infinity` tokens accepted by `JsonParser::allow_non_finite_numbers`.
+    pub fn allow_non_finite_numbers(mut self, allow: bool) -> Self {
+        self.allow_non_finite_numbers = allow;
+        self
+    }
+
+    /// Controls whether whole-valued floats drop their fractional part, so
+    /// that `JsonValue::Number(30.0)` is written as `30` rather than `30.0`.
+    ///
+    /// This changes what the output parses back to: `30` becomes
+    /// `JsonValue::Integer(30)`, not `JsonValue::Number(30.0)`, and `-0.0`
+    /// becomes `-0`, which parses as a float but may be read as `0` by other
+    /// tools. Floats written with an exponent are unaffected. Disabled by
+    /// default, so that every value round-trips to the same variant.
+    pub fn trim_integer_floats(mut self, trim: bool) -> Self {
+        self.trim_integer_floats = trim;
+        self
+    }
+
+    /// Chooses between plain and exponent notation for floats. Defaults to
+    /// [`FloatNotation::Shortest`].
+    pub fn float_notation(mut self, notation: FloatNotation) -> Self {
+        self.float_notation = notation;
+        self
+    }
+
+    /// Controls whether every non-ASCII character in strings and keys is
+    /// written as a `\uXXXX` escape, with a surrogate pair for characters
+    /// outside the Basic Multilingual Plane.
+    ///
+    /// The output is then pure ASCII and safe for transports that mangle
+    /// other bytes; it parses back to the same strings. Disabled by default,
+    /// so non-ASCII text is written directly as UTF-8.
+    pub fn ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    /// Controls whether every `/` in strings and keys is written as `\/`.
+    ///
+    /// JSON never requires the escape, but it keeps a `</script>` inside a
+    /// string from closing an HTML `<script>` block the JSON is embedded in.
+    /// The output parses back to the same strings. Disabled by default.
+    pub fn escape_forward_slashes(mut self, escape: bool) -> Self {
+        self.escape_forward_slashes = escape;
+        self
+    }
+}
+
+/// Writes values to a `fmt::Write` sink according to a set of options.
+struct Serializer<'a, W> {
+    out: W,
+    options: &'a SerializeOptions,
+}
+
+impl<W: Write> Serializer<'_, W> {
+    /// Writes a value nested `level` containers deep.
+    ///
+    /// Without an indent the output has no insignificant whitespace. With one,
+    /// every element goes on its own line; empty arrays and objects stay on
+    /// one line as `[]` and `{}`.
+    fn write_value(&mut self, value: &JsonValue, level: usize) -> fmt::Result {
+        match value {
+            JsonValue::Null => self.out.write_str("null"),
+            JsonValue::Boolean(b) => self.out.write_str(if *b { "true" } else { "false" }),
+            JsonValue::Integer(i) => write!(self.out, "{}", i),
+            JsonValue::Unsigned(u) => write!(self.out, "{}", u),
+            JsonValue::RawNumber(raw) if self.options.canonical => self.write_canonical_raw(raw),
+            JsonValue::RawNumber(raw) => self.out.write_str(raw),
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) if self.options.canonical => {
+                self.write_canonical_raw(&d.to_string())
+            }
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) => write!(self.out, "{}", d),
+            JsonValue::Number(f) if self.options.canonical && f.is_finite() => {
+                self.write_canonical_float(*f)
+            }
+            JsonValue::Number(f) => self.write_float(*f),
+            JsonValue::String(s) => self.write_string(s),
+            JsonValue::SharedString(s) => self.write_string(s),
+            JsonValue::Raw(raw) if self.options.canonical || self.options.indent.is_some() => {
+                self.write_reformatted_raw(raw, level)
+            }
+            JsonValue::Raw(raw) => self.out.write_str(raw),
+            JsonValue::Array(values) => {
+                self.out.write_char('[')?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        self.out.write_char(',')?;
+                    }
+                    self.write_newline(level + 1)?;
+                    self.write_value(v, level + 1)?;
+                }
+                if !values.is_empty() {
+                    self.write_newline(level)?;
+                }
+                self.out.write_char(']')
+            }
+            JsonValue::Object(map) if self.options.canonical => {
+                let mut members: Vec<_> = map.iter().collect();
+                members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+                self.write_object(members.into_iter(), level)
+            }
+            JsonValue::Object(map) => self.write_object(map.iter(), level),
+        }
+    }
+
+    /// Writes an object with the given members, in iteration order.
+    fn write_object<'v>(
+        &mut self,
+        members: impl Iterator<Item = (&'v String, &'v JsonValue)>,
+        level: usize,
+    ) -> fmt::Result {
+        self.out.write_char('{')?;
+        let mut empty = true;
+        for (k, v) in members {
+            if !empty {
+                self.out.write_char(',')?;
+            }
+            empty = false;
+            self.write_newline(level + 1)?;
+            self.write_string(k)?;
+            self.out.write_char(':')?;
+            if self.options.indent.is_some() {
+                self.out.write_char(' ')?;
+            }
+            self.write_value(v, level + 1)?;
+        }
+        if !empty {
+            self.write_newline(level)?;
+        }
+        self.out.write_char('}')
+    }
+
+    /// Starts a new line indented to the given nesting level, if pretty
+    /// printing is enabled.
+    fn write_newline(&mut self, level: usize) -> fmt::Result {
+        if let Some(indent) = &self.options.indent {
+            self.out.write_char('\n')?;
+            for _ in 0..level {
+                self.out.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a float so that it parses back to the same `JsonValue::Number`,
+    /// unless `trim_integer_floats` is set.
+    ///
+    /// The `Debug` representation is the shortest one that round-trips and
+    /// always carries a decimal point or exponent. `Display` is the same
+    /// digits without an exponent, but also without a fraction for whole
+    /// values.
+    fn write_float(&mut self, f: f64) -> fmt::Result {
+        if f.is_finite() {
+            let trim = self.options.trim_integer_floats;
+            match self.options.float_notation {
+                FloatNotation::Shortest if trim => {
+                    let text = alloc::format!("{:?}", f);
+                    self.out.write_str(text.strip_suffix(".0").unwrap_or(&text))
+                }
+                FloatNotation::Shortest => write!(self.out, "{:?}", f),
+                FloatNotation::Fixed if trim || f % 1.0 != 0.0 => write!(self.out, "{}", f),
+                FloatNotation::Fixed => write!(self.out, "{}.0", f),
+                FloatNotation::Scientific => write!(self.out, "{:e}", f),
+            }
+        } else if !self.options.allow_non_finite_numbers {
+            self.out.write_str("null")
+        } else if f.is_nan() {
+            self.out.write_str("NaN")
+        } else if f > 0.0 {
+            self.out.write_str("Infinity")
+        } else {
+            self.out.write_str("-Infinity")
+        }
+    }
+
+    /// Writes a finite float in the ECMAScript number format: the shortest
+    /// round-tripping digits, with no fraction for integral values and
+    /// exponent notation only below 1e-6 or from 1e21.
+    fn write_canonical_float(&mut self, f: f64) -> fmt::Result {
+        if f == 0.0 {
+            return self.out.write_char('0');
+        }
+        if f < 0.0 {
+            self.out.write_char('-')?;
+        }
+        // `{:e}` yields the shortest round-tripping digits as `d.ddde<exp>`.
+        let scientific = alloc::format!("{:e}", f.abs());
+        let (mantissa, exponent) = scientific.split_once('e').expect("`{:e}` has an exponent");
+        let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+        let exponent: i32 = exponent.parse().expect("`{:e}` exponent is an integer");
+        // Position of the decimal point relative to the start of `digits`.
+        let point = exponent + 1;
+        let len = digits.len() as i32;
+        if len <= point && point <= 21 {
+            self.out.write_str(&digits)?;
+            for _ in len..point {
+                self.out.write_char('0')?;
+            }
+            Ok(())
+        } else if 0 < point && point <= 21 {
+            let (int, frac) = digits.split_at(point as usize);
+            write!(self.out, "{}.{}", int, frac)
+        } else if -6 < point && point <= 0 {
+            self.out.write_str("0.")?;
+            for _ in point..0 {
+                self.out.write_char('0')?;
+            }
+            self.out.write_str(&digits)
+        } else {
+            let (first, rest) = digits.split_at(1);
+            self.out.write_str(first)?;
+            if !rest.is_empty() {
+                write!(self.out, ".{}", rest)?;
+            }
+            write!(
+                self.out,
+                "e{}{}",
+                if exponent > 0 { "+" } else { "-" },
+                exponent.abs()
+            )
+        }
+    }
+
+    /// Writes a raw number in canonical form. Integer literals are already
+    /// minimal and are kept exactly; anything else is formatted as the
+    /// nearest `f64`.
+    fn write_canonical_raw(&mut self, raw: &str) -> fmt::Result {
+        if raw == "-0" {
+            self.out.write_char('0')
+        } else if raw.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+            self.out.write_str(raw)
+        } else {
+            match raw.parse::<f64>() {
+                Ok(f) if f.is_finite() => self.write_canonical_float(f),
+                _ => self.out.write_str("null"),
+            }
+        }
+    }
+
+    /// Writes raw source text in the current format by parsing it first, so
+    /// that canonical and pretty output do not depend on how the source was
+    /// spelled. The parse accepts the syntax extensions `parse_partial` may
+    /// have skipped over; text that still fails to parse is written as is.
+    fn write_reformatted_raw(&mut self, raw: &str, level: usize) -> fmt::Result {
+        let parsed = JsonParser::new(raw)
+            .allow_comments(true)
+            .with_trailing_commas(true)
+            .allow_single_quotes(true)
+            .allow_unquoted_keys(true)
+            .allow_non_finite_numbers(true)
+            .allow_control_characters(true)
+            .parse();
+        match parsed {
+            Ok(value) => self.write_value(&value, level),
+            Err(_) => self.out.write_str(raw),
+        }
+    }
+
+    /// Writes a quoted string, escaping quotes, backslashes and control
+    /// characters, with `ascii_only` every non-ASCII character, and with
+    /// `escape_forward_slashes` every `/`.
+    fn write_string(&mut self, s: &str) -> fmt::Result {
+        self.out.write_char('"')?;
+        let mut start = 0;
+        for (i, c) in s.char_indices() {
+            let escape = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '/' if self.options.escape_forward_slashes => "\\/",
+                '\u{0008}' => "\\b",
+                '\u{000C}' => "\\f",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                // Canonical output only escapes what JSON requires.
+                c if c.is_control() && !(self.options.canonical && c > '\u{1F}') => "",
+                c if self.options.ascii_only && !c.is_ascii() => "",
+                _ => continue,
+            };
+            self.out.write_str(&s[start..i])?;
+            if escape.is_empty() {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(self.out, "\\u{:04x}", unit)?;
+                }
+            } else {
+                self.out.write_str(escape)?;
+            }
+            start = i + c.len_utf8();
+        }
+        self.out.write_str(&s[start..])?;
+        self.out.write_char('"')
+    }
+}
+
+/// Writes `value` to `out` according to `options`.
+pub(crate) fn write_value<W: Write>(
+    out: W,
+    value: &JsonValue,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    Serializer { out, options }.write_value(value, 0)
+}
+
+impl fmt::Display for JsonValue {
+    /// Formats the value as compact JSON.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self, &SerializeOptions::default())
+    }
+}
+
+impl JsonValue {
+    /// Serializes the value according to `options`.
+    pub fn to_string_with(&self, options: &SerializeOptions) -> String {
+        let mut out = String::new();
+        write_value(&mut out, self, options).expect("writing to a String cannot fail");
+        out
+    }
+
+    /// Serializes the value in this crate's canonical form, for signing and
+    /// hashing.
+    ///
+    /// Object members are sorted by the UTF-16 code units of their keys,
+    /// there is no insignificant whitespace, and floats use the shortest
+    /// ECMAScript form (`1.0` is written as `1`). Documents that differ only
+    /// in member order or float spelling produce identical output. Integers
+    /// are written exactly, even beyond the range of `f64`; NaN and the
+    /// infinities are written as `null`.
+    ///
+    /// This follows RFC 8785 (JCS) for keys and floats but is not JCS: JCS
+    /// rounds every number through `f64`, so `9007199254740993` becomes
+    /// `9007199254740992`, and rejects non-finite numbers. Output, and any
+    /// signature over it, only matches other JCS implementations for
+    /// documents without such numbers.
+    pub fn to_string_canonical(&self) -> String {
+        let options = SerializeOptions {
+            canonical: true,
+            ..SerializeOptions::default()
+        };
+        self.to_string_with(&options)
+    }
+
+    /// Returns a 64-bit hash of the value's canonical form, for caching and
+    /// change detection.
+    ///
+    /// The hash covers the same text as [`JsonValue::to_string_canonical`],
+    /// streamed through 64-bit FNV-1a without building the string, so it
+    /// ignores member order and float spelling and is the same on every
+    /// platform and across releases. A [`JsonValue::Raw`] is hashed as the
+    /// value its text parses to, so a document gets the same fingerprint from
+    /// `parse_partial` as from a full parse. It is not a cryptographic hash: compare
+    /// the documents themselves where collisions matter.
+    ///
+    /// ```rust
+    /// use arjp::parse_json;
+    ///
+    /// let a = parse_json(r#"{"x": 1, "y": [true, null]}"#).unwrap();
+    /// let b = parse_json("{ \"y\": [ true, null ], \"x\": 1.0 }").unwrap();
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let options = SerializeOptions {
+            canonical: true,
+            ..SerializeOptions::default()
+        };
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        write_value(&mut hasher, self, &options).expect("hashing cannot fail");
+        hasher.0
+    }
+
+    /// Serializes the value as human-readable JSON indented by `indent`
+    /// spaces per nesting level.
+    pub fn to_string_pretty(&self, indent: usize) -> String {
+        self.to_string_pretty_with(&" ".repeat(indent))
+    }
+
+    /// Serializes the value as human-readable JSON, repeating `indent` once
+    /// per nesting level. Use `"\t"` for tab indentation.
+    pub fn to_string_pretty_with(&self, indent: &str) -> String {
+        self.to_string_with(&SerializeOptions::new().indent(indent))
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64-bit FNV-1a hasher fed with serialized text by `fingerprint`.
+struct Fnv1a(u64);
+
+impl Write for Fnv1a {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &b in s.as_bytes() {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(FNV_PRIME);
+        }
+        Ok(())
+    }
+}
+
+/// Adapts an `io::Write` sink to `fmt::Write`, keeping the underlying I/O
+/// error that `fmt::Error` cannot carry.
+#[cfg(feature = "std")]
+struct IoWriter<W> {
+    inner: W,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl JsonValue {
+    /// Serializes the value as compact JSON straight into `writer`, without
+    /// building the whole string in memory first.
+    ///
+    /// Output is passed to `writer` in many small pieces, so wrap unbuffered
+    /// sinks such as a `File` or `TcpStream` in a `BufWriter`.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_to_with(writer, &SerializeOptions::default())
+    }
+
+    /// Like [`JsonValue::write_to`], but writes human-readable JSON indented
+    /// by `indent` spaces per nesting level.
+    pub fn write_to_pretty<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        indent: usize,
+    ) -> std::io::Result<()> {
+        self.write_to_with(writer, &SerializeOptions::new().indent(&" ".repeat(indent)))
+    }
+
+    /// Serializes the value into `writer` according to `options`.
+    pub fn write_to_with<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        options: &SerializeOptions,
+    ) -> std::io::Result<()> {
+        let mut out = IoWriter {
+            inner: writer,
+            error: None,
+        };
+        match write_value(&mut out, self, options) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => Err(out
+                .error
+                .unwrap_or_else(|| std::io::Error::other("formatter error"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Map;
+
+    #[test]
+    fn test_serialize_scalars() {
+        assert_eq!(JsonValue::Null.to_string(), "null");
+        assert_eq!(JsonValue::Boolean(true).to_string(), "true");
+        assert_eq!(JsonValue::Boolean(false).to_string(), "false");
+        assert_eq!(JsonValue::Integer(-42).to_string(), "-42");
+        assert_eq!(JsonValue::Number(1.5).to_string(), "1.5");
+        assert_eq!(JsonValue::Number(30.0).to_string(), "30.0");
+        assert_eq!(JsonValue::Number(1e300).to_string(), "1e300");
+        assert_eq!(JsonValue::Number(f64::NAN).to_string(), "null");
+    }
+
+    #[test]
+    fn test_serialize_string_escapes() {
+        let value = JsonValue::String("a\"b\\c\n\t\u{0001}\u{0008}\u{000C}\r/☺".to_string());
+        assert_eq!(value.to_string(), "\"a\\\"b\\\\c\\n\\t\\u0001\\b\\f\\r/☺\"");
+    }
+
+    #[test]
+    fn test_serialize_ascii_only() {
+        let value = crate::json!({"café": ["naïve ☺", "rocket 🚀", "plain"]});
+        let utf8 = value.to_string();
+        assert_eq!(utf8, r#"{"café":["naïve ☺","rocket 🚀","plain"]}"#);
+
+        let ascii = value.to_string_with(&SerializeOptions::new().ascii_only(true));
+        assert_eq!(
+            ascii,
+            r#"{"caf\u00e9":["na\u00efve \u263a","rocket \ud83d\ude80","plain"]}"#
+        );
+        assert!(ascii.is_ascii());
+
+        for text in [utf8, ascii] {
+            assert_eq!(crate::parse_json(&text).unwrap(), value, "{}", text);
+        }
+
+        let control = JsonValue::from("\u{0085}\t\u{0001}");
+        let options = SerializeOptions::new().ascii_only(true);
+        assert_eq!(control.to_string_with(&options), r#""\u0085\t\u0001""#);
+    }
+
+    #[test]
+    fn test_serialize_escape_forward_slashes() {
+        let value = crate::json!({"html": "</script><script>alert(1)</script>", "a/b": 1});
+        let plain = value.to_string_with(&SerializeOptions::new());
+        assert!(plain.contains("</script>"));
+        assert!(!plain.contains("\\/"));
+
+        let escaped = value.to_string_with(&SerializeOptions::new().escape_forward_slashes(true));
+        assert!(escaped.contains(r#""<\/script><script>alert(1)<\/script>""#));
+        assert!(escaped.contains(r#""a\/b":1"#));
+        assert!(!escaped.contains("</"));
+
+        for text in [plain, escaped] {
+            assert_eq!(crate::parse_json(&text).unwrap(), value, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_serialize_containers() {
+        let value = JsonValue::Array(vec![
+            JsonValue::Integer(1),
+            JsonValue::Array(vec![]),
+            JsonValue::Object(Map::new()),
+        ]);
+        assert_eq!(value.to_string(), "[1,[],{}]");
+
+        let mut map = Map::new();
+        map.insert("key".to_string(), JsonValue::Null);
+        assert_eq!(JsonValue::Object(map).to_string(), "{\"key\":null}");
+    }
+
+    #[test]
+    fn test_serialize_pretty() {
+        let value = JsonValue::Array(vec![
+            JsonValue::Integer(1),
+            JsonValue::Array(vec![JsonValue::Boolean(true), JsonValue::Null]),
+            JsonValue::Array(vec![]),
+            JsonValue::Object(Map::new()),
+        ]);
+        assert_eq!(
+            value.to_string_pretty(2),
+            "[\n  1,\n  [\n    true,\n    null\n  ],\n  [],\n  {}\n]"
+        );
+    }
+
+    #[test]
+    fn test_serialize_pretty_object() {
+        let mut inner = Map::new();
+        inner.insert("b".to_string(), JsonValue::Integer(2));
+        let mut map = Map::new();
+        map.insert("a".to_string(), JsonValue::Object(inner));
+        let value = JsonValue::Object(map);
+        assert_eq!(
+            value.to_string_pretty(4),
+            "{\n    \"a\": {\n        \"b\": 2\n    }\n}"
+        );
+        assert_eq!(
+            value.to_string_pretty_with("\t"),
+            "{\n\t\"a\": {\n\t\t\"b\": 2\n\t}\n}"
+        );
+    }
+
+    #[test]
+    fn test_serialize_pretty_scalar() {
+        assert_eq!(JsonValue::Integer(5).to_string_pretty(2), "5");
+        assert_eq!(JsonValue::Array(vec![]).to_string_pretty(2), "[]");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_to() {
+        let value = crate::parse_json(r#"{"a": [1, 2.5, "x\ny"], "b": {}, "c": null}"#).unwrap();
+        let mut out = Vec::new();
+        value.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), value.to_string());
+
+        let mut out = Vec::new();
+        value.write_to_pretty(&mut out, 2).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), value.to_string_pretty(2));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_to_reports_io_error() {
+        let mut buf = [0u8; 4];
+        let mut out = &mut buf[..];
+        let error = JsonValue::from("too long to fit")
+            .write_to(&mut out)
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_serialize_trim_integer_floats() {
+        let trim = SerializeOptions::new().trim_integer_floats(true);
+        let cases = [
+            (30.0, "30.0", "30"),
+            (0.5, "0.5", "0.5"),
+            (-0.0, "-0.0", "-0"),
+            (1e15, "1000000000000000.0", "1000000000000000"),
+            (1e16, "1e16", "1e16"),
+            (1e300, "1e300", "1e300"),
+            (1.5e-7, "1.5e-7", "1.5e-7"),
+        ];
+        for (f, plain, trimmed) in cases {
+            let value = JsonValue::Number(f);
+            assert_eq!(value.to_string(), plain);
+            assert_eq!(value.to_string_with(&trim), trimmed);
+        }
+        // Trimmed whole floats read back as integers.
+        let text = JsonValue::Number(30.0).to_string_with(&trim);
+        assert_eq!(crate::parse_json(&text).unwrap(), JsonValue::Integer(30));
+    }
+
+    #[test]
+    fn test_serialize_float_notation() {
+        let fixed = SerializeOptions::new().float_notation(FloatNotation::Fixed);
+        let scientific = SerializeOptions::new().float_notation(FloatNotation::Scientific);
+        let cases = [
+            (30.0, "30.0", "3e1"),
+            (0.5, "0.5", "5e-1"),
+            (-2.25, "-2.25", "-2.25e0"),
+            (1e20, "100000000000000000000.0", "1e20"),
+            (1.5e-7, "0.00000015", "1.5e-7"),
+        ];
+        for (f, fixed_text, scientific_text) in cases {
+            let value = JsonValue::Number(f);
+            assert_eq!(value.to_string_with(&fixed), fixed_text);
+            assert_eq!(value.to_string_with(&scientific), scientific_text);
+            for text in [fixed_text, scientific_text] {
+                assert_eq!(crate::parse_json(text).unwrap(), value, "{}", text);
+            }
+        }
+        assert_eq!(
+            JsonValue::Number(1e300).to_string_with(&fixed).len(),
+            "1".len() + 300 + ".0".len()
+        );
+        assert_eq!(
+            JsonValue::Number(30.0).to_string_with(&fixed.trim_integer_floats(true)),
+            "30"
+        );
+    }
+
+    #[test]
+    fn test_serialize_non_finite() {
+        let value = JsonValue::Array(vec![
+            JsonValue::Number(f64::NAN),
+            JsonValue::Number(f64::INFINITY),
+            JsonValue::Number(f64::NEG_INFINITY),
+        ]);
+        assert_eq!(value.to_string(), "[null,null,null]");
+        assert_eq!(
+            value.to_string_with(&SerializeOptions::new().allow_non_finite_numbers(true)),
+            "[NaN,Infinity,-Infinity]"
+        );
+    }
+
+    #[test]
+    fn test_serialize_canonical_numbers() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-2.5, "-2.5"),
+            (100.0, "100"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (1.5e300, "1.5e+300"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (-1.25e-10, "-1.25e-10"),
+            (0.1, "0.1"),
+            (123.456, "123.456"),
+            (f64::NAN, "null"),
+        ];
+        for (f, expected) in cases {
+            assert_eq!(JsonValue::Number(f).to_string_canonical(), expected);
+        }
+        assert_eq!(
+            JsonValue::Unsigned(u64::MAX).to_string_canonical(),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            JsonValue::RawNumber("1.50".to_string()).to_string_canonical(),
+            "1.5"
+        );
+        assert_eq!(
+            JsonValue::RawNumber("-0".to_string()).to_string_canonical(),
+            "0"
+        );
+        assert_eq!(
+            JsonValue::RawNumber("123456789012345678901234".to_string()).to_string_canonical(),
+            "123456789012345678901234"
+        );
+        // Unlike RFC 8785, integers are not rounded through `f64`.
+        assert_eq!(
+            JsonValue::Integer(9007199254740993).to_string_canonical(),
+            "9007199254740993"
+        );
+    }
+
+    #[test]
+    fn test_serialize_canonical_ordering() {
+        let a =
+            crate::parse_json(r#"{"b": [1.0, {"z": null, "y": "\u007f"}], "a": true}"#).unwrap();
+        let b =
+            crate::parse_json(r#"{ "a" : true, "b" : [1, {"y": "\u007F", "z": null}] }"#).unwrap();
+        assert_eq!(
+            a.to_string_canonical(),
+            "{\"a\":true,\"b\":[1,{\"y\":\"\u{7f}\",\"z\":null}]}"
+        );
+        assert_eq!(a.to_string_canonical(), b.to_string_canonical());
+
+        // U+FF61 sorts before U+1F600 by code point but after it by UTF-16
+        // code unit, since the latter is a surrogate pair starting 0xD83D.
+        let value = crate::parse_json(r#"{"\uff61": 1, "\ud83d\ude00": 2, "a": 3}"#).unwrap();
+        assert_eq!(
+            value.to_string_canonical(),
+            "{\"a\":3,\"\u{1F600}\":2,\"\u{FF61}\":1}"
+        );
+    }
+
+    #[test]
+    fn test_serialize_raw() {
+        let input = r#"{"a":1,"b":{"y": 1, "x": 2},"c":[ 1,2 ]}"#;
+        let full = crate::parse_json(input).unwrap();
+        let partial = crate::JsonParser::new(input)
+            .parse_partial(&["/a"])
+            .unwrap();
+        assert!(matches!(partial["b"], JsonValue::Raw(_)));
+
+        // Compact output forwards raw text; canonical and pretty output
+        // format it like a parsed value.
+        assert_eq!(partial["b"].to_string(), r#"{"y": 1, "x": 2}"#);
+        assert_eq!(partial.to_string_canonical(), full.to_string_canonical());
+        assert_eq!(
+            partial.to_string_canonical(),
+            r#"{"a":1,"b":{"x":2,"y":1},"c":[1,2]}"#
+        );
+        assert_eq!(partial["c"].to_string(), "[ 1,2 ]");
+        assert_eq!(partial["c"].to_string_pretty(2), "[\n  1,\n  2\n]");
+
+        // Lenient syntax skipped by `parse_partial` is normalized too.
+        let raw = JsonValue::Raw("{'b': [1, 2,], /* c */ a: NaN}".to_string());
+        assert_eq!(raw.to_string_canonical(), r#"{"a":null,"b":[1,2]}"#);
+
+        // Text that is not JSON is the caller's responsibility and is
+        // written unchanged in every format.
+        let raw = JsonValue::Raw("not json".to_string());
+        assert_eq!(raw.to_string(), "not json");
+        assert_eq!(raw.to_string_canonical(), "not json");
+        assert_eq!(raw.to_string_pretty(2), "not json");
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let base = crate::parse_json(r#"{"a": {"x": 1, "y": [1, 2]}, "b": "text"}"#).unwrap();
+        let same = [
+            r#"{"b": "text", "a": {"y": [1, 2], "x": 1}}"#,
+            "{\n  \"a\" : { \"x\" : 1.0 , \"y\" : [ 1 , 2 ] } ,\n  \"b\" : \"text\"\n}",
+            r#"{"a": {"x": 1e0, "y": [1, 2]}, "b": "\u0074ext"}"#,
+        ];
+        for input in same {
+            let value = crate::parse_json(input).unwrap();
+            assert_eq!(value.fingerprint(), base.fingerprint(), "{}", input);
+        }
+        let different = [
+            r#"{"a": {"x": 2, "y": [1, 2]}, "b": "text"}"#,
+            r#"{"a": {"x": 1, "y": [2, 1]}, "b": "text"}"#,
+            r#"{"a": {"x": 1, "y": [1, 2]}, "b": "Text"}"#,
+            r#"{"a": {"x": 1, "y": [1, 2]}, "c": "text"}"#,
+            r#"{"a": {"x": 1, "y": [1, 2]}}"#,
+            r#"{"a": {"x": "1", "y": [1, 2]}, "b": "text"}"#,
+        ];
+        for input in different {
+            let value = crate::parse_json(input).unwrap();
+            assert_ne!(value.fingerprint(), base.fingerprint(), "{}", input);
+        }
+
+        // Raw values from `parse_partial` hash like their parsed form.
+        for input in same {
+            for pointers in [&[][..], &["/a/x"], &["/b"]] {
+                let value = crate::JsonParser::new(input)
+                    .parse_partial(pointers)
+                    .unwrap();
+                assert_eq!(value.fingerprint(), base.fingerprint(), "{}", input);
+            }
+        }
+
+        // The hash is pinned, so stored fingerprints stay valid.
+        assert_eq!(JsonValue::Null.fingerprint(), 0x5b9b_c4ba_5281_08e4);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::value::{map_with_capacity, JsonValue};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
+
+impl Serialize for JsonValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JsonValue::Null => serializer.serialize_unit(),
+            JsonValue::Boolean(b) => serializer.serialize_bool(*b),
+            JsonValue::Integer(i) => serializer.serialize_i64(*i),
+            JsonValue::Unsigned(u) => serializer.serialize_u64(*u),
+            JsonValue::Number(f) => serializer.serialize_f64(*f),
+            // Serde has no portable arbitrary-precision number, so raw numbers
+            // go out as the closest native type.
+            JsonValue::RawNumber(raw) => {
+                if let Ok(i) = raw.parse::<i64>() {
+                    serializer.serialize_i64(i)
+                } else if let Ok(u) = raw.parse::<u64>() {
+                    serializer.serialize_u64(u)
+                } else {
+                    serializer.serialize_f64(raw.parse().unwrap_or(f64::NAN))
+                }
+            }
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) => {
+                use rust_decimal::prelude::ToPrimitive;
+                match d.to_i64().filter(|_| d.fract().is_zero()) {
+                    Some(i) => serializer.serialize_i64(i),
+                    None => serializer.serialize_f64(d.to_f64().unwrap_or(f64::NAN)),
+                }
+            }
+            JsonValue::String(s) => serializer.serialize_str(s),
+            JsonValue::SharedString(s) => serializer.serialize_str(s),
+            JsonValue::Array(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            JsonValue::Object(map) => {
+                let mut out = serializer.serialize_map(Some(map.len()))?;
+                for (key, value) in map {
+                    out.serialize_entry(key, value)?;
+                }
+                out.end()
+            }
+            // Other formats cannot take the text verbatim, so it is parsed.
+            JsonValue::Raw(raw) => crate::parse_json(raw)
+                .map_err(ser::Error::custom)?
+                .serialize(serializer),
+        }
+    }
+}
+
+/// Maps the serde data model onto `JsonValue` variants.
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = JsonValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JsonValue, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<JsonValue, E> {
+        Ok(JsonValue::Boolean(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Integer(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<JsonValue, E> {
+        Ok(JsonValue::from(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(JsonValue::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<JsonValue, A::Error> {
+        let mut map = map_with_capacity(access.size_hint().unwrap_or(0));
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+        Ok(JsonValue::Object(map))
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<JsonValue, D::Error> {
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+
+    fn round_trip(value: &JsonValue) -> JsonValue {
+        let text = serde_json::to_string(value).unwrap();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let value = parse_json(
+            r#"{"name": "Zoë", "age": 30, "score": -1.5, "tags": ["a", null, true], "meta": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn test_serde_scalars() {
+        for value in [
+            JsonValue::Null,
+            JsonValue::Boolean(false),
+            JsonValue::Integer(i64::MIN),
+            JsonValue::Number(0.25),
+            JsonValue::String("\"quoted\"\n".to_string()),
+            JsonValue::Array(vec![]),
+        ] {
+            assert_eq!(round_trip(&value), value);
+        }
+    }
+
+    #[test]
+    fn test_serde_large_unsigned() {
+        let value: JsonValue = serde_json::from_str("18446744073709551615").unwrap();
+        assert_eq!(value, JsonValue::Unsigned(u64::MAX));
+        assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn test_serde_embedded_in_struct() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Envelope {
+            kind: String,
+            payload: JsonValue,
+        }
+
+        let envelope = Envelope {
+            kind: "event".to_string(),
+            payload: parse_json(r#"{"ids": [1, 2]}"#).unwrap(),
+        };
+        let text = serde_json::to_string(&envelope).unwrap();
+        assert_eq!(text, r#"{"kind":"event","payload":{"ids":[1,2]}}"#);
+        assert_eq!(serde_json::from_str::<Envelope>(&text).unwrap(), envelope);
+    }
+}
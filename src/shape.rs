@@ -0,0 +1,191 @@
+use crate::value::JsonValue;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// The expected structure of a value, checked by [`JsonValue::matches_shape`].
+///
+/// This is a structural type check rather than JSON Schema: it says which
+/// type each value must have and which members an object must contain, but
+/// nothing about ranges, lengths or formats.
+///
+/// ```rust
+/// use arjp::{json, Shape};
+///
+/// let user = Shape::object([
+///     ("id", Shape::Integer),
+///     ("name", Shape::String),
+///     ("tags", Shape::array(Shape::String)),
+///     ("email", Shape::optional(Shape::String)),
+/// ]);
+/// assert!(json!({"id": 1, "name": "John", "tags": ["admin"]}).matches_shape(&user));
+/// assert!(!json!({"id": "1", "name": "John", "tags": []}).matches_shape(&user));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// Any value at all.
+    Any,
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Boolean,
+    /// A number of any variant.
+    Number,
+    /// A number with no fractional part that fits in an `i64` or a `u64`,
+    /// as accepted by [`JsonValue::as_i64`] and [`JsonValue::as_u64`].
+    Integer,
+    /// A string, whether `String` or `SharedString`.
+    String,
+    /// An array whose elements all match the inner shape.
+    Array(Box<Shape>),
+    /// An object containing each listed key with a value of the paired
+    /// shape. Members not listed are allowed and not checked.
+    Object(Vec<(String, Shape)>),
+    /// `null` or the inner shape; as an object member, the key may also be
+    /// missing.
+    Optional(Box<Shape>),
+}
+
+impl Shape {
+    /// Creates a [`Shape::Array`] whose elements match `element`.
+    pub fn array(element: Shape) -> Self {
+        Shape::Array(Box::new(element))
+    }
+
+    /// Creates a [`Shape::Object`] from `(key, shape)` pairs.
+    pub fn object<'k>(members: impl IntoIterator<Item = (&'k str, Shape)>) -> Self {
+        Shape::Object(
+            members
+                .into_iter()
+                .map(|(key, shape)| (key.to_string(), shape))
+                .collect(),
+        )
+    }
+
+    /// Creates a [`Shape::Optional`] around `shape`.
+    pub fn optional(shape: Shape) -> Self {
+        Shape::Optional(Box::new(shape))
+    }
+}
+
+impl JsonValue {
+    /// Returns `true` if this value has the structure described by `shape`.
+    ///
+    /// Containers are checked recursively. A [`JsonValue::Raw`] only matches
+    /// [`Shape::Any`], since its type is not known without parsing it.
+    pub fn matches_shape(&self, shape: &Shape) -> bool {
+        match shape {
+            Shape::Any => true,
+            Shape::Null => self.is_null(),
+            Shape::Boolean => self.as_bool().is_some(),
+            Shape::Number => self.as_f64().is_some(),
+            Shape::Integer => self.as_i64().is_some() || self.as_u64().is_some(),
+            Shape::String => self.as_str().is_some(),
+            Shape::Array(element) => self
+                .as_array()
+                .is_some_and(|values| values.iter().all(|v| v.matches_shape(element))),
+            Shape::Object(members) => self.as_object().is_some_and(|map| {
+                members.iter().all(|(key, shape)| match map.get(key) {
+                    Some(value) => value.matches_shape(shape),
+                    None => matches!(shape, Shape::Optional(_) | Shape::Any),
+                })
+            }),
+            Shape::Optional(inner) => self.is_null() || self.matches_shape(inner),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_shape() -> Shape {
+        Shape::object([
+            ("id", Shape::Integer),
+            ("name", Shape::String),
+            ("active", Shape::Boolean),
+            ("score", Shape::Number),
+            ("tags", Shape::array(Shape::String)),
+            (
+                "address",
+                Shape::object([
+                    ("city", Shape::String),
+                    ("zip", Shape::optional(Shape::String)),
+                ]),
+            ),
+            ("note", Shape::optional(Shape::String)),
+            ("extra", Shape::Any),
+        ])
+    }
+
+    #[test]
+    fn test_matches_shape_well_formed() {
+        let shape = record_shape();
+        let record = json!({
+            "id": 7,
+            "name": "John",
+            "active": true,
+            "score": 9.5,
+            "tags": ["a", "b"],
+            "address": {"city": "Rome", "zip": null},
+            "extra": [1, {"x": null}],
+            "unlisted": "ignored"
+        });
+        assert!(record.matches_shape(&shape));
+
+        // Whole floats count as integers, empty arrays match any element
+        // shape, and optional members may be missing or present.
+        let record = json!({
+            "id": 7.0,
+            "name": "John",
+            "active": false,
+            "score": 18446744073709551615u64,
+            "tags": [],
+            "address": {"city": "Rome"},
+            "note": "hi"
+        });
+        assert!(record.matches_shape(&shape));
+    }
+
+    #[test]
+    fn test_matches_shape_malformed() {
+        let shape = record_shape();
+        let valid = json!({
+            "id": 7,
+            "name": "John",
+            "active": true,
+            "score": 9.5,
+            "tags": ["a"],
+            "address": {"city": "Rome"}
+        });
+        assert!(valid.matches_shape(&shape));
+
+        let breakages: [(&str, JsonValue); 7] = [
+            ("/id", json!(7.5)),
+            ("/name", json!(null)),
+            ("/active", json!("yes")),
+            ("/score", json!("9.5")),
+            ("/tags/0", json!(1)),
+            ("/address/city", json!(["Rome"])),
+            ("/address/zip", json!(12345)),
+        ];
+        for (pointer, replacement) in breakages {
+            let mut record = valid.clone();
+            match record.pointer_mut(pointer) {
+                Some(slot) => *slot = replacement,
+                None => {
+                    let (parent, key) = pointer.rsplit_once('/').unwrap();
+                    record.pointer_mut(parent).unwrap().insert(key, replacement);
+                }
+            }
+            assert!(!record.matches_shape(&shape), "{}", pointer);
+        }
+
+        let mut missing = valid.clone();
+        missing.remove("name");
+        assert!(!missing.matches_shape(&shape));
+        assert!(!json!([valid]).matches_shape(&shape));
+        assert!(!JsonValue::Raw("{}".into()).matches_shape(&Shape::object([])));
+        assert!(JsonValue::Raw("{}".into()).matches_shape(&Shape::Any));
+    }
+}
@@ -0,0 +1,257 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::value::{JsonValue, Map};
+
+/// The map type backing `SharedValue::Object`.
+///
+/// Like [`Map`], this is a `HashMap` by default, an insertion-ordered
+/// `IndexMap` with the `preserve_order` feature and a `BTreeMap` without the
+/// `std` feature.
+#[cfg(all(feature = "std", not(feature = "preserve_order")))]
+pub type SharedMap = std::collections::HashMap<String, SharedValue>;
+
+/// `SharedMap` used without the `std` feature.
+#[cfg(not(feature = "std"))]
+pub type SharedMap = alloc::collections::BTreeMap<String, SharedValue>;
+
+/// Insertion-ordered `SharedMap` used when the `preserve_order` feature is
+/// enabled.
+#[cfg(feature = "preserve_order")]
+pub type SharedMap = indexmap::IndexMap<String, SharedValue>;
+
+/// A JSON value whose arrays and objects are reference counted, so that
+/// cloning it never copies a subtree.
+///
+/// Cloning a `JsonValue` copies the whole tree. Cloning a `SharedValue`
+/// only bumps the count of its outermost container, and the clone shares
+/// everything below with the original. Mutation goes through
+/// [`SharedValue::as_array_mut`] and [`SharedValue::as_object_mut`], which
+/// copy a container first if it is shared: the copy holds the same
+/// reference-counted children, so only the containers on the path to the
+/// change are duplicated and the rest stays shared. This suits layered
+/// configuration, where many overlays differ from a base in a few places.
+///
+/// ```rust
+/// use arjp::{json, SharedValue};
+///
+/// let base = SharedValue::from(json!({"db": {"host": "localhost"}, "log": {"level": "info"}}));
+/// let mut overlay = base.clone();
+/// let log = overlay.as_object_mut().unwrap().get_mut("log").unwrap();
+/// log.as_object_mut().unwrap().insert("level".into(), "debug".into());
+///
+/// assert_eq!(base.into_owned()["log"]["level"], json!("info"));
+/// assert_eq!(overlay.into_owned()["log"]["level"], json!("debug"));
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub enum SharedValue {
+    /// The JSON null value.
+    Null,
+    /// A JSON boolean value (true or false).
+    Boolean(bool),
+    /// A JSON integer that fits in a signed 64-bit integer.
+    Integer(i64),
+    /// A JSON integer above `i64::MAX` that fits in an unsigned 64-bit
+    /// integer.
+    Unsigned(u64),
+    /// Any other JSON number, stored as a 64-bit float.
+    Number(f64),
+    /// A JSON number kept as its exact source text.
+    RawNumber(String),
+    /// A JSON number stored as an exact decimal.
+    #[cfg(feature = "decimal")]
+    Decimal(rust_decimal::Decimal),
+    /// A JSON string value.
+    String(String),
+    /// A JSON array, shared between clones until one of them changes it.
+    Array(Arc<Vec<SharedValue>>),
+    /// A JSON object, shared between clones until one of them changes it.
+    Object(Arc<SharedMap>),
+    /// Unparsed source text carried over from a [`JsonValue::Raw`].
+    Raw(String),
+}
+
+impl SharedValue {
+    /// Returns the elements if the value is an array.
+    pub fn as_array(&self) -> Option<&Vec<SharedValue>> {
+        match self {
+            SharedValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements for modification if the value is an array,
+    /// first copying the array if it is shared with another value.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<SharedValue>> {
+        match self {
+            SharedValue::Array(values) => Some(Arc::make_mut(values)),
+            _ => None,
+        }
+    }
+
+    /// Returns the members if the value is an object.
+    pub fn as_object(&self) -> Option<&SharedMap> {
+        match self {
+            SharedValue::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the members for modification if the value is an object,
+    /// first copying the object if it is shared with another value.
+    pub fn as_object_mut(&mut self) -> Option<&mut SharedMap> {
+        match self {
+            SharedValue::Object(map) => Some(Arc::make_mut(map)),
+            _ => None,
+        }
+    }
+
+    /// Returns the value stored under `key` if this is an object containing it.
+    pub fn get(&self, key: &str) -> Option<&SharedValue> {
+        self.as_object()?.get(key)
+    }
+
+    /// Returns the element at `index` if this is an array long enough to hold it.
+    pub fn get_index(&self, index: usize) -> Option<&SharedValue> {
+        self.as_array()?.get(index)
+    }
+
+    /// Returns whether both values are the same shared array or object, as
+    /// opposed to merely equal ones. Always `false` for other values.
+    pub fn ptr_eq(&self, other: &SharedValue) -> bool {
+        match (self, other) {
+            (SharedValue::Array(a), SharedValue::Array(b)) => Arc::ptr_eq(a, b),
+            (SharedValue::Object(a), SharedValue::Object(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Converts back to a plain `JsonValue`. Containers that are not shared
+    /// are moved; shared ones are copied.
+    pub fn into_owned(self) -> JsonValue {
+        match self {
+            SharedValue::Null => JsonValue::Null,
+            SharedValue::Boolean(b) => JsonValue::Boolean(b),
+            SharedValue::Integer(i) => JsonValue::Integer(i),
+            SharedValue::Unsigned(u) => JsonValue::Unsigned(u),
+            SharedValue::Number(f) => JsonValue::Number(f),
+            SharedValue::RawNumber(raw) => JsonValue::RawNumber(raw),
+            #[cfg(feature = "decimal")]
+            SharedValue::Decimal(d) => JsonValue::Decimal(d),
+            SharedValue::String(s) => JsonValue::String(s),
+            SharedValue::Array(values) => JsonValue::Array(
+                Arc::unwrap_or_clone(values)
+                    .into_iter()
+                    .map(Self::into_owned)
+                    .collect(),
+            ),
+            SharedValue::Object(map) => JsonValue::Object(
+                Arc::unwrap_or_clone(map)
+                    .into_iter()
+                    .map(|(k, v)| (k, v.into_owned()))
+                    .collect::<Map>(),
+            ),
+            SharedValue::Raw(raw) => JsonValue::Raw(raw),
+        }
+    }
+}
+
+impl From<JsonValue> for SharedValue {
+    /// Moves a value into reference-counted containers.
+    fn from(value: JsonValue) -> Self {
+        match value {
+            JsonValue::Null => SharedValue::Null,
+            JsonValue::Boolean(b) => SharedValue::Boolean(b),
+            JsonValue::Integer(i) => SharedValue::Integer(i),
+            JsonValue::Unsigned(u) => SharedValue::Unsigned(u),
+            JsonValue::Number(f) => SharedValue::Number(f),
+            JsonValue::RawNumber(raw) => SharedValue::RawNumber(raw),
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) => SharedValue::Decimal(d),
+            JsonValue::String(s) => SharedValue::String(s),
+            JsonValue::SharedString(s) => SharedValue::String(String::from(&*s)),
+            JsonValue::Array(values) => {
+                SharedValue::Array(Arc::new(values.into_iter().map(Self::from).collect()))
+            }
+            JsonValue::Object(map) => SharedValue::Object(Arc::new(
+                map.into_iter().map(|(k, v)| (k, Self::from(v))).collect(),
+            )),
+            JsonValue::Raw(raw) => SharedValue::Raw(raw),
+        }
+    }
+}
+
+impl From<&str> for SharedValue {
+    fn from(s: &str) -> Self {
+        SharedValue::String(s.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+
+    #[test]
+    fn test_round_trip_through_owned() {
+        let value = parse_json(r#"{"a": [1, 2.5, null, true, "x"], "b": {"c": "d"}}"#).unwrap();
+        let shared = SharedValue::from(value.clone());
+        assert_eq!(shared.clone().into_owned(), value);
+        assert_eq!(shared.into_owned(), value);
+    }
+
+    #[test]
+    fn test_raw_is_kept_verbatim() {
+        for text in [r#"{"a": [1, 2]}"#, "not json"] {
+            let shared = SharedValue::from(JsonValue::Raw(text.into()));
+            assert_eq!(shared, SharedValue::Raw(text.into()));
+            assert_eq!(shared.into_owned(), JsonValue::Raw(text.into()));
+        }
+    }
+
+    #[test]
+    fn test_clone_shares_structure() {
+        let base = SharedValue::from(parse_json(r#"{"a": {"x": 1}, "b": [1, 2]}"#).unwrap());
+        let copy = base.clone();
+        assert_eq!(copy, base);
+        assert!(copy.ptr_eq(&base));
+        assert!(copy.get("a").unwrap().ptr_eq(base.get("a").unwrap()));
+    }
+
+    #[test]
+    fn test_copy_on_write() {
+        let original = parse_json(r#"{"a": {"x": 1}, "b": [1, 2]}"#).unwrap();
+        let base = SharedValue::from(original.clone());
+        let mut overlay = base.clone();
+        overlay
+            .as_object_mut()
+            .unwrap()
+            .get_mut("b")
+            .unwrap()
+            .as_array_mut()
+            .unwrap()
+            .push(SharedValue::Integer(3));
+
+        // The change is visible only in the overlay, and the branch it did
+        // not touch is still shared.
+        assert_ne!(overlay, base);
+        assert!(!overlay.ptr_eq(&base));
+        assert!(overlay.get("a").unwrap().ptr_eq(base.get("a").unwrap()));
+        assert_eq!(
+            overlay.into_owned(),
+            parse_json(r#"{"a": {"x": 1}, "b": [1, 2, 3]}"#).unwrap()
+        );
+        assert_eq!(base.into_owned(), original);
+    }
+
+    #[test]
+    fn test_unshared_mutation_does_not_copy() {
+        let mut value = SharedValue::from(parse_json("[[1], [2]]").unwrap());
+        let inner = value.get_index(0).unwrap().as_array().unwrap().as_ptr();
+        let first = &mut value.as_array_mut().unwrap()[0];
+        assert_eq!(first.as_array_mut().unwrap().as_ptr(), inner);
+        assert!(SharedValue::Null.as_array_mut().is_none());
+        assert!(SharedValue::Integer(1).as_object_mut().is_none());
+    }
+}
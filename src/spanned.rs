@@ -0,0 +1,126 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::value::{map_with_capacity, JsonValue};
+
+/// A parsed value together with the byte range it occupies in the input.
+///
+/// Produced by [`JsonParser::parse_with_spans`](crate::JsonParser::parse_with_spans)
+/// for tools such as editors and linters that need to map values back to
+/// their source text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedValue {
+    /// Byte range of the value in the input, from its first byte to just
+    /// past its last.
+    pub span: Range<usize>,
+    /// The value itself.
+    pub kind: SpannedKind,
+}
+
+/// The content of a `SpannedValue`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpannedKind {
+    /// Any value other than an array or object.
+    Scalar(JsonValue),
+    /// An array, with a span for every element.
+    Array(Vec<SpannedValue>),
+    /// An object, with its members in source order.
+    Object(Vec<SpannedMember>),
+}
+
+/// An object member of a `SpannedValue`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedMember {
+    /// The decoded key.
+    pub key: String,
+    /// Byte range of the key in the input, including any quotes.
+    pub key_span: Range<usize>,
+    /// The member's value.
+    pub value: SpannedValue,
+}
+
+impl SpannedValue {
+    /// Returns the value of the member named `key` if this is an object
+    /// containing it. If the key is repeated, the last occurrence wins, as
+    /// in `JsonParser::parse`.
+    pub fn get(&self, key: &str) -> Option<&SpannedValue> {
+        match &self.kind {
+            SpannedKind::Object(members) => members
+                .iter()
+                .rev()
+                .find(|member| member.key == key)
+                .map(|member| &member.value),
+            _ => None,
+        }
+    }
+
+    /// Returns the element at `index` if this is an array long enough to hold it.
+    pub fn get_index(&self, index: usize) -> Option<&SpannedValue> {
+        match &self.kind {
+            SpannedKind::Array(values) => values.get(index),
+            _ => None,
+        }
+    }
+
+    /// Drops the spans, producing the same value `JsonParser::parse` would.
+    pub fn into_value(self) -> JsonValue {
+        match self.kind {
+            SpannedKind::Scalar(value) => value,
+            SpannedKind::Array(values) => {
+                JsonValue::Array(values.into_iter().map(Self::into_value).collect())
+            }
+            SpannedKind::Object(members) => {
+                let mut map = map_with_capacity(members.len());
+                for member in members {
+                    map.insert(member.key, member.value.into_value());
+                }
+                JsonValue::Object(map)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_json, JsonParser};
+
+    #[test]
+    fn test_spans_of_nested_values() {
+        let input = r#"{"user": {"name": "Ann", "tags": [1, "x"]}}"#;
+        let value = JsonParser::new(input).parse_with_spans().unwrap();
+        assert_eq!(value.span, 0..input.len());
+
+        let name = value.get("user").unwrap().get("name").unwrap();
+        assert_eq!(&input[name.span.clone()], r#""Ann""#);
+        assert_eq!(name.span.start, input.find(r#""Ann""#).unwrap());
+
+        let tags = value.get("user").unwrap().get("tags").unwrap();
+        assert_eq!(&input[tags.span.clone()], r#"[1, "x"]"#);
+        assert_eq!(&input[tags.get_index(1).unwrap().span.clone()], r#""x""#);
+
+        match &value.get("user").unwrap().kind {
+            SpannedKind::Object(members) => {
+                assert_eq!(members[1].key, "tags");
+                assert_eq!(&input[members[1].key_span.clone()], r#""tags""#);
+            }
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_spans_keep_source_order_and_duplicates() {
+        let input = r#"{"b": 1, "a": 2, "b": 3}"#;
+        let value = JsonParser::new(input).parse_with_spans().unwrap();
+        match &value.kind {
+            SpannedKind::Object(members) => {
+                let keys: Vec<&str> = members.iter().map(|m| m.key.as_str()).collect();
+                assert_eq!(keys, ["b", "a", "b"]);
+            }
+            other => panic!("expected object, got {:?}", other),
+        }
+        assert_eq!(value.get("b").unwrap().span, 22..23);
+        assert_eq!(value.into_value(), parse_json(input).unwrap());
+    }
+}
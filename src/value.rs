@@ -1,20 +1,1160 @@
-use std::collections::HashMap;
+use crate::error::{ParseError, ParseErrorKind, Result};
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::Index;
+#[cfg(feature = "decimal")]
+use rust_decimal::prelude::ToPrimitive;
+
+/// The map type backing `JsonValue::Object`.
+///
+/// By default this is a `HashMap`, so member order is unspecified. With the
+/// `preserve_order` feature enabled it is an `IndexMap` that keeps members in
+/// the order they appeared in the source, which the serializer then follows.
+/// The ordered map costs some extra memory per entry and slightly slower
+/// lookups and removals.
+#[cfg(all(feature = "std", not(feature = "preserve_order")))]
+pub type Map = std::collections::HashMap<String, JsonValue>;
+
+/// `Map` used without the `std` feature, which keeps members sorted by key.
+#[cfg(not(feature = "std"))]
+pub type Map = alloc::collections::BTreeMap<String, JsonValue>;
+
+/// Insertion-ordered `Map` used when the `preserve_order` feature is enabled.
+#[cfg(feature = "preserve_order")]
+pub type Map = indexmap::IndexMap<String, JsonValue>;
+
+/// A view into a single member of a `Map`, vacant or occupied, returned by
+/// [`JsonValue::entry`]. It is the entry type of whichever map backs `Map`.
+#[cfg(all(feature = "std", not(feature = "preserve_order")))]
+pub type Entry<'a> = std::collections::hash_map::Entry<'a, String, JsonValue>;
+
+/// `Entry` used without the `std` feature.
+#[cfg(not(feature = "std"))]
+pub type Entry<'a> = alloc::collections::btree_map::Entry<'a, String, JsonValue>;
+
+/// `Entry` used when the `preserve_order` feature is enabled.
+#[cfg(feature = "preserve_order")]
+pub type Entry<'a> = indexmap::map::Entry<'a, String, JsonValue>;
+
+/// Creates an empty `Map` with room for `capacity` members, where the
+/// backing map supports pre-allocation.
+pub(crate) fn map_with_capacity(capacity: usize) -> Map {
+    #[cfg(feature = "std")]
+    return Map::with_capacity(capacity);
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = capacity;
+        Map::new()
+    }
+}
+
+/// Passes every member of `map` to `f`, leaving `map` empty but, where the
+/// backing map supports it, keeping its allocation.
+pub(crate) fn drain_map(map: &mut Map, f: impl FnMut((String, JsonValue))) {
+    #[cfg(all(feature = "std", not(feature = "preserve_order")))]
+    map.drain().for_each(f);
+    #[cfg(feature = "preserve_order")]
+    map.drain(..).for_each(f);
+    #[cfg(not(feature = "std"))]
+    core::mem::take(map).into_iter().for_each(f);
+}
 
 /// Represents a JSON value according to the JSON specification.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// Values implement `Eq` and `Hash` so they can be stored in hash-based
+/// collections. To make that sound for floats, `Number` compares all `NaN`s
+/// equal to each other and `0.0` equal to `-0.0`; otherwise numbers compare
+/// as `f64` does. `Integer(1)` and `Number(1.0)` are distinct values.
+#[derive(Debug, Clone)]
 pub enum JsonValue {
     /// The JSON null value.
     Null,
     /// A JSON boolean value (true or false).
     Boolean(bool),
-    /// A JSON number, stored as a 64-bit float.
+    /// A JSON integer that fits in a signed 64-bit integer.
+    Integer(i64),
+    /// A JSON integer above `i64::MAX` that fits in an unsigned 64-bit
+    /// integer. Smaller non-negative integers are always `Integer`.
+    Unsigned(u64),
+    /// Any other JSON number, stored as a 64-bit float.
     Number(f64),
+    /// A JSON number kept as its exact source text, produced by
+    /// `JsonParser::with_arbitrary_precision`.
+    RawNumber(String),
+    /// A JSON number stored as an exact decimal, produced by
+    /// `JsonParser::with_decimal_numbers`.
+    #[cfg(feature = "decimal")]
+    Decimal(rust_decimal::Decimal),
     /// A JSON string.
     String(String),
+    /// A JSON string whose contents are shared with other equal strings,
+    /// produced by [`JsonValue::intern_strings`].
+    ///
+    /// It is equal to, orders and hashes like, and serializes as the
+    /// `String` with the same contents, and [`JsonValue::as_str`] returns
+    /// either. Code matching on `JsonValue::String` directly must handle
+    /// this variant too to see interned strings. The parser never produces
+    /// it.
+    SharedString(Arc<str>),
     /// A JSON array containing a list of values.
     Array(Vec<JsonValue>),
     /// A JSON object containing key-value pairs.
-    Object(HashMap<String, JsonValue>),
+    Object(Map),
+    /// Any JSON value kept unparsed as its exact source text, produced by
+    /// `JsonParser::parse_partial` for the parts of a document it skips.
+    ///
+    /// Compact output writes it verbatim, so forwarding it reproduces the
+    /// input byte for byte; canonical and pretty output parse it and write
+    /// the result like any other value. It equals, orders and hashes by its
+    /// text alone, and never equals a parsed value; parse the text to
+    /// inspect it.
+    ///
+    /// The text is not checked when the variant is built by hand. It must be
+    /// exactly one JSON value, or the serialized document will not be valid
+    /// JSON.
+    Raw(String),
+}
+
+impl JsonValue {
+    /// Returns `true` if the value is `null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    /// Returns the boolean if the value is `true` or `false`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            JsonValue::Boolean(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns the string contents if the value is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            JsonValue::SharedString(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements if the value is an array.
+    pub fn as_array(&self) -> Option<&Vec<JsonValue>> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements mutably if the value is an array.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<JsonValue>> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the members if the value is an object.
+    pub fn as_object(&self) -> Option<&Map> {
+        match self {
+            JsonValue::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the members mutably if the value is an object.
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            JsonValue::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Consumes the value, returning its string if it is a string. An
+    /// interned string is copied.
+    pub fn into_string(self) -> Option<String> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            JsonValue::SharedString(s) => Some(String::from(&*s)),
+            _ => None,
+        }
+    }
+
+    /// Consumes the value, returning its elements if it is an array.
+    pub fn into_array(self) -> Option<Vec<JsonValue>> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Consumes the value, returning its members if it is an object.
+    pub fn into_object(self) -> Option<Map> {
+        match self {
+            JsonValue::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `i64` if it is an integer, or a float with no
+    /// fractional part that fits in the `i64` range.
+    ///
+    /// A `RawNumber` is converted on demand by the same rules.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            JsonValue::Integer(i) => Some(i),
+            JsonValue::Unsigned(u) => i64::try_from(u).ok(),
+            JsonValue::Number(f)
+                if trunc(f) == f && f >= i64::MIN as f64 && f < i64::MAX as f64 =>
+            {
+                Some(f as i64)
+            }
+            JsonValue::RawNumber(ref raw) => raw
+                .parse()
+                .ok()
+                .or_else(|| JsonValue::Number(raw.parse().ok()?).as_i64()),
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) if d.fract().is_zero() => d.to_i64(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `u64` if it is a non-negative integer, or a
+    /// float with no fractional part that fits in the `u64` range.
+    ///
+    /// A `RawNumber` is converted on demand by the same rules.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            JsonValue::Integer(i) => u64::try_from(i).ok(),
+            JsonValue::Unsigned(u) => Some(u),
+            JsonValue::Number(f) if trunc(f) == f && f >= 0.0 && f < u64::MAX as f64 => {
+                Some(f as u64)
+            }
+            JsonValue::RawNumber(ref raw) => raw
+                .parse()
+                .ok()
+                .or_else(|| JsonValue::Number(raw.parse().ok()?).as_u64()),
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) if d.fract().is_zero() => d.to_u64(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value stored under `key` if this is an object containing it.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// Like [`JsonValue::get`], but matches keys ignoring ASCII case, as
+    /// header-like keys such as `Content-Type` often are.
+    ///
+    /// A key spelled exactly like `key` is preferred. Otherwise the first
+    /// matching member in the map's iteration order is returned, so if the
+    /// object holds several keys that differ only by case, which one is
+    /// found is unspecified.
+    pub fn get_ci(&self, key: &str) -> Option<&JsonValue> {
+        let map = self.as_object()?;
+        map.get(key).or_else(|| {
+            map.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value)
+        })
+    }
+
+    /// Returns a mutable reference to the value stored under `key` if this
+    /// is an object containing it.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonValue> {
+        match self {
+            JsonValue::Object(map) => map.get_mut(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the element at `index` if this is an array long enough to hold it.
+    pub fn get_index(&self, index: usize) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Array(values) => values.get(index),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index` if this is an
+    /// array long enough to hold it.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut JsonValue> {
+        match self {
+            JsonValue::Array(values) => values.get_mut(index),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `f64` if it is a number of any kind.
+    ///
+    /// Integers beyond 2^53 are rounded to the nearest representable float,
+    /// and a `RawNumber` too large for `f64` becomes an infinity.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            JsonValue::Integer(i) => Some(i as f64),
+            JsonValue::Unsigned(u) => Some(u as f64),
+            JsonValue::Number(f) => Some(f),
+            JsonValue::RawNumber(ref raw) => raw.parse().ok(),
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) => d.to_f64(),
+            _ => None,
+        }
+    }
+
+    /// Inserts a member if the value is an object, returning the value
+    /// previously stored under `key`.
+    ///
+    /// Returns `None` without inserting if the value is not an object.
+    pub fn insert(&mut self, key: &str, value: JsonValue) -> Option<JsonValue> {
+        self.as_object_mut()?.insert(key.to_string(), value)
+    }
+
+    /// Returns the entry for `key` if the value is an object, for in-place
+    /// insertion or update.
+    ///
+    /// Returns `None` if the value is not an object.
+    ///
+    /// ```rust
+    /// use arjp::{json, JsonValue};
+    ///
+    /// let mut doc = json!({});
+    /// doc.entry("tags").unwrap().or_insert(json!([])).push(json!("new"));
+    /// *doc.entry("count").unwrap().or_insert(JsonValue::from(0)) = JsonValue::from(1);
+    /// assert_eq!(doc, json!({"tags": ["new"], "count": 1}));
+    /// ```
+    pub fn entry(&mut self, key: &str) -> Option<Entry<'_>> {
+        Some(self.as_object_mut()?.entry(key.to_string()))
+    }
+
+    /// Removes a member if the value is an object, returning its value.
+    ///
+    /// With the `preserve_order` feature the remaining members keep their
+    /// order. Returns `None` if the value is not an object or has no such key.
+    pub fn remove(&mut self, key: &str) -> Option<JsonValue> {
+        let map = self.as_object_mut()?;
+        #[cfg(feature = "preserve_order")]
+        return map.shift_remove(key);
+        #[cfg(not(feature = "preserve_order"))]
+        return map.remove(key);
+    }
+
+    /// Appends an element if the value is an array; does nothing otherwise.
+    pub fn push(&mut self, value: JsonValue) {
+        if let Some(values) = self.as_array_mut() {
+            values.push(value);
+        }
+    }
+
+    /// Returns an iterator over the members if the value is an object.
+    pub fn entries(&self) -> Option<impl Iterator<Item = (&String, &JsonValue)>> {
+        self.as_object().map(|map| map.iter())
+    }
+
+    /// Returns an iterator over the keys if the value is an object.
+    pub fn keys(&self) -> Option<impl Iterator<Item = &str>> {
+        self.as_object().map(|map| map.keys().map(String::as_str))
+    }
+
+    /// Returns an iterator over the member values if the value is an object.
+    pub fn values(&self) -> Option<impl Iterator<Item = &JsonValue>> {
+        self.as_object().map(|map| map.values())
+    }
+
+    /// Returns an iterator over the elements if the value is an array.
+    pub fn iter(&self) -> Option<impl Iterator<Item = &JsonValue>> {
+        self.as_array().map(|values| values.iter())
+    }
+
+    /// Returns the number of elements of an array or members of an object,
+    /// or `None` for any other value.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            JsonValue::Array(values) => Some(values.len()),
+            JsonValue::Object(map) => Some(map.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether an array or object has no elements or members, or
+    /// `None` for any other value.
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Looks up a nested value by an RFC 6901 JSON Pointer such as
+    /// `/user/addresses/0/city`.
+    ///
+    /// The empty pointer refers to the whole value. In each segment `~1`
+    /// stands for `/` and `~0` for `~`. Returns `None` if the pointer is
+    /// malformed or any segment is missing or names the wrong kind of value.
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonValue> {
+        let mut target = self;
+        for token in pointer_tokens(pointer)? {
+            target = match target {
+                JsonValue::Object(map) => map.get(&token)?,
+                JsonValue::Array(values) => values.get(array_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+
+    /// Like [`JsonValue::pointer`], but returns a mutable reference.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue> {
+        let mut target = self;
+        for token in pointer_tokens(pointer)? {
+            target = match target {
+                JsonValue::Object(map) => map.get_mut(&token)?,
+                JsonValue::Array(values) => values.get_mut(array_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+
+    /// Stores `value` at an RFC 6901 JSON Pointer, replacing what is there.
+    ///
+    /// The last segment may also name a new member of an existing object,
+    /// or be `-` to append to an array; an array index must already exist.
+    /// Every earlier segment must already exist, and the empty pointer
+    /// replaces the whole value. Use [`JsonValue::pointer_set_create`] to
+    /// create missing objects along the way.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `ParseErrorKind::InvalidPointer`, leaving the value
+    /// unchanged, if the pointer is malformed, a segment does not exist, or
+    /// the path runs through a scalar.
+    ///
+    /// ```rust
+    /// use arjp::json;
+    ///
+    /// let mut value = json!({"user": {"name": "John", "tags": []}});
+    /// value.pointer_set("/user/name", json!("Jane")).unwrap();
+    /// value.pointer_set("/user/tags/-", json!("admin")).unwrap();
+    /// assert_eq!(value, json!({"user": {"name": "Jane", "tags": ["admin"]}}));
+    /// assert!(value.pointer_set("/user/name/first", json!("J")).is_err());
+    /// ```
+    pub fn pointer_set(&mut self, pointer: &str, value: JsonValue) -> Result<()> {
+        self.set_pointer(pointer, value, false)
+    }
+
+    /// Like [`JsonValue::pointer_set`], but creates an empty object for each
+    /// missing member along the path, and appends one for a `-` segment
+    /// before the last. Scalars on the path are still an error.
+    pub fn pointer_set_create(&mut self, pointer: &str, value: JsonValue) -> Result<()> {
+        self.set_pointer(pointer, value, true)
+    }
+
+    /// Shared implementation of `pointer_set` and `pointer_set_create`.
+    ///
+    /// Members are only created once every existing segment has resolved,
+    /// and nothing below a created object can fail, so an error never leaves
+    /// a partial path behind.
+    fn set_pointer(&mut self, pointer: &str, value: JsonValue, create_missing: bool) -> Result<()> {
+        let error = |message: &str| {
+            ParseError::new(
+                ParseErrorKind::InvalidPointer,
+                &format!("{}: {:?}", message, pointer),
+            )
+        };
+        let tokens: Vec<String> = pointer_tokens(pointer)
+            .ok_or_else(|| error("Invalid pointer"))?
+            .collect();
+        let Some((last, parents)) = tokens.split_last() else {
+            *self = value;
+            return Ok(());
+        };
+        let mut target = self;
+        for token in parents {
+            target = match target {
+                JsonValue::Object(map) => {
+                    if create_missing && !map.contains_key(token) {
+                        map.insert(token.clone(), JsonValue::Object(Map::new()));
+                    }
+                    map.get_mut(token).ok_or_else(|| error("Path not found"))?
+                }
+                JsonValue::Array(values) => {
+                    let index = if create_missing && token == "-" {
+                        values.push(JsonValue::Object(Map::new()));
+                        Some(values.len() - 1)
+                    } else {
+                        array_index(token)
+                    };
+                    index
+                        .and_then(|index| values.get_mut(index))
+                        .ok_or_else(|| error("Path not found"))?
+                }
+                _ => return Err(error("Path runs through a scalar")),
+            };
+        }
+        match target {
+            JsonValue::Object(map) => {
+                map.insert(last.clone(), value);
+            }
+            JsonValue::Array(values) if last == "-" => values.push(value),
+            JsonValue::Array(values) => {
+                *array_index(last)
+                    .and_then(|index| values.get_mut(index))
+                    .ok_or_else(|| error("Path not found"))? = value;
+            }
+            _ => return Err(error("Path runs through a scalar")),
+        }
+        Ok(())
+    }
+
+    /// Returns every value in the tree, including this one, for which `pred`
+    /// returns `true`.
+    ///
+    /// The walk is depth-first and visits a container before its children,
+    /// so matches come back in document order. Object members are visited
+    /// in the map's iteration order.
+    ///
+    /// ```rust
+    /// use arjp::json;
+    ///
+    /// let value = json!({"home": "https://a.example", "links": ["https://b.example", "mailto:c"]});
+    /// let urls = value.find_all(|v| v.as_str().is_some_and(|s| s.starts_with("https://")));
+    /// assert_eq!(urls.len(), 2);
+    /// ```
+    pub fn find_all<F: Fn(&JsonValue) -> bool>(&self, pred: F) -> Vec<&JsonValue> {
+        let mut found = Vec::new();
+        self.find_each(&mut |value| {
+            if pred(value) {
+                found.push(value);
+            }
+            true
+        });
+        found
+    }
+
+    /// Like [`JsonValue::find_all`], but stops at and returns the first
+    /// match.
+    pub fn find_first<F: Fn(&JsonValue) -> bool>(&self, pred: F) -> Option<&JsonValue> {
+        let mut found = None;
+        self.find_each(&mut |value| {
+            if pred(value) {
+                found = Some(value);
+                return false;
+            }
+            true
+        });
+        found
+    }
+
+    /// Calls `f` on this value and then on each descendant, depth-first,
+    /// until `f` returns `false`. Returns whether the walk ran to the end.
+    fn find_each<'a>(&'a self, f: &mut impl FnMut(&'a JsonValue) -> bool) -> bool {
+        f(self) && self.into_iter().all(|child| child.find_each(f))
+    }
+
+    /// Merges `other` into this value, as when layering configuration files.
+    ///
+    /// If both values are objects, each member of `other` is added to this
+    /// one. A shallow merge overwrites existing members outright. A deep
+    /// merge instead merges the two member values in turn, so nested objects
+    /// combine key by key while arrays and scalars are replaced. If either
+    /// value is not an object, `other` replaces this value. A `null` in
+    /// `other` is an ordinary value: it overwrites rather than removes.
+    ///
+    /// With `concat_arrays`, an array meeting an array is not replaced: the
+    /// elements from `other` are appended to it instead. This applies to the
+    /// values themselves and, in a deep merge, to nested members.
+    ///
+    /// ```rust
+    /// use arjp::json;
+    ///
+    /// let mut config = json!({"log": {"level": "info", "file": "a.log"}, "ports": [80]});
+    /// config.merge(json!({"log": {"level": "debug"}, "ports": [8080]}), true, false);
+    /// assert_eq!(
+    ///     config,
+    ///     json!({"log": {"level": "debug", "file": "a.log"}, "ports": [8080]})
+    /// );
+    ///
+    /// config.merge(json!({"ports": [443]}), true, true);
+    /// assert_eq!(config["ports"], json!([8080, 443]));
+    /// ```
+    pub fn merge(&mut self, other: JsonValue, deep: bool, concat_arrays: bool) {
+        match (self, other) {
+            (JsonValue::Object(map), JsonValue::Object(other)) => {
+                for (key, value) in other {
+                    match map.get_mut(&key) {
+                        Some(existing) if deep => existing.merge(value, deep, concat_arrays),
+                        _ => {
+                            map.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (JsonValue::Array(values), JsonValue::Array(other)) if concat_arrays => {
+                values.extend(other)
+            }
+            (this, other) => *this = other,
+        }
+    }
+
+    /// Flattens nested objects and arrays into a single map from dotted key
+    /// paths to leaf values, as in `user.address.city` or `items.0.price`.
+    ///
+    /// Array elements use their index as the path segment. Empty nested
+    /// objects and arrays are kept as leaves, so nothing is lost; an empty
+    /// top-level container yields an empty map, and a top-level scalar is
+    /// stored under the empty key. Keys that already contain the separator
+    /// are not escaped, so distinct paths can collide; the later one wins.
+    ///
+    /// ```rust
+    /// use arjp::{json, JsonValue};
+    ///
+    /// let flat = json!({"user": {"tags": ["a"]}}).flatten();
+    /// assert_eq!(flat["user.tags.0"], JsonValue::from("a"));
+    /// ```
+    pub fn flatten(&self) -> Map {
+        self.flatten_with(".")
+    }
+
+    /// Like [`JsonValue::flatten`], but joins path segments with
+    /// `separator`, such as `"/"` or `"__"`.
+    pub fn flatten_with(&self, separator: &str) -> Map {
+        let mut out = map_with_capacity(0);
+        match self {
+            JsonValue::Array(_) | JsonValue::Object(_) => {
+                flatten_children(self, None, separator, &mut out)
+            }
+            scalar => {
+                out.insert(String::new(), scalar.clone());
+            }
+        }
+        out
+    }
+
+    /// Replaces every string value with a [`JsonValue::SharedString`],
+    /// making equal strings share a single allocation.
+    ///
+    /// Documents that repeat the same strings many times, such as enum-like
+    /// status fields, then keep one copy of each distinct string. The first
+    /// occurrence of each is copied once into the shared pool and every
+    /// `String` is freed. Object keys are left as they are, since `Map` keys
+    /// are always `String`s. Equality, ordering, hashing and serialization
+    /// are unaffected; only code matching on `JsonValue::String` directly
+    /// sees the change of variant. The shared strings are reference counted
+    /// with `Arc`, so the value stays `Send` and `Sync`.
+    ///
+    /// ```rust
+    /// use arjp::{json, JsonValue};
+    ///
+    /// let mut value = json!([{"state": "open"}, {"state": "open"}]);
+    /// value.intern_strings();
+    /// match (&value[0]["state"], &value[1]["state"]) {
+    ///     (JsonValue::SharedString(a), JsonValue::SharedString(b)) => {
+    ///         assert!(std::sync::Arc::ptr_eq(a, b))
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// assert_eq!(value, json!([{"state": "open"}, {"state": "open"}]));
+    /// ```
+    pub fn intern_strings(&mut self) {
+        let mut pool: BTreeSet<Arc<str>> = BTreeSet::new();
+        let mut pending = vec![self];
+        while let Some(value) = pending.pop() {
+            let text = match value {
+                JsonValue::String(s) => s.as_str(),
+                JsonValue::SharedString(s) => s,
+                JsonValue::Array(values) => {
+                    pending.extend(values.iter_mut());
+                    continue;
+                }
+                JsonValue::Object(map) => {
+                    pending.extend(map.values_mut());
+                    continue;
+                }
+                _ => continue,
+            };
+            let shared = match pool.get(text) {
+                Some(shared) => Arc::clone(shared),
+                None => {
+                    let shared = Arc::<str>::from(text);
+                    pool.insert(Arc::clone(&shared));
+                    shared
+                }
+            };
+            *value = JsonValue::SharedString(shared);
+        }
+    }
+
+    /// Returns whether two values are equal, treating numbers that differ by
+    /// at most `epsilon` as equal.
+    ///
+    /// This is the same comparison as
+    /// [`JsonValue::semantic_eq_with_tolerance`], under the name test
+    /// assertions on computed documents tend to look for. Objects still
+    /// compare regardless of member order, and numbers regardless of
+    /// variant.
+    ///
+    /// ```rust
+    /// use arjp::json;
+    ///
+    /// let computed = json!({"total": 0.1 + 0.2, "items": [1.0, 2.5]});
+    /// assert_ne!(computed, json!({"total": 0.3, "items": [1.0, 2.5]}));
+    /// assert!(computed.approx_eq(&json!({"total": 0.3, "items": [1, 2.5]}), 1e-12));
+    /// ```
+    pub fn approx_eq(&self, other: &JsonValue, epsilon: f64) -> bool {
+        self.semantic_eq_with_tolerance(other, epsilon)
+    }
+
+    /// Compares two values by meaning rather than representation, which is
+    /// what test assertions usually want.
+    ///
+    /// Objects are equal when they have the same keys with semantically
+    /// equal values, whatever their member order or backing map. Numbers
+    /// are equal when they have the same exact numeric value, regardless of
+    /// variant, so `1`, `1.0` and a raw `1.00` all match; `NaN` matches
+    /// `NaN`. Arrays compare element-wise in order. Unlike `==`, this never
+    /// distinguishes `Integer(1)` from `Number(1.0)`.
+    pub fn semantic_eq(&self, other: &JsonValue) -> bool {
+        self.semantic_eq_with_tolerance(other, 0.0)
+    }
+
+    /// Like [`JsonValue::semantic_eq`], but also treats two numbers as equal
+    /// when their `f64` values differ by at most `tolerance`.
+    ///
+    /// This absorbs rounding differences in computed floats. A tolerance of
+    /// `0.0` gives exact comparison.
+    pub fn semantic_eq_with_tolerance(&self, other: &JsonValue, tolerance: f64) -> bool {
+        match (self, other) {
+            (JsonValue::Array(a), JsonValue::Array(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|(a, b)| a.semantic_eq_with_tolerance(b, tolerance))
+            }
+            (JsonValue::Object(a), JsonValue::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(key, a)| {
+                        b.get(key)
+                            .is_some_and(|b| a.semantic_eq_with_tolerance(b, tolerance))
+                    })
+            }
+            _ if type_rank(self) == 2 && type_rank(other) == 2 => {
+                compare_numeric_values(self, other) == Ordering::Equal
+                    || match (self.as_f64(), other.as_f64()) {
+                        (Some(a), Some(b)) => (a - b).abs() <= tolerance,
+                        _ => false,
+                    }
+            }
+            _ => self == other,
+        }
+    }
+}
+
+/// Adds the leaves below the container `value`, whose own path is `path`,
+/// to `out`. The top-level container has no path of its own.
+fn flatten_children(value: &JsonValue, path: Option<&mut String>, separator: &str, out: &mut Map) {
+    let mut root = String::new();
+    let is_root = path.is_none();
+    let path = path.unwrap_or(&mut root);
+    let mut visit = |segment: &str, child: &JsonValue| {
+        let parent_len = path.len();
+        if !is_root {
+            path.push_str(separator);
+        }
+        path.push_str(segment);
+        match child {
+            JsonValue::Array(values) if !values.is_empty() => {
+                flatten_children(child, Some(&mut *path), separator, out)
+            }
+            JsonValue::Object(map) if !map.is_empty() => {
+                flatten_children(child, Some(&mut *path), separator, out)
+            }
+            leaf => {
+                out.insert(path.clone(), leaf.clone());
+            }
+        }
+        path.truncate(parent_len);
+    };
+    match value {
+        JsonValue::Array(values) => {
+            for (index, child) in values.iter().enumerate() {
+                visit(&index.to_string(), child);
+            }
+        }
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                visit(key, child);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Iterator over the direct children of a `JsonValue`, created by iterating
+/// over `&JsonValue`.
+///
+/// Arrays yield their elements, objects yield their member values, and
+/// scalars yield nothing.
+pub struct Children<'a> {
+    inner: ChildrenInner<'a>,
+}
+
+enum ChildrenInner<'a> {
+    Empty,
+    Array(core::slice::Iter<'a, JsonValue>),
+    Object(<&'a Map as IntoIterator>::IntoIter),
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = &'a JsonValue;
+
+    fn next(&mut self) -> Option<&'a JsonValue> {
+        match &mut self.inner {
+            ChildrenInner::Empty => None,
+            ChildrenInner::Array(values) => values.next(),
+            ChildrenInner::Object(members) => members.next().map(|(_, value)| value),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.inner {
+            ChildrenInner::Empty => (0, Some(0)),
+            ChildrenInner::Array(values) => values.size_hint(),
+            ChildrenInner::Object(members) => members.size_hint(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a JsonValue {
+    type Item = &'a JsonValue;
+    type IntoIter = Children<'a>;
+
+    fn into_iter(self) -> Children<'a> {
+        let inner = match self {
+            JsonValue::Array(values) => ChildrenInner::Array(values.iter()),
+            JsonValue::Object(map) => ChildrenInner::Object(map.iter()),
+            _ => ChildrenInner::Empty,
+        };
+        Children { inner }
+    }
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens, or returns
+/// `None` if a non-empty pointer does not start with `/`.
+pub(crate) fn pointer_tokens(pointer: &str) -> Option<impl Iterator<Item = String> + '_> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return None;
+    }
+    Some(
+        pointer
+            .split('/')
+            .skip(1)
+            .map(|token| token.replace("~1", "/").replace("~0", "~")),
+    )
+}
+
+/// Parses an array index token, which RFC 6901 limits to `0` or digits
+/// without a leading zero.
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl PartialEq for JsonValue {
+    fn eq(&self, other: &JsonValue) -> bool {
+        match (self, other) {
+            (JsonValue::Null, JsonValue::Null) => true,
+            (JsonValue::Boolean(a), JsonValue::Boolean(b)) => a == b,
+            (JsonValue::Integer(a), JsonValue::Integer(b)) => a == b,
+            (JsonValue::Unsigned(a), JsonValue::Unsigned(b)) => a == b,
+            (JsonValue::RawNumber(a), JsonValue::RawNumber(b)) => a == b,
+            #[cfg(feature = "decimal")]
+            (JsonValue::Decimal(a), JsonValue::Decimal(b)) => a == b,
+            (JsonValue::Number(a), JsonValue::Number(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (
+                JsonValue::String(_) | JsonValue::SharedString(_),
+                JsonValue::String(_) | JsonValue::SharedString(_),
+            ) => self.as_str() == other.as_str(),
+            (JsonValue::Array(a), JsonValue::Array(b)) => a == b,
+            (JsonValue::Object(a), JsonValue::Object(b)) => a == b,
+            (JsonValue::Raw(a), JsonValue::Raw(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for JsonValue {}
+
+impl Hash for JsonValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            // An interned string hashes as the `String` it is equal to.
+            JsonValue::SharedString(_) => {
+                core::mem::discriminant(&JsonValue::String(String::new())).hash(state)
+            }
+            _ => core::mem::discriminant(self).hash(state),
+        }
+        match self {
+            JsonValue::Null => {}
+            JsonValue::Boolean(b) => b.hash(state),
+            JsonValue::Integer(i) => i.hash(state),
+            JsonValue::Unsigned(u) => u.hash(state),
+            JsonValue::RawNumber(raw) => raw.hash(state),
+            #[cfg(feature = "decimal")]
+            JsonValue::Decimal(d) => d.hash(state),
+            JsonValue::Number(f) => {
+                // Collapse the values `eq` treats as equal onto one bit pattern.
+                let bits = if f.is_nan() {
+                    f64::NAN.to_bits()
+                } else if *f == 0.0 {
+                    0
+                } else {
+                    f.to_bits()
+                };
+                bits.hash(state);
+            }
+            JsonValue::String(s) => s.hash(state),
+            JsonValue::SharedString(s) => s.hash(state),
+            JsonValue::Raw(raw) => raw.hash(state),
+            JsonValue::Array(values) => values.hash(state),
+            JsonValue::Object(map) => {
+                // Member order does not affect equality, so combine the
+                // per-member hashes with a commutative operation.
+                let mut combined = 0u64;
+                for (key, value) in map {
+                    let mut hasher = MemberHasher::default();
+                    key.hash(&mut hasher);
+                    value.hash(&mut hasher);
+                    combined = combined.wrapping_add(hasher.finish());
+                }
+                map.len().hash(state);
+                combined.hash(state);
+            }
+        }
+    }
+}
+
+/// FNV-1a hasher for the per-member hashes of an object.
+///
+/// It only needs to be deterministic, so a tiny hasher that also works
+/// without `std` is used instead of `DefaultHasher`.
+struct MemberHasher(u64);
+
+impl Default for MemberHasher {
+    fn default() -> Self {
+        MemberHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for MemberHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Orders values first by type and then by content.
+///
+/// The type order is `Null < Boolean < number < String < Array < Object <
+/// Raw`, where `Integer`, `Unsigned`, `Number`, `Decimal` and `RawNumber` together
+/// form the number type:
+///
+/// - booleans order `false < true`;
+/// - numbers compare by numeric value, exactly even for large integers, with
+///   `NaN` above every other number and a `RawNumber` taking the value of its
+///   nearest `f64`; numbers of equal value but different variants order
+///   `Integer < Unsigned < Number < Decimal < RawNumber`, and equal-valued
+///   raw numbers order by their text, so the ordering agrees with `==`;
+/// - strings compare lexicographically by bytes;
+/// - arrays compare element-wise, a shorter prefix ordering first;
+/// - objects compare their members sorted by key, as if they were arrays of
+///   `(key, value)` pairs, so member order never matters;
+/// - raw values compare lexicographically by their source text.
+impl Ord for JsonValue {
+    fn cmp(&self, other: &JsonValue) -> Ordering {
+        match (self, other) {
+            (JsonValue::Null, JsonValue::Null) => Ordering::Equal,
+            (JsonValue::Boolean(a), JsonValue::Boolean(b)) => a.cmp(b),
+            (
+                JsonValue::Integer(_)
+                | JsonValue::Unsigned(_)
+                | JsonValue::Number(_)
+                | JsonValue::RawNumber(_),
+                _,
+            ) if type_rank(other) == type_rank(self) => compare_numbers(self, other),
+            #[cfg(feature = "decimal")]
+            (JsonValue::Decimal(_), _) if type_rank(other) == 2 => compare_numbers(self, other),
+            (
+                JsonValue::String(_) | JsonValue::SharedString(_),
+                JsonValue::String(_) | JsonValue::SharedString(_),
+            ) => self.as_str().cmp(&other.as_str()),
+            (JsonValue::Array(a), JsonValue::Array(b)) => a.cmp(b),
+            (JsonValue::Object(a), JsonValue::Object(b)) => {
+                let mut a: Vec<_> = a.iter().collect();
+                let mut b: Vec<_> = b.iter().collect();
+                a.sort();
+                b.sort();
+                a.cmp(&b)
+            }
+            (JsonValue::Raw(a), JsonValue::Raw(b)) => a.cmp(b),
+            _ => type_rank(self).cmp(&type_rank(other)),
+        }
+    }
+}
+
+impl PartialOrd for JsonValue {
+    fn partial_cmp(&self, other: &JsonValue) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Position of a value's type in the cross-type ordering.
+fn type_rank(value: &JsonValue) -> u8 {
+    match value {
+        JsonValue::Null => 0,
+        JsonValue::Boolean(_) => 1,
+        JsonValue::Integer(_)
+        | JsonValue::Unsigned(_)
+        | JsonValue::Number(_)
+        | JsonValue::RawNumber(_) => 2,
+        #[cfg(feature = "decimal")]
+        JsonValue::Decimal(_) => 2,
+        JsonValue::String(_) | JsonValue::SharedString(_) => 3,
+        JsonValue::Array(_) => 4,
+        JsonValue::Object(_) => 5,
+        JsonValue::Raw(_) => 6,
+    }
+}
+
+/// Compares floats numerically, treating all `NaN`s as equal to each other
+/// and greater than any other number.
+fn compare_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// Compares two number values by exact numeric value, breaking ties by
+/// variant as `Integer < Unsigned < Number < Decimal < RawNumber` and then
+/// by decimal value or raw text.
+fn compare_numbers(a: &JsonValue, b: &JsonValue) -> Ordering {
+    let variant_rank = |value: &JsonValue| match value {
+        JsonValue::Integer(_) => 0,
+        JsonValue::Unsigned(_) => 1,
+        JsonValue::Number(_) => 2,
+        JsonValue::RawNumber(_) => 4,
+        _ => 3,
+    };
+    compare_numeric_values(a, b)
+        .then(variant_rank(a).cmp(&variant_rank(b)))
+        .then_with(|| match (a, b) {
+            (JsonValue::RawNumber(a), JsonValue::RawNumber(b)) => a.cmp(b),
+            #[cfg(feature = "decimal")]
+            (JsonValue::Decimal(a), JsonValue::Decimal(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        })
+}
+
+/// Compares two number values by exact numeric value alone.
+fn compare_numeric_values(a: &JsonValue, b: &JsonValue) -> Ordering {
+    match (numeric_value(a), numeric_value(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(a), Err(b)) => compare_integer_float(a, b),
+        (Err(a), Ok(b)) => compare_integer_float(b, a).reverse(),
+        (Err(a), Err(b)) => compare_floats(a, b),
+    }
+}
+
+/// Returns a number value as a widened integer, or as a float for `Number`,
+/// `RawNumber` and fractional `Decimal`s.
+fn numeric_value(value: &JsonValue) -> core::result::Result<i128, f64> {
+    match *value {
+        JsonValue::Integer(i) => Ok(i128::from(i)),
+        JsonValue::Unsigned(u) => Ok(i128::from(u)),
+        JsonValue::Number(f) => Err(f),
+        JsonValue::RawNumber(ref raw) => Err(raw.parse().unwrap_or(f64::NAN)),
+        #[cfg(feature = "decimal")]
+        JsonValue::Decimal(d) => match d.fract().is_zero() {
+            true => d.to_i128().ok_or(f64::NAN),
+            false => Err(d.to_f64().unwrap_or(f64::NAN)),
+        },
+        _ => unreachable!("not a number value"),
+    }
+}
+
+/// Rounds towards zero. Floats of magnitude 2^52 and above are already
+/// whole; this avoids `f64::trunc`, which is unavailable without `std`.
+fn trunc(f: f64) -> f64 {
+    if f.abs() < 4_503_599_627_370_496.0 {
+        (f as i64) as f64
+    } else {
+        f
+    }
+}
+
+/// Compares an integer with a float by exact numeric value.
+fn compare_integer_float(i: i128, f: f64) -> Ordering {
+    const LIMIT: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0; // 2^127
+    if f.is_nan() || f >= LIMIT {
+        return Ordering::Less;
+    }
+    if f < -LIMIT {
+        return Ordering::Greater;
+    }
+    // `f` is within the `i128` range, so its integral part converts exactly.
+    let whole = trunc(f);
+    i.cmp(&(whole as i128))
+        .then_with(|| compare_floats(0.0, f - whole))
+}
+
+impl Index<&str> for JsonValue {
+    type Output = JsonValue;
+
+    /// Looks up an object member.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not an object or has no such key. Use
+    /// [`JsonValue::get`] for a non-panicking lookup.
+    fn index(&self, key: &str) -> &JsonValue {
+        self.get(key)
+            .unwrap_or_else(|| panic!("no key {:?} in JSON value", key))
+    }
+}
+
+impl Index<usize> for JsonValue {
+    type Output = JsonValue;
+
+    /// Looks up an array element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not an array or the index is out of bounds. Use
+    /// [`JsonValue::get_index`] for a non-panicking lookup.
+    fn index(&self, index: usize) -> &JsonValue {
+        self.get_index(index)
+            .unwrap_or_else(|| panic!("no index {} in JSON value", index))
+    }
 }
 
 #[cfg(test)]
@@ -42,6 +1182,61 @@ mod tests {
         assert_eq!(value, JsonValue::Number(123.45));
     }
 
+    #[test]
+    fn test_integer() {
+        let value = JsonValue::Integer(42);
+        assert_eq!(value, JsonValue::Integer(42));
+        assert_ne!(value, JsonValue::Number(42.0));
+    }
+
+    #[test]
+    fn test_as_i64() {
+        assert_eq!(JsonValue::Integer(i64::MAX).as_i64(), Some(i64::MAX));
+        assert_eq!(JsonValue::Integer(i64::MIN).as_i64(), Some(i64::MIN));
+        assert_eq!(JsonValue::Number(3.0).as_i64(), Some(3));
+        assert_eq!(JsonValue::Number(-0.0).as_i64(), Some(0));
+        assert_eq!(JsonValue::Number(3.5).as_i64(), None);
+        assert_eq!(JsonValue::Number(1e20).as_i64(), None);
+        assert_eq!(JsonValue::Null.as_i64(), None);
+    }
+
+    #[test]
+    fn test_as_u64() {
+        assert_eq!(JsonValue::Unsigned(u64::MAX).as_u64(), Some(u64::MAX));
+        assert_eq!(JsonValue::Unsigned(u64::MAX).as_i64(), None);
+        assert_eq!(JsonValue::Integer(7).as_u64(), Some(7));
+        assert_eq!(JsonValue::Integer(-7).as_u64(), None);
+        assert_eq!(JsonValue::Number(3.0).as_u64(), Some(3));
+        assert_eq!(JsonValue::Number(-3.0).as_u64(), None);
+        assert_eq!(JsonValue::Number(1e20).as_u64(), None);
+        assert_eq!(
+            JsonValue::Unsigned(1 << 63).as_f64(),
+            Some(9223372036854775808.0)
+        );
+    }
+
+    #[test]
+    fn test_raw_number_accessors() {
+        let raw = |s: &str| JsonValue::RawNumber(s.to_string());
+        assert_eq!(raw("0.1").as_f64(), Some(0.1));
+        assert_eq!(raw("1e400").as_f64(), Some(f64::INFINITY));
+        assert_eq!(raw("-12").as_i64(), Some(-12));
+        assert_eq!(raw("12.0").as_i64(), Some(12));
+        assert_eq!(raw("12.5").as_i64(), None);
+        assert_eq!(raw("18446744073709551615").as_u64(), Some(u64::MAX));
+        assert_ne!(raw("1.0"), raw("1"));
+        assert!(raw("1") > JsonValue::Number(1.0));
+        assert!(raw("0.5") < JsonValue::Integer(1));
+        assert!(raw("1.0") < raw("1.00"));
+    }
+
+    #[test]
+    fn test_as_f64() {
+        assert_eq!(JsonValue::Integer(7).as_f64(), Some(7.0));
+        assert_eq!(JsonValue::Number(-1.5).as_f64(), Some(-1.5));
+        assert_eq!(JsonValue::String("1".to_string()).as_f64(), None);
+    }
+
     #[test]
     fn test_string() {
         let value = JsonValue::String("test".to_string());
@@ -59,9 +1254,893 @@ mod tests {
 
     #[test]
     fn test_object() {
-        let mut map = HashMap::new();
+        let mut map = Map::new();
         map.insert("key".to_string(), JsonValue::String("value".to_string()));
         let value = JsonValue::Object(map.clone());
         assert_eq!(value, JsonValue::Object(map));
     }
+
+    #[test]
+    fn test_get() {
+        let mut map = Map::new();
+        map.insert("key".to_string(), JsonValue::Boolean(true));
+        let value = JsonValue::Object(map);
+        assert_eq!(value.get("key"), Some(&JsonValue::Boolean(true)));
+        assert_eq!(value.get("missing"), None);
+        assert_eq!(value.get_index(0), None);
+        assert_eq!(JsonValue::Null.get("key"), None);
+    }
+
+    #[test]
+    fn test_get_ci() {
+        let value = crate::parse_json(r#"{"Content-Type": "x", "ÄBC": 1}"#).unwrap();
+        let expected = Some(&JsonValue::from("x"));
+        assert_eq!(value.get_ci("content-type"), expected);
+        assert_eq!(value.get_ci("CONTENT-TYPE"), expected);
+        assert_eq!(value.get_ci("Content-Type"), expected);
+        assert_eq!(value.get("content-type"), None);
+        assert_eq!(value.get_ci("content_type"), None);
+        // Only ASCII letters fold.
+        assert_eq!(value.get_ci("äbc"), None);
+        assert_eq!(value.get_ci("Äbc"), Some(&JsonValue::Integer(1)));
+
+        let value = crate::parse_json(r#"{"ETAG": 1, "etag": 2, "ETag": 3}"#).unwrap();
+        assert_eq!(value.get_ci("ETag"), Some(&JsonValue::Integer(3)));
+        assert!(value.get_ci("Etag").is_some());
+
+        assert_eq!(JsonValue::from("Content-Type").get_ci("content-type"), None);
+    }
+
+    #[test]
+    fn test_get_index() {
+        let value = JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Null]);
+        assert_eq!(value.get_index(0), Some(&JsonValue::Integer(1)));
+        assert_eq!(value.get_index(2), None);
+        assert_eq!(value.get("0"), None);
+        assert_eq!(JsonValue::String("abc".to_string()).get_index(0), None);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut map = Map::new();
+        map.insert(
+            "list".to_string(),
+            JsonValue::Array(vec![JsonValue::Integer(10), JsonValue::Integer(20)]),
+        );
+        let value = JsonValue::Object(map);
+        assert_eq!(value["list"][1], JsonValue::Integer(20));
+    }
+
+    #[test]
+    #[should_panic(expected = "no key \"missing\"")]
+    fn test_index_missing_key() {
+        let _ = &JsonValue::Object(Map::new())["missing"];
+    }
+
+    #[test]
+    #[should_panic(expected = "no index 0")]
+    fn test_index_wrong_type() {
+        let _ = &JsonValue::Null[0];
+    }
+
+    fn pointer_document() -> JsonValue {
+        crate::parse_json(
+            r#"{"user": {"addresses": [{"city": "Rome"}, {"city": "Oslo"}]},
+                "a/b": 1, "m~n": 2, "": 3, "list": [10, 20]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pointer() {
+        let value = pointer_document();
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(
+            value.pointer("/user/addresses/1/city"),
+            Some(&JsonValue::String("Oslo".to_string()))
+        );
+        assert_eq!(value.pointer("/list/0"), Some(&JsonValue::Integer(10)));
+        assert_eq!(value.pointer("/a~1b"), Some(&JsonValue::Integer(1)));
+        assert_eq!(value.pointer("/m~0n"), Some(&JsonValue::Integer(2)));
+        assert_eq!(value.pointer("/"), Some(&JsonValue::Integer(3)));
+    }
+
+    #[test]
+    fn test_pointer_misses() {
+        let value = pointer_document();
+        assert_eq!(value.pointer("user"), None);
+        assert_eq!(value.pointer("/missing"), None);
+        assert_eq!(value.pointer("/list/2"), None);
+        assert_eq!(value.pointer("/list/01"), None);
+        assert_eq!(value.pointer("/list/-"), None);
+        assert_eq!(value.pointer("/list/+1"), None);
+        assert_eq!(value.pointer("/user/addresses/city"), None);
+        assert_eq!(value.pointer("/list/0/deeper"), None);
+    }
+
+    #[test]
+    fn test_pointer_mut() {
+        let mut value = pointer_document();
+        *value.pointer_mut("/user/addresses/0/city").unwrap() = JsonValue::Null;
+        assert!(value["user"]["addresses"][0]["city"].is_null());
+        *value.pointer_mut("").unwrap() = JsonValue::Boolean(true);
+        assert_eq!(value, JsonValue::Boolean(true));
+        assert!(value.pointer_mut("/x").is_none());
+    }
+
+    #[test]
+    fn test_pointer_set_replace() {
+        let mut value = pointer_document();
+        value
+            .pointer_set("/user/addresses/1/city", JsonValue::from("Bergen"))
+            .unwrap();
+        assert_eq!(
+            value["user"]["addresses"][1]["city"].as_str(),
+            Some("Bergen")
+        );
+        value.pointer_set("/a~1b", JsonValue::Null).unwrap();
+        assert!(value["a/b"].is_null());
+        value.pointer_set("/list/0", JsonValue::from(11)).unwrap();
+        assert_eq!(value["list"], crate::json!([11, 20]));
+        value
+            .pointer_set("/user/name", JsonValue::from("Ann"))
+            .unwrap();
+        assert_eq!(value["user"]["name"].as_str(), Some("Ann"));
+
+        value.pointer_set("", JsonValue::from(true)).unwrap();
+        assert_eq!(value, JsonValue::Boolean(true));
+    }
+
+    #[test]
+    fn test_pointer_set_create_missing() {
+        let mut value = crate::json!({"a": {"keep": 1}, "items": []});
+        let err = value.pointer_set("/a/b/c", JsonValue::from(2)).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidPointer);
+        assert_eq!(value, crate::json!({"a": {"keep": 1}, "items": []}));
+
+        value
+            .pointer_set_create("/a/b/c", JsonValue::from(2))
+            .unwrap();
+        value
+            .pointer_set_create("/x/y", JsonValue::from(3))
+            .unwrap();
+        value
+            .pointer_set_create("/items/-/id", JsonValue::from(4))
+            .unwrap();
+        // Created containers are always objects, so `-` only appends to an
+        // array that already exists.
+        value
+            .pointer_set_create("/new/-/id", JsonValue::from(5))
+            .unwrap();
+        assert_eq!(
+            value,
+            crate::json!({
+                "a": {"keep": 1, "b": {"c": 2}},
+                "x": {"y": 3},
+                "items": [{"id": 4}],
+                "new": {"-": {"id": 5}}
+            })
+        );
+    }
+
+    #[test]
+    fn test_pointer_set_array_append() {
+        let mut value = pointer_document();
+        value.pointer_set("/list/-", JsonValue::from(30)).unwrap();
+        value.pointer_set("/list/-", JsonValue::from(40)).unwrap();
+        assert_eq!(value["list"], crate::json!([10, 20, 30, 40]));
+        value
+            .pointer_set("/user/addresses/-", crate::json!({"city": "Lima"}))
+            .unwrap();
+        assert_eq!(value["user"]["addresses"][2]["city"].as_str(), Some("Lima"));
+    }
+
+    #[test]
+    fn test_pointer_set_errors() {
+        let original = pointer_document();
+        for pointer in [
+            "list",
+            "/list/4",
+            "/list/01",
+            "/missing/x",
+            "/list/0/x",
+            "/a~1b/c",
+            "/list/1/x/y",
+        ] {
+            let mut value = original.clone();
+            for create in [false, true] {
+                let result = if create {
+                    value.pointer_set_create(pointer, JsonValue::Null)
+                } else {
+                    value.pointer_set(pointer, JsonValue::Null)
+                };
+                if create && pointer == "/missing/x" {
+                    assert!(result.is_ok());
+                    continue;
+                }
+                let err = result.unwrap_err();
+                assert_eq!(err.kind(), ParseErrorKind::InvalidPointer, "{}", pointer);
+                assert!(err.to_string().contains(pointer), "{}", err);
+                assert_eq!(value, original, "{}", pointer);
+            }
+        }
+        let err = pointer_document()
+            .pointer_set("/list/0/x", JsonValue::Null)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Path runs through a scalar: \"/list/0/x\"");
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut value = JsonValue::Object(Map::new());
+        assert_eq!(value.insert("a", JsonValue::Integer(1)), None);
+        assert_eq!(
+            value.insert("a", JsonValue::Integer(2)),
+            Some(JsonValue::Integer(1))
+        );
+        assert_eq!(value["a"], JsonValue::Integer(2));
+
+        assert_eq!(value.remove("a"), Some(JsonValue::Integer(2)));
+        assert_eq!(value.remove("a"), None);
+        assert_eq!(value, JsonValue::Object(Map::new()));
+
+        let mut scalar = JsonValue::Integer(1);
+        assert_eq!(scalar.insert("a", JsonValue::Null), None);
+        assert_eq!(scalar.remove("a"), None);
+        assert_eq!(scalar, JsonValue::Integer(1));
+    }
+
+    #[test]
+    fn test_push() {
+        let mut value = JsonValue::Array(vec![]);
+        value.push(JsonValue::Integer(1));
+        value.push(JsonValue::Null);
+        assert_eq!(
+            value,
+            JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Null])
+        );
+
+        let mut object = JsonValue::Object(Map::new());
+        object.push(JsonValue::Null);
+        assert_eq!(object, JsonValue::Object(Map::new()));
+    }
+
+    #[test]
+    fn test_entries_and_iter() {
+        let value = crate::parse_json(r#"{"a": [1, 2, [3]], "b": {"c": null}}"#).unwrap();
+
+        let mut keys: Vec<&str> = value.entries().unwrap().map(|(k, _)| k.as_str()).collect();
+        keys.sort();
+        assert_eq!(keys, ["a", "b"]);
+        assert!(value.iter().is_none());
+
+        let numbers: Vec<i64> = value["a"]
+            .iter()
+            .unwrap()
+            .filter_map(JsonValue::as_i64)
+            .collect();
+        assert_eq!(numbers, [1, 2]);
+        assert!(value["a"].entries().is_none());
+        assert_eq!(value["b"].entries().unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_keys_and_values() {
+        let value = crate::parse_json(r#"{"id": 1, "name": "Ann", "tags": []}"#).unwrap();
+        let object = value.as_object().unwrap();
+
+        let mut keys: Vec<&str> = value.keys().unwrap().collect();
+        keys.sort();
+        assert_eq!(keys, ["id", "name", "tags"]);
+        // Keys and values borrow from the map itself, in the same order.
+        for ((key, member), (entry_key, entry_value)) in value
+            .keys()
+            .unwrap()
+            .zip(value.values().unwrap())
+            .zip(object.iter())
+        {
+            assert!(core::ptr::eq(key, entry_key.as_str()));
+            assert!(core::ptr::eq(member, entry_value));
+        }
+        assert_eq!(value.values().unwrap().count(), 3);
+
+        assert!(value["tags"].keys().is_none());
+        assert!(value["tags"].values().is_none());
+        assert_eq!(JsonValue::Object(Map::new()).keys().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        fn count_leaves(value: &JsonValue) -> usize {
+            match value {
+                JsonValue::Array(_) | JsonValue::Object(_) => {
+                    value.into_iter().map(count_leaves).sum()
+                }
+                _ => 1,
+            }
+        }
+
+        let value = crate::parse_json(r#"{"a": [1, 2, [3]], "b": {"c": null}, "d": []}"#).unwrap();
+        assert_eq!(count_leaves(&value), 4);
+        assert_eq!((&value).into_iter().size_hint(), (3, Some(3)));
+        assert_eq!((&JsonValue::Integer(1)).into_iter().next(), None);
+
+        let mut seen = 0;
+        for child in &value["a"] {
+            assert!(child.as_i64().is_some() || child.as_array().is_some());
+            seen += 1;
+        }
+        assert_eq!(seen, 3);
+    }
+
+    fn hash_of(value: &JsonValue) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_float_equality() {
+        assert_eq!(JsonValue::Number(f64::NAN), JsonValue::Number(-f64::NAN));
+        assert_eq!(JsonValue::Number(0.0), JsonValue::Number(-0.0));
+        assert_ne!(JsonValue::Number(1.0), JsonValue::Number(f64::NAN));
+        assert_eq!(
+            hash_of(&JsonValue::Number(f64::NAN)),
+            hash_of(&JsonValue::Number(-f64::NAN))
+        );
+        assert_eq!(
+            hash_of(&JsonValue::Number(0.0)),
+            hash_of(&JsonValue::Number(-0.0))
+        );
+    }
+
+    #[test]
+    fn test_hash_set() {
+        use std::collections::HashSet;
+
+        let inputs = [
+            r#"{"a": 1, "b": [true, null]}"#,
+            r#"{"b": [true, null], "a": 1}"#,
+            r#"{"a": 1.0, "b": [true, null]}"#,
+            r#"[1, "x"]"#,
+            r#"[1, "x"]"#,
+            r#""x""#,
+        ];
+        let set: HashSet<JsonValue> = inputs
+            .iter()
+            .map(|input| crate::parse_json(input).unwrap())
+            .chain([JsonValue::Number(f64::NAN), JsonValue::Number(f64::NAN)])
+            .collect();
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(&crate::parse_json(r#"{"b": [true, null], "a": 1}"#).unwrap()));
+        assert!(set.contains(&JsonValue::Number(f64::NAN)));
+    }
+
+    #[test]
+    fn test_ordering_across_types() {
+        let mut values = crate::parse_json(
+            r#"[{"b": 1}, [2], "b", 3.5, true, null, {"a": 2}, [1, 5], "a", -1, false, [1], 2]"#,
+        )
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .clone();
+        values.sort();
+        let sorted = crate::parse_json(
+            r#"[null, false, true, -1, 2, 3.5, "a", "b", [1], [1, 5], [2], {"a": 2}, {"b": 1}]"#,
+        )
+        .unwrap();
+        assert_eq!(&values, sorted.as_array().unwrap());
+    }
+
+    #[test]
+    fn test_raw_compares_by_text() {
+        let raw = JsonValue::Raw("[1, 2]".to_string());
+        assert_eq!(raw, JsonValue::Raw("[1, 2]".to_string()));
+        assert_ne!(raw, JsonValue::Raw("[1,2]".to_string()));
+        assert_ne!(raw, crate::parse_json("[1, 2]").unwrap());
+        assert!(raw > JsonValue::Object(Map::new()));
+        assert!(raw < JsonValue::Raw("[2]".to_string()));
+        assert_eq!(raw.to_string(), "[1, 2]");
+    }
+
+    #[test]
+    fn test_ordering_numbers() {
+        let big = 9_007_199_254_740_993;
+        assert!(JsonValue::Integer(big) > JsonValue::Number(big as f64));
+        assert!(JsonValue::Integer(1) < JsonValue::Number(1.5));
+        assert!(JsonValue::Integer(-1) > JsonValue::Number(-1.5));
+        assert!(JsonValue::Integer(1) < JsonValue::Number(1.0));
+        assert!(JsonValue::Integer(i64::MAX) < JsonValue::Number(1e19));
+        assert!(JsonValue::Integer(i64::MIN) > JsonValue::Number(-1e19));
+        assert!(JsonValue::Integer(i64::MAX) < JsonValue::Unsigned(u64::MAX));
+        assert!(JsonValue::Unsigned(u64::MAX) < JsonValue::Number(1e20));
+        assert!(JsonValue::Unsigned(1 << 63) < JsonValue::Number(9223372036854775808.0));
+        assert!(JsonValue::Unsigned(u64::MAX) > JsonValue::Number(1e19));
+        assert!(JsonValue::Number(f64::INFINITY) < JsonValue::Number(f64::NAN));
+        assert!(JsonValue::Integer(i64::MAX) < JsonValue::Number(f64::NAN));
+        assert_eq!(
+            JsonValue::Number(0.0).partial_cmp(&JsonValue::Number(-0.0)),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn test_ordering_objects_ignore_member_order() {
+        let a = crate::parse_json(r#"{"x": 1, "y": 2}"#).unwrap();
+        let b = crate::parse_json(r#"{"y": 2, "x": 1}"#).unwrap();
+        let c = crate::parse_json(r#"{"x": 1, "y": 3}"#).unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+        assert!(a < c);
+    }
+
+    /// One value of every variant, used to check accessors against mismatches.
+    fn all_variants() -> Vec<JsonValue> {
+        vec![
+            JsonValue::Null,
+            JsonValue::Boolean(true),
+            JsonValue::Integer(1),
+            JsonValue::Number(1.5),
+            JsonValue::String("s".to_string()),
+            JsonValue::Array(vec![JsonValue::Null]),
+            JsonValue::Object(Map::new()),
+        ]
+    }
+
+    #[test]
+    fn test_is_null() {
+        let results: Vec<bool> = all_variants().iter().map(JsonValue::is_null).collect();
+        assert_eq!(results, [true, false, false, false, false, false, false]);
+    }
+
+    #[test]
+    fn test_as_bool() {
+        let results: Vec<_> = all_variants().iter().map(JsonValue::as_bool).collect();
+        assert_eq!(results, [None, Some(true), None, None, None, None, None]);
+    }
+
+    #[test]
+    fn test_as_str() {
+        let values = all_variants();
+        let results: Vec<_> = values.iter().map(JsonValue::as_str).collect();
+        assert_eq!(results, [None, None, None, None, Some("s"), None, None]);
+    }
+
+    #[test]
+    fn test_as_f64_all_variants() {
+        let results: Vec<_> = all_variants().iter().map(JsonValue::as_f64).collect();
+        assert_eq!(
+            results,
+            [None, None, Some(1.0), Some(1.5), None, None, None]
+        );
+    }
+
+    #[test]
+    fn test_as_array() {
+        let values = all_variants();
+        let matched: Vec<_> = values.iter().map(|v| v.as_array().is_some()).collect();
+        assert_eq!(matched, [false, false, false, false, false, true, false]);
+        assert_eq!(values[5].as_array(), Some(&vec![JsonValue::Null]));
+
+        let mut value = JsonValue::Array(vec![]);
+        value.as_array_mut().unwrap().push(JsonValue::Integer(7));
+        assert_eq!(value, JsonValue::Array(vec![JsonValue::Integer(7)]));
+        assert!(JsonValue::Null.as_array_mut().is_none());
+    }
+
+    #[test]
+    fn test_as_object() {
+        let values = all_variants();
+        let matched: Vec<_> = values.iter().map(|v| v.as_object().is_some()).collect();
+        assert_eq!(matched, [false, false, false, false, false, false, true]);
+
+        let mut value = JsonValue::Object(Map::new());
+        value
+            .as_object_mut()
+            .unwrap()
+            .insert("k".to_string(), JsonValue::Null);
+        assert_eq!(value.get("k"), Some(&JsonValue::Null));
+        assert!(JsonValue::Boolean(false).as_object_mut().is_none());
+    }
+
+    #[test]
+    fn test_semantic_eq_ignores_member_order() {
+        let parsed = crate::parse_json(r#"{"z": 1, "a": [true, {"y": null, "b": "s"}]}"#).unwrap();
+        // Rebuild every object from a sorted `BTreeMap`, so with
+        // `preserve_order` the members are stored in a different order.
+        fn sorted(value: &JsonValue) -> JsonValue {
+            match value {
+                JsonValue::Array(values) => JsonValue::Array(values.iter().map(sorted).collect()),
+                JsonValue::Object(map) => JsonValue::Object(
+                    map.iter()
+                        .map(|(k, v)| (k.clone(), sorted(v)))
+                        .collect::<alloc::collections::BTreeMap<_, _>>()
+                        .into_iter()
+                        .collect(),
+                ),
+                other => other.clone(),
+            }
+        }
+        let rebuilt = sorted(&parsed);
+        assert!(parsed.semantic_eq(&rebuilt));
+        assert!(rebuilt.semantic_eq(&parsed));
+
+        let missing = crate::parse_json(r#"{"z": 1, "a": [true, {"y": null}]}"#).unwrap();
+        assert!(!parsed.semantic_eq(&missing));
+        assert!(!missing.semantic_eq(&parsed));
+        let reordered =
+            crate::parse_json(r#"{"z": 1, "a": [{"y": null, "b": "s"}, true]}"#).unwrap();
+        assert!(!parsed.semantic_eq(&reordered));
+    }
+
+    #[test]
+    fn test_semantic_eq_numbers() {
+        assert!(JsonValue::Integer(1).semantic_eq(&JsonValue::Number(1.0)));
+        assert_ne!(JsonValue::Integer(1), JsonValue::Number(1.0));
+        assert!(JsonValue::RawNumber("1.00".to_string()).semantic_eq(&JsonValue::Integer(1)));
+        assert!(JsonValue::Unsigned(u64::MAX).semantic_eq(&JsonValue::Unsigned(u64::MAX)));
+        assert!(!JsonValue::Unsigned(u64::MAX)
+            .semantic_eq(&JsonValue::Number(u64::MAX as f64 - 4096.0)));
+        assert!(JsonValue::Number(f64::NAN).semantic_eq(&JsonValue::Number(f64::NAN)));
+        assert!(!JsonValue::Integer(1).semantic_eq(&JsonValue::String("1".to_string())));
+
+        let sum = JsonValue::Number(0.1 + 0.2);
+        assert!(!sum.semantic_eq(&JsonValue::Number(0.3)));
+        assert!(sum.semantic_eq_with_tolerance(&JsonValue::Number(0.3), 1e-9));
+        assert!(!sum.semantic_eq_with_tolerance(&JsonValue::Number(0.31), 1e-9));
+        let a = JsonValue::Array(vec![JsonValue::Integer(10), JsonValue::Number(2.0000001)]);
+        let b = JsonValue::Array(vec![JsonValue::Number(10.05), JsonValue::Integer(2)]);
+        assert!(a.semantic_eq_with_tolerance(&b, 0.1));
+        assert!(!a.semantic_eq(&b));
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut value = crate::parse_json(r#"{"user": {"tags": ["a"]}, "n": 1}"#).unwrap();
+        *value.get_mut("n").unwrap() = JsonValue::Integer(2);
+        let tag = value
+            .get_mut("user")
+            .and_then(|user| user.get_mut("tags"))
+            .and_then(|tags| tags.get_index_mut(0))
+            .unwrap();
+        *tag = JsonValue::from("b");
+        assert_eq!(value["n"], JsonValue::Integer(2));
+        assert_eq!(value["user"]["tags"][0], JsonValue::from("b"));
+
+        assert!(value.get_mut("missing").is_none());
+        assert!(value["n"].clone().get_mut("n").is_none());
+        assert!(value.get_index_mut(0).is_none());
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut value = JsonValue::Object(Map::new());
+        *value
+            .entry("count")
+            .unwrap()
+            .or_insert(JsonValue::Integer(0)) = JsonValue::Integer(1);
+        value
+            .entry("count")
+            .unwrap()
+            .and_modify(|count| *count = JsonValue::Integer(count.as_i64().unwrap() + 1))
+            .or_insert(JsonValue::Integer(0));
+        value
+            .entry("tags")
+            .unwrap()
+            .or_insert_with(|| JsonValue::Array(Vec::new()))
+            .push(JsonValue::from("x"));
+        assert_eq!(value["count"], JsonValue::Integer(2));
+        assert_eq!(value["tags"], JsonValue::Array(vec![JsonValue::from("x")]));
+
+        assert!(JsonValue::Null.entry("count").is_none());
+    }
+
+    #[test]
+    fn test_find_all_numbers() {
+        let value = crate::parse_json(
+            r#"[1, "two", {"a": 3.5, "b": [null, -4, {"c": true}], "d": "5"}, [[6]], 18446744073709551615]"#,
+        )
+        .unwrap();
+        let is_number = |v: &JsonValue| v.as_f64().is_some();
+        let numbers = value.find_all(is_number);
+        assert_eq!(numbers.len(), 5);
+        assert_eq!(numbers[0], &JsonValue::Integer(1));
+        assert_eq!(numbers[3], &JsonValue::Integer(6));
+        assert_eq!(numbers[4], &JsonValue::Unsigned(u64::MAX));
+        let mut nested: Vec<f64> = numbers[1..3].iter().filter_map(|n| n.as_f64()).collect();
+        nested.sort_by(f64::total_cmp);
+        assert_eq!(nested, [-4.0, 3.5]);
+
+        assert_eq!(value.find_first(is_number), Some(&JsonValue::Integer(1)));
+        assert_eq!(value[3].find_first(is_number), Some(&JsonValue::Integer(6)));
+        assert_eq!(
+            value.find_first(|v| v.as_bool() == Some(true)),
+            Some(&JsonValue::Boolean(true))
+        );
+        assert!(value.find_first(|v| v.as_str() == Some("six")).is_none());
+
+        // The root itself is a candidate.
+        assert_eq!(value.find_all(|v| v.as_array().is_some()).len(), 4);
+        assert_eq!(
+            JsonValue::Integer(9).find_all(is_number),
+            [&JsonValue::Integer(9)]
+        );
+    }
+
+    #[test]
+    fn test_merge_shallow() {
+        let mut value = crate::json!({"a": 1, "b": {"x": 1, "y": 2}, "c": [1]});
+        value.merge(
+            crate::json!({"b": {"x": 9}, "c": null, "d": "new"}),
+            false,
+            false,
+        );
+        assert_eq!(
+            value,
+            crate::json!({"a": 1, "b": {"x": 9}, "c": null, "d": "new"})
+        );
+    }
+
+    #[test]
+    fn test_merge_deep() {
+        let base = crate::json!({
+            "server": {"host": "localhost", "tls": {"enabled": false, "cert": "a.pem"}},
+            "features": ["x", "y"],
+            "name": "base"
+        });
+        let layer = crate::json!({
+            "server": {"tls": {"enabled": true}, "port": 8080},
+            "features": ["z"],
+            "name": {"first": "layered"}
+        });
+
+        let mut merged = base.clone();
+        merged.merge(layer.clone(), true, false);
+        assert_eq!(
+            merged,
+            crate::json!({
+                "server": {
+                    "host": "localhost",
+                    "tls": {"enabled": true, "cert": "a.pem"},
+                    "port": 8080
+                },
+                "features": ["z"],
+                "name": {"first": "layered"}
+            })
+        );
+
+        let mut merged = base.clone();
+        merged.merge(layer.clone(), true, true);
+        assert_eq!(merged["features"], crate::json!(["x", "y", "z"]));
+        assert_eq!(merged["server"]["tls"]["cert"], JsonValue::from("a.pem"));
+
+        // A shallow merge replaces nested arrays whatever the flag says.
+        let mut merged = base;
+        merged.merge(layer, false, true);
+        assert_eq!(merged["features"], crate::json!(["z"]));
+
+        for deep in [false, true] {
+            let mut list = crate::json!([1, {"a": 1}]);
+            list.merge(crate::json!([{"a": 2}]), deep, true);
+            assert_eq!(list, crate::json!([1, {"a": 1}, {"a": 2}]));
+        }
+    }
+
+    #[test]
+    fn test_merge_non_objects() {
+        for deep in [false, true] {
+            let mut value = crate::json!({"a": 1});
+            value.merge(crate::json!([1, 2]), deep, false);
+            assert_eq!(value, crate::json!([1, 2]));
+
+            let mut value = JsonValue::from("text");
+            value.merge(crate::json!({"a": 1}), deep, false);
+            assert_eq!(value, crate::json!({"a": 1}));
+
+            let mut value = crate::json!([1]);
+            value.merge(crate::json!([2]), deep, false);
+            assert_eq!(value, crate::json!([2]));
+
+            let mut value = crate::json!([1]);
+            value.merge(JsonValue::Null, deep, true);
+            assert!(value.is_null());
+        }
+    }
+
+    #[test]
+    fn test_flatten() {
+        let value = crate::parse_json(
+            r#"{"user": {"name": "Ann", "address": {"city": "Oslo"}},
+                "items": [{"price": 1.5}, {"price": 2}],
+                "flags": [true, null]}"#,
+        )
+        .unwrap();
+        let flat = value.flatten();
+        let expected = [
+            ("user.name", JsonValue::String("Ann".to_string())),
+            ("user.address.city", JsonValue::String("Oslo".to_string())),
+            ("items.0.price", JsonValue::Number(1.5)),
+            ("items.1.price", JsonValue::Integer(2)),
+            ("flags.0", JsonValue::Boolean(true)),
+            ("flags.1", JsonValue::Null),
+        ];
+        assert_eq!(flat.len(), expected.len());
+        for (path, leaf) in expected {
+            assert_eq!(flat.get(path), Some(&leaf), "{}", path);
+        }
+
+        let flat = value.flatten_with("/");
+        assert_eq!(flat["items/1/price"], JsonValue::Integer(2));
+    }
+
+    #[test]
+    fn test_flatten_empty_and_scalar() {
+        let value = crate::parse_json(r#"{"a": {}, "b": [], "c": {"": [1]}}"#).unwrap();
+        let flat = value.flatten();
+        assert_eq!(flat.len(), 3);
+        assert_eq!(flat["a"], JsonValue::Object(Map::new()));
+        assert_eq!(flat["b"], JsonValue::Array(Vec::new()));
+        assert_eq!(flat["c..0"], JsonValue::Integer(1));
+
+        assert!(JsonValue::Array(Vec::new()).flatten().is_empty());
+        assert!(JsonValue::Object(Map::new()).flatten().is_empty());
+        let flat = JsonValue::Integer(7).flatten();
+        assert_eq!(flat.len(), 1);
+        assert_eq!(flat[""], JsonValue::Integer(7));
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let expected = crate::parse_json(
+            r#"{"stats": {"mean": 0.3, "ratios": [0.7, 1e-9, -2.5]}, "label": "run", "n": 3}"#,
+        )
+        .unwrap();
+        let mut computed = expected.clone();
+        *computed.pointer_mut("/stats/mean").unwrap() = JsonValue::Number(0.1 + 0.2);
+        *computed.pointer_mut("/stats/ratios/0").unwrap() = JsonValue::Number(0.1 * 7.0);
+        assert_ne!(computed, expected);
+        assert!(computed.approx_eq(&expected, 1e-12));
+        assert!(expected.approx_eq(&computed, 1e-12));
+        assert!(!computed.approx_eq(&expected, 0.0));
+
+        let mut changed = computed.clone();
+        *changed.pointer_mut("/stats/ratios/1").unwrap() = JsonValue::Number(2e-9);
+        assert!(!changed.approx_eq(&expected, 1e-12));
+        assert!(changed.approx_eq(&expected, 1e-6));
+
+        // Tolerance applies to numbers only; everything else must match.
+        let mut relabeled = computed.clone();
+        relabeled
+            .as_object_mut()
+            .unwrap()
+            .insert("label".to_string(), JsonValue::from("Run"));
+        assert!(!relabeled.approx_eq(&expected, 1.0));
+        let mut extra = computed;
+        extra
+            .as_object_mut()
+            .unwrap()
+            .insert("x".to_string(), JsonValue::Null);
+        assert!(!extra.approx_eq(&expected, 1.0));
+        assert!(!JsonValue::Integer(1).approx_eq(&JsonValue::from("1"), 1.0));
+        assert!(JsonValue::Number(f64::NAN).approx_eq(&JsonValue::Number(f64::NAN), 0.0));
+    }
+
+    #[test]
+    fn test_len_and_is_empty() {
+        let cases = [
+            ("[]", Some(0)),
+            ("[1, [2, 3], null]", Some(3)),
+            ("{}", Some(0)),
+            (r#"{"a": 1, "b": {"c": 2}}"#, Some(2)),
+            ("null", None),
+            ("true", None),
+            ("0", None),
+            ("-1.5", None),
+            ("18446744073709551615", None),
+            ("\"\"", None),
+            ("\"abc\"", None),
+        ];
+        for (input, len) in cases {
+            let value = crate::parse_json(input).unwrap();
+            assert_eq!(value.len(), len, "{}", input);
+            assert_eq!(value.is_empty(), len.map(|len| len == 0), "{}", input);
+        }
+        assert_eq!(JsonValue::RawNumber("1".to_string()).len(), None);
+        assert_eq!(JsonValue::SharedString(Arc::from("")).is_empty(), None);
+        assert_eq!(
+            JsonValue::Array(vec![JsonValue::Null]).is_empty(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_intern_strings() {
+        let states = ["open", "closed", "pending"];
+        let items: Vec<JsonValue> = (0..3000)
+            .map(|i| {
+                let mut map = Map::new();
+                map.insert("state".to_string(), JsonValue::from(states[i % 3]));
+                map.insert("id".to_string(), JsonValue::Integer(i as i64));
+                JsonValue::Object(map)
+            })
+            .collect();
+        let original = JsonValue::Array(items);
+        let mut value = original.clone();
+        value.intern_strings();
+
+        // 3000 strings now share three allocations.
+        let mut allocations = BTreeSet::new();
+        for item in value.as_array().unwrap() {
+            match &item["state"] {
+                JsonValue::SharedString(s) => {
+                    allocations.insert(Arc::as_ptr(s) as *const u8 as usize);
+                    assert_eq!(Arc::strong_count(s), 1000);
+                }
+                other => panic!("not interned: {:?}", other),
+            }
+        }
+        assert_eq!(allocations.len(), 3);
+
+        assert_eq!(value, original);
+        assert_eq!(value.to_string(), original.to_string());
+        assert_eq!(value.fingerprint(), original.fingerprint());
+        assert_eq!(value[7]["state"].as_str(), Some("closed"));
+
+        // Interning again merges separately interned copies into one pool.
+        let mut pair = JsonValue::Array(vec![
+            JsonValue::SharedString(Arc::from("x")),
+            JsonValue::SharedString(Arc::from("x")),
+        ]);
+        pair.intern_strings();
+        match (&pair[0], &pair[1]) {
+            (JsonValue::SharedString(a), JsonValue::SharedString(b)) => assert!(Arc::ptr_eq(a, b)),
+            _ => panic!("not interned"),
+        }
+    }
+
+    #[test]
+    fn test_shared_string_matches_string() {
+        use core::hash::BuildHasher;
+        let owned = JsonValue::String("abc".to_string());
+        let shared = JsonValue::SharedString(Arc::from("abc"));
+        assert_eq!(shared, owned);
+        assert_eq!(owned, shared);
+        assert_eq!(shared.cmp(&owned), Ordering::Equal);
+        assert!(shared < JsonValue::String("abd".to_string()));
+        assert!(shared > JsonValue::Integer(1));
+        let state = std::hash::RandomState::new();
+        assert_eq!(state.hash_one(&shared), state.hash_one(&owned));
+        assert_eq!(shared.clone().into_string(), Some("abc".to_string()));
+        assert_eq!(String::try_from(shared.clone()).unwrap(), "abc");
+        assert_eq!(shared.to_string(), "\"abc\"");
+    }
+
+    #[test]
+    fn test_into_variants() {
+        let text = "a fairly long string".to_string();
+        let text_ptr = text.as_ptr();
+        let value = JsonValue::String(text);
+        // The buffer is moved out, not copied.
+        let moved = value.into_string().unwrap();
+        assert_eq!(moved.as_ptr(), text_ptr);
+        assert_eq!(JsonValue::Integer(1).into_string(), None);
+
+        let values = vec![JsonValue::from("x"), JsonValue::Null];
+        let values_ptr = values.as_ptr();
+        let array = JsonValue::Array(values);
+        let drained = array.into_array().unwrap();
+        assert_eq!(drained.as_ptr(), values_ptr);
+        let strings: Vec<String> = drained
+            .into_iter()
+            .filter_map(JsonValue::into_string)
+            .collect();
+        assert_eq!(strings, ["x"]);
+        assert_eq!(JsonValue::Null.into_array(), None);
+
+        let mut map = Map::new();
+        map.insert("k".to_string(), JsonValue::from("v"));
+        let object = JsonValue::Object(map.clone());
+        assert_eq!(object.into_object(), Some(map));
+        assert_eq!(JsonValue::Array(vec![]).into_object(), None);
+    }
 }
@@ -0,0 +1,179 @@
+use crate::value::{JsonValue, Map};
+
+/// A callback for each node of a tree walked by [`JsonValue::accept`].
+///
+/// Every method has an empty default, so an implementation only overrides
+/// the kinds of value it cares about. Containers are reported before their
+/// contents: `visit_array` and `visit_object` see the whole container, and
+/// the walk then descends into it on its own. Each object member reports its
+/// key through `visit_key` just before its value.
+///
+/// ```rust
+/// use arjp::{json, JsonValue, Visitor};
+///
+/// struct CountNumbers(usize);
+///
+/// impl Visitor for CountNumbers {
+///     fn visit_number(&mut self, _value: &JsonValue) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let mut counter = CountNumbers(0);
+/// json!({"a": [1, 2.5], "b": {"c": -3}}).accept(&mut counter);
+/// assert_eq!(counter.0, 3);
+/// ```
+pub trait Visitor {
+    /// Called for a `null`.
+    fn visit_null(&mut self) {}
+
+    /// Called for a boolean.
+    fn visit_bool(&mut self, _value: bool) {}
+
+    /// Called for a number of any variant; use [`JsonValue::as_i64`],
+    /// [`JsonValue::as_f64`] and friends to read it.
+    fn visit_number(&mut self, _value: &JsonValue) {}
+
+    /// Called for a string, whether `String` or `SharedString`.
+    fn visit_string(&mut self, _value: &str) {}
+
+    /// Called for an array, before its elements are visited.
+    fn visit_array(&mut self, _values: &[JsonValue]) {}
+
+    /// Called for an object, before its members are visited.
+    fn visit_object(&mut self, _map: &Map) {}
+
+    /// Called with each object key, just before the member's value is
+    /// visited.
+    fn visit_key(&mut self, _key: &str) {}
+
+    /// Called for a [`JsonValue::Raw`] with its unparsed source text.
+    fn visit_raw(&mut self, _text: &str) {}
+}
+
+impl JsonValue {
+    /// Walks this value depth-first, calling the matching [`Visitor`] method
+    /// for it and for every value nested inside it.
+    ///
+    /// Array elements are visited in order; object members in the map's
+    /// iteration order.
+    pub fn accept<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        match self {
+            JsonValue::Null => visitor.visit_null(),
+            JsonValue::Boolean(b) => visitor.visit_bool(*b),
+            JsonValue::String(s) => visitor.visit_string(s),
+            JsonValue::SharedString(s) => visitor.visit_string(s),
+            JsonValue::Raw(raw) => visitor.visit_raw(raw),
+            JsonValue::Array(values) => {
+                visitor.visit_array(values);
+                for value in values {
+                    value.accept(visitor);
+                }
+            }
+            JsonValue::Object(map) => {
+                visitor.visit_object(map);
+                for (key, value) in map {
+                    visitor.visit_key(key);
+                    value.accept(visitor);
+                }
+            }
+            number => visitor.visit_number(number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[derive(Default)]
+    struct StringLengths {
+        strings: usize,
+        total: usize,
+        keys: usize,
+    }
+
+    impl Visitor for StringLengths {
+        fn visit_string(&mut self, value: &str) {
+            self.strings += 1;
+            self.total += value.chars().count();
+        }
+
+        fn visit_key(&mut self, key: &str) {
+            self.keys += key.len();
+        }
+    }
+
+    #[test]
+    fn test_visitor_counts_string_lengths() {
+        let mut value = crate::parse_json(
+            r#"{"name": "Zoë", "tags": ["a", "bc", 3, null], "nested": {"x": [{"y": "def"}], "z": true}}"#,
+        )
+        .unwrap();
+        let mut lengths = StringLengths::default();
+        value.accept(&mut lengths);
+        assert_eq!(lengths.strings, 4);
+        assert_eq!(lengths.total, 9);
+        assert_eq!(lengths.keys, "nametagsnestedxyz".len());
+
+        // Interned strings are reported the same way.
+        value.intern_strings();
+        let mut interned = StringLengths::default();
+        value.accept(&mut interned);
+        assert_eq!((interned.strings, interned.total), (4, 9));
+    }
+
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl Visitor for Trace {
+        fn visit_null(&mut self) {
+            self.0.push("null".into());
+        }
+
+        fn visit_bool(&mut self, value: bool) {
+            self.0.push(alloc::format!("{}", value));
+        }
+
+        fn visit_number(&mut self, value: &JsonValue) {
+            self.0.push(alloc::format!("{}", value));
+        }
+
+        fn visit_string(&mut self, value: &str) {
+            self.0.push(alloc::format!("{:?}", value));
+        }
+
+        fn visit_array(&mut self, values: &[JsonValue]) {
+            self.0.push(alloc::format!("array({})", values.len()));
+        }
+
+        fn visit_object(&mut self, map: &Map) {
+            self.0.push(alloc::format!("object({})", map.len()));
+        }
+    }
+
+    #[test]
+    fn test_visitor_order() {
+        let value =
+            crate::parse_json(r#"[1, [true, null], {"k": 18446744073709551615}, 2.5, "s"]"#)
+                .unwrap();
+        let mut trace = Trace::default();
+        value.accept(&mut trace);
+        assert_eq!(
+            trace.0,
+            [
+                "array(5)",
+                "1",
+                "array(2)",
+                "true",
+                "null",
+                "object(1)",
+                "18446744073709551615",
+                "2.5",
+                "\"s\"",
+            ]
+        );
+    }
+}
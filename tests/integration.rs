@@ -1,5 +1,10 @@
-use arjp::{parse_json, JsonParser, JsonValue};
-use std::collections::HashMap;
+use arjp::{
+    apply_patch, parse_framed, parse_json, parse_json_bytes, parse_json_owned, parse_lines,
+    parse_many, BorrowedValue, JsonParser, JsonValue, Map, ParseErrorKind, SerializeOptions,
+    SpannedKind, DEFAULT_MAX_DEPTH,
+};
+#[cfg(feature = "std")]
+use arjp::{parse_json_reader, ReaderParser};
 
 #[test]
 fn test_parse_null() {
@@ -55,8 +60,8 @@ fn test_parse_string() {
 #[test]
 fn test_parse_number() {
     let mut parser_int = JsonParser::new("123");
-    assert_eq!(parser_int.parse().unwrap(), JsonValue::Number(123.0));
-    assert_eq!(parse_json("123").unwrap(), JsonValue::Number(123.0));
+    assert_eq!(parser_int.parse().unwrap(), JsonValue::Integer(123));
+    assert_eq!(parse_json("123").unwrap(), JsonValue::Integer(123));
 
     let mut parser_float = JsonParser::new("-456.789");
     assert_eq!(parser_float.parse().unwrap(), JsonValue::Number(-456.789));
@@ -77,7 +82,7 @@ fn test_parse_array() {
     assert_eq!(
         parser.parse().unwrap(),
         JsonValue::Array(vec![
-            JsonValue::Number(1.0),
+            JsonValue::Integer(1),
             JsonValue::String("test".to_string()),
             JsonValue::Boolean(true)
         ])
@@ -85,7 +90,7 @@ fn test_parse_array() {
     assert_eq!(
         parse_json("[1, \"test\", true]").unwrap(),
         JsonValue::Array(vec![
-            JsonValue::Number(1.0),
+            JsonValue::Integer(1),
             JsonValue::String("test".to_string()),
             JsonValue::Boolean(true)
         ])
@@ -95,16 +100,13 @@ fn test_parse_array() {
 #[test]
 fn test_parse_object() {
     let mut parser_empty = JsonParser::new("{}");
-    assert_eq!(
-        parser_empty.parse().unwrap(),
-        JsonValue::Object(HashMap::new())
-    );
-    assert_eq!(parse_json("{}").unwrap(), JsonValue::Object(HashMap::new()));
+    assert_eq!(parser_empty.parse().unwrap(), JsonValue::Object(Map::new()));
+    assert_eq!(parse_json("{}").unwrap(), JsonValue::Object(Map::new()));
 
     let mut parser = JsonParser::new("{\"name\": \"John\", \"age\": 30}");
-    let mut expected = HashMap::new();
+    let mut expected = Map::new();
     expected.insert("name".to_string(), JsonValue::String("John".to_string()));
-    expected.insert("age".to_string(), JsonValue::Number(30.0));
+    expected.insert("age".to_string(), JsonValue::Integer(30));
     assert_eq!(parser.parse().unwrap(), JsonValue::Object(expected.clone()));
     assert_eq!(
         parse_json("{\"name\": \"John\", \"age\": 30}").unwrap(),
@@ -126,3 +128,757 @@ fn test_parse_errors() {
     assert!(parser_unterminated_array.parse().is_err());
     assert!(parse_json("[1,").is_err());
 }
+
+#[test]
+fn test_parse_error_kinds() {
+    let cases = [
+        ("\"unterminated", ParseErrorKind::UnterminatedString),
+        ("invalid", ParseErrorKind::UnexpectedChar),
+        ("[1,", ParseErrorKind::UnexpectedEof),
+        ("1.x", ParseErrorKind::InvalidNumber),
+        ("\"\\x\"", ParseErrorKind::InvalidEscape),
+        ("nulx", ParseErrorKind::InvalidLiteral),
+        ("{1: 2}", ParseErrorKind::InvalidKey),
+        ("{} {}", ParseErrorKind::TrailingData),
+    ];
+    for (input, kind) in cases {
+        assert_eq!(parse_json(input).unwrap_err().kind(), kind, "{}", input);
+    }
+}
+
+#[test]
+fn test_truncated_input_is_eof() {
+    let document = r#"{"name": "caf\u00e9 \ud83d\ude00", "n": [-1.5e+3, {"ok": true}]}"#;
+    assert!(parse_json(document).is_ok());
+    // Every proper prefix is incomplete rather than malformed.
+    for end in 0..document.len() {
+        let prefix = &document[..end];
+        let error = parse_json(prefix).unwrap_err();
+        assert!(error.is_eof(), "{:?}: {}", prefix, error);
+        #[cfg(feature = "std")]
+        {
+            let reader_error = parse_json_reader(prefix.as_bytes()).unwrap_err();
+            assert!(
+                reader_error.is_eof(),
+                "reader {:?}: {}",
+                prefix,
+                reader_error
+            );
+            assert_eq!(reader_error.offset(), error.offset(), "reader {:?}", prefix);
+            assert_eq!(reader_error.to_string(), error.to_string());
+        }
+    }
+
+    for input in [
+        "1.x",
+        "[-]",
+        "{\"a\" 1}",
+        "\"\\ud83dx\"",
+        "\"\\ud83d\\n\"",
+        "[1 2",
+        "nulx",
+    ] {
+        let error = parse_json(input).unwrap_err();
+        assert!(!error.is_eof(), "{:?}: {}", input, error);
+        #[cfg(feature = "std")]
+        {
+            let reader_error = parse_json_reader(input.as_bytes()).unwrap_err();
+            assert_eq!(reader_error.kind(), error.kind(), "reader {:?}", input);
+            assert_eq!(reader_error.offset(), error.offset(), "reader {:?}", input);
+        }
+    }
+    for input in ["Na", "Infin", "-Inf", "[1, -Infinit", "{\"a\": N"] {
+        let error = JsonParser::new(input)
+            .allow_non_finite_numbers(true)
+            .parse()
+            .unwrap_err();
+        assert!(error.is_eof(), "{:?}: {}", input, error);
+    }
+    #[cfg(feature = "std")]
+    for input in ["nul", "[tru", "\"\\ud83d", "\"\\ud83d\\"] {
+        let error = parse_json(input).unwrap_err();
+        let reader_error = JsonParser::from_reader(input.as_bytes())
+            .parse()
+            .unwrap_err();
+        assert_eq!(reader_error.offset(), error.offset(), "reader {:?}", input);
+    }
+}
+
+#[test]
+fn test_error_display_format() {
+    let cases = [
+        ("invalid", "Expected value, got 'i' at line 1 column 1"),
+        (
+            "{\n  \"a\": 1\n  \"b\": 2\n}",
+            "Expected comma or closing brace, got '\"' at line 3 column 3",
+        ),
+        ("[1,", "Unexpected end of input at line 1 column 4"),
+        (
+            "[\"a\", \"b\\q\"]",
+            "Invalid escape sequence: \\q at line 1 column 11",
+        ),
+        (
+            "{}\n\n  {}",
+            "Extra characters after JSON value, starting with '{' at line 3 column 3",
+        ),
+    ];
+    for (input, message) in cases {
+        assert_eq!(parse_json(input).unwrap_err().to_string(), message);
+        #[cfg(feature = "std")]
+        {
+            let reader_err = ReaderParser::with_buffer_size(input.as_bytes(), 4)
+                .parse()
+                .unwrap_err();
+            assert_eq!(reader_err.to_string(), message);
+        }
+    }
+}
+
+#[test]
+fn test_parse_integer_precision() {
+    // 2^53 + 1 is not representable as an f64.
+    let value = parse_json("9007199254740993").unwrap();
+    assert_eq!(value, JsonValue::Integer(9007199254740993));
+    assert_eq!(value.as_i64(), Some(9007199254740993));
+
+    let value = parse_json("[30, 30.0, 3e1]").unwrap();
+    assert_eq!(
+        value,
+        JsonValue::Array(vec![
+            JsonValue::Integer(30),
+            JsonValue::Number(30.0),
+            JsonValue::Number(30.0)
+        ])
+    );
+}
+
+#[test]
+fn test_serialize_round_trip() {
+    let inputs = [
+        "null",
+        "true",
+        "-17",
+        "0.1",
+        "-0.0",
+        "1e-300",
+        "\"quote \\\" backslash \\\\ newline \\n control \\u001f unicode \\u263A\"",
+        "[1, 2.5, [\"nested\", {}], []]",
+        "{\"name\": \"John\", \"tags\": [\"a\", \"b\"], \"meta\": {\"ok\": false}}",
+    ];
+    for input in inputs {
+        let value = parse_json(input).unwrap();
+        assert_eq!(parse_json(&value.to_string()).unwrap(), value, "{}", input);
+    }
+}
+
+#[test]
+fn test_control_character_round_trip() {
+    let value = parse_json(r#""a\u0000b\u0000""#).unwrap();
+    assert_eq!(value, JsonValue::String("a\0b\0".to_string()));
+    assert_eq!(value.as_str().unwrap().len(), 4);
+    assert_eq!(value.to_string(), r#""a\u0000b\u0000""#);
+
+    // Every C0 control character is escaped, never written raw.
+    let controls: String = (0u8..0x20).map(char::from).collect();
+    let text = JsonValue::String(controls.clone()).to_string();
+    assert!(text.bytes().all(|b| b >= 0x20), "{:?}", text);
+    assert!(text.starts_with(r#""\u0000\u0001"#));
+    assert!(text.contains(r#"\b\t\n\u000b\f\r"#));
+    assert_eq!(parse_json(&text).unwrap(), JsonValue::String(controls));
+
+    // A raw NUL is still rejected inside a string.
+    assert!(parse_json("\"a\0b\"").is_err());
+}
+
+#[test]
+fn test_serialize_pretty_round_trip() {
+    let input = r#"{"users": [{"name": "Ann", "roles": ["admin"]}, {"name": "Bob", "roles": []}]}"#;
+    let value = parse_json(input).unwrap();
+    let pretty = value.to_string_pretty(2);
+    assert!(pretty.contains("\n    {\n      \""));
+    assert_eq!(parse_json(&pretty).unwrap(), value);
+    assert_eq!(
+        parse_json(&value.to_string_pretty_with("\t")).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_parse_astral_plane_characters() {
+    assert_eq!(
+        parse_json(r#""smile \uD83D\uDE00, clef \uD834\uDD1E""#).unwrap(),
+        JsonValue::String("smile 😀, clef 𝄞".to_string())
+    );
+    assert_eq!(
+        parse_json(r#""\uDE00\uD83D""#).unwrap_err().kind(),
+        ParseErrorKind::InvalidUnicode
+    );
+}
+
+#[test]
+fn test_navigate_nested_value() {
+    let value = parse_json(r#"{"user": {"name": "Ann", "emails": ["a@x.io", "b@x.io"]}}"#).unwrap();
+    assert_eq!(value["user"]["name"], JsonValue::String("Ann".to_string()));
+    assert_eq!(
+        value["user"]["emails"][1],
+        JsonValue::String("b@x.io".to_string())
+    );
+    assert_eq!(value.get("user").and_then(|u| u.get("age")), None);
+    assert_eq!(value["user"].get_index(0), None);
+}
+
+#[test]
+fn test_deeply_nested_input_is_rejected() {
+    let input = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+    let err = parse_json(&input).unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::DepthLimitExceeded);
+
+    let input = format!(
+        "{}{}",
+        "[".repeat(DEFAULT_MAX_DEPTH),
+        "]".repeat(DEFAULT_MAX_DEPTH)
+    );
+    assert!(parse_json(&input).is_ok());
+}
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn test_preserve_key_order() {
+    let value = parse_json(r#"{"b":1,"a":2,"c":3}"#).unwrap();
+    let keys: Vec<&str> = value
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str())
+        .collect();
+    assert_eq!(keys, ["b", "a", "c"]);
+    assert_eq!(value.to_string(), r#"{"b":1,"a":2,"c":3}"#);
+}
+
+#[test]
+fn test_edit_and_reserialize() {
+    let mut value = parse_json(r#"{"b":1,"a":[2],"c":3}"#).unwrap();
+    value.remove("a");
+    value.insert("d", JsonValue::Array(vec![]));
+    value.pointer_mut("/c").unwrap().push(JsonValue::Null);
+    if let Some(list) = value.pointer_mut("/d") {
+        list.push(JsonValue::Integer(4));
+    }
+    assert_eq!(
+        parse_json(&value.to_string()).unwrap(),
+        parse_json(r#"{"b":1,"c":3,"d":[4]}"#).unwrap()
+    );
+    #[cfg(feature = "preserve_order")]
+    assert_eq!(value.to_string(), r#"{"b":1,"c":3,"d":[4]}"#);
+}
+
+#[test]
+fn test_reject_duplicate_keys() {
+    let input = r#"{"outer": {"a": 1, "b": 2, "a": 3}}"#;
+    assert_eq!(
+        parse_json(input).unwrap()["outer"]["a"],
+        JsonValue::Integer(3)
+    );
+
+    let mut parser = JsonParser::new(input).reject_duplicate_keys(true);
+    let err = parser.parse().unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::DuplicateKey);
+    assert_eq!(err.offset(), input.rfind("\"a\""));
+
+    let mut parser = JsonParser::new(r#"{"a": 1, "b": {"a": 2}}"#).reject_duplicate_keys(true);
+    assert!(parser.parse().is_ok());
+}
+
+#[test]
+fn test_parse_bytes() {
+    let input = r#"{"name": "Zoë", "tags": ["☺", "😀"], "n": 1.5}"#;
+    assert_eq!(
+        parse_json_bytes(input.as_bytes()).unwrap(),
+        parse_json(input).unwrap()
+    );
+
+    let err = parse_json_bytes(b"\"\xC3\x28\"").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::InvalidUtf8);
+    assert_eq!(err.offset(), Some(1));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_reader() {
+    let file = std::fs::File::open("data/github.json").unwrap();
+    let text = std::fs::read_to_string("data/github.json").unwrap();
+    assert_eq!(parse_json_reader(file).unwrap(), parse_json(&text).unwrap());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_reader_io_error() {
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("connection reset"))
+        }
+    }
+
+    let err = parse_json_reader(FailingReader).unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::Io);
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn test_parse_lines() {
+    let input = "{\"id\": 1}\n\n  \n[1, 2]\r\n{\"id\": \n\"last\"}\n";
+    let results: Vec<_> = parse_lines(input).collect();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap()["id"], JsonValue::Integer(1));
+    assert_eq!(
+        results[1].as_ref().unwrap(),
+        &JsonValue::Array(vec![JsonValue::Integer(1), JsonValue::Integer(2)])
+    );
+    assert_eq!(
+        results[2].as_ref().unwrap_err().kind(),
+        ParseErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        results[3].as_ref().unwrap_err().kind(),
+        ParseErrorKind::TrailingData
+    );
+
+    assert_eq!(parse_lines("").count(), 0);
+}
+
+#[test]
+fn test_u64_round_trip() {
+    let input = "[18446744073709551615,9223372036854775808,-9223372036854775808]";
+    let value = parse_json(input).unwrap();
+    assert_eq!(value[0].as_u64(), Some(u64::MAX));
+    assert_eq!(value[1], JsonValue::Unsigned(1 << 63));
+    assert_eq!(value[2].as_i64(), Some(i64::MIN));
+    assert_eq!(value.to_string(), input);
+    #[cfg(feature = "std")]
+    assert_eq!(parse_json_reader(input.as_bytes()).unwrap(), value);
+}
+
+#[test]
+fn test_arbitrary_precision_round_trip() {
+    let input = r#"{"price": 0.1, "huge": 1e400, "id": 12345678901234567890123}"#;
+    let value = JsonParser::new(input)
+        .with_arbitrary_precision(true)
+        .parse()
+        .unwrap();
+    assert_eq!(value["price"].to_string(), "0.1");
+    assert_eq!(value["huge"].to_string(), "1e400");
+    assert_eq!(value["huge"].as_f64(), Some(f64::INFINITY));
+    assert_eq!(value["price"].as_f64(), Some(0.1));
+    assert_eq!(value["id"].as_u64(), None);
+    let reparsed = JsonParser::new(&value.to_string())
+        .with_arbitrary_precision(true)
+        .parse()
+        .unwrap();
+    assert_eq!(reparsed, value);
+}
+
+#[test]
+fn test_parse_many() {
+    assert_eq!(
+        parse_many("1 true \"x\" [2]").unwrap(),
+        vec![
+            JsonValue::Integer(1),
+            JsonValue::Boolean(true),
+            JsonValue::from("x"),
+            JsonValue::Array(vec![JsonValue::Integer(2)]),
+        ]
+    );
+    assert_eq!(parse_many("\t{\"a\":\n1}\r\n\n null ").unwrap().len(), 2);
+    assert!(parse_many(" \n ").unwrap().is_empty());
+
+    let error = parse_many("1 [2,] 3").unwrap_err();
+    assert_eq!(error.kind(), ParseErrorKind::UnexpectedChar);
+    assert_eq!(error.offset(), Some(5));
+}
+
+#[test]
+fn test_parse_framed() {
+    let body = br#"{"id": 7, "tags": ["a"]}"#;
+    let mut buf = (body.len() as u32).to_be_bytes().to_vec();
+    buf.extend_from_slice(body);
+    buf.extend_from_slice(b"\x00\x00");
+    let (value, used) = parse_framed(&buf).unwrap();
+    assert_eq!(used, 4 + body.len());
+    assert_eq!(value["id"], JsonValue::Integer(7));
+    assert_eq!(value["tags"][0], JsonValue::from("a"));
+
+    // The body is parsed on its own, so trailing bytes inside it are an error.
+    let error = parse_framed(b"\x00\x00\x00\x031 2").unwrap_err();
+    assert_eq!(error.kind(), ParseErrorKind::TrailingData);
+    assert_eq!(error.offset(), Some(2));
+}
+
+#[test]
+fn test_parse_framed_short_buffer() {
+    let mut buf = 9u32.to_be_bytes().to_vec();
+    buf.extend_from_slice(b"[1, 2");
+    for end in 0..buf.len() {
+        let error = parse_framed(&buf[..end]).unwrap_err();
+        assert_eq!(error.kind(), ParseErrorKind::UnexpectedEof, "{}", end);
+        assert!(error.is_eof());
+        assert_eq!(error.offset(), Some(end));
+    }
+    buf.extend_from_slice(b", 3]");
+    let (value, used) = parse_framed(&buf).unwrap();
+    assert_eq!(used, 13);
+    assert_eq!(value[2], JsonValue::Integer(3));
+
+    // A complete frame holding truncated JSON is still an EOF-style error.
+    let error = parse_framed(b"\x00\x00\x00\x02[1").unwrap_err();
+    assert!(error.is_eof());
+}
+
+#[test]
+fn test_parse_concatenated_values() {
+    let input = "{\"a\":1}{\"b\":2}\n[3]  ";
+    let mut parser = JsonParser::new(input);
+    let mut values = Vec::new();
+    while !parser.remaining().trim().is_empty() {
+        values.push(parser.parse_one().unwrap());
+    }
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0]["a"], JsonValue::Integer(1));
+    assert_eq!(values[1]["b"], JsonValue::Integer(2));
+    assert_eq!(values[2][0], JsonValue::Integer(3));
+}
+
+#[test]
+fn test_non_finite_round_trip() {
+    let input = "[NaN,Infinity,-Infinity]";
+    assert!(parse_json(input).is_err());
+
+    let value = JsonParser::new(input)
+        .allow_non_finite_numbers(true)
+        .parse()
+        .unwrap();
+    let options = SerializeOptions::new().allow_non_finite_numbers(true);
+    assert_eq!(value.to_string_with(&options), input);
+    assert_eq!(value.to_string(), "[null,null,null]");
+}
+
+#[test]
+fn test_trailing_commas() {
+    let input = "{\n  \"name\": \"app\",\n  \"plugins\": [\"a\", \"b\",],\n}";
+    assert_eq!(
+        parse_json(input).unwrap_err().kind(),
+        ParseErrorKind::UnexpectedChar
+    );
+
+    let value = JsonParser::new(input)
+        .with_trailing_commas(true)
+        .parse()
+        .unwrap();
+    assert_eq!(
+        value,
+        parse_json(r#"{"name": "app", "plugins": ["a", "b"]}"#).unwrap()
+    );
+}
+
+#[test]
+fn test_jsonc_config() {
+    let input = r#"{
+    // Editor settings
+    "editor.tabSize": 4, /* spaces */
+    "files.exclude": ["target" /* build output */]
+}"#;
+    assert!(parse_json(input).is_err());
+
+    let value = JsonParser::new(input).allow_comments(true).parse().unwrap();
+    assert_eq!(value["editor.tabSize"], JsonValue::Integer(4));
+    assert_eq!(
+        value["files.exclude"][0],
+        JsonValue::String("target".to_string())
+    );
+}
+
+#[test]
+fn test_apply_patch() {
+    let mut doc = parse_json(r#"{"name": "John", "roles": ["user"], "old": 1}"#).unwrap();
+    let patch = parse_json(
+        r#"[
+            {"op": "test", "path": "/name", "value": "John"},
+            {"op": "add", "path": "/roles/0", "value": "admin"},
+            {"op": "move", "from": "/old", "path": "/legacy"},
+            {"op": "copy", "from": "/name", "path": "/display"},
+            {"op": "replace", "path": "/name", "value": "Jo"},
+            {"op": "remove", "path": "/roles/1"}
+        ]"#,
+    )
+    .unwrap();
+    apply_patch(&mut doc, &patch).unwrap();
+    assert_eq!(
+        doc,
+        parse_json(r#"{"name": "Jo", "roles": ["admin"], "legacy": 1, "display": "John"}"#)
+            .unwrap()
+    );
+
+    let failing =
+        parse_json(r#"[{"op": "remove", "path": "/name"}, {"op": "remove", "path": "/missing"}]"#)
+            .unwrap();
+    let before = doc.clone();
+    let err = apply_patch(&mut doc, &failing).unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::InvalidPatch);
+    assert_eq!(doc, before);
+}
+
+#[test]
+fn test_canonical_serialization() {
+    let a = parse_json(r#"{"id": 7, "amount": 10.50, "meta": {"z": [1e3], "a": "x"}}"#).unwrap();
+    let b = parse_json(
+        r#"{
+            "meta": {"a": "x", "z": [1000]},
+            "amount": 1.05e1,
+            "id": 7
+        }"#,
+    )
+    .unwrap();
+    let canonical = a.to_string_canonical();
+    assert_eq!(
+        canonical,
+        r#"{"amount":10.5,"id":7,"meta":{"a":"x","z":[1000]}}"#
+    );
+    assert_eq!(canonical, b.to_string_canonical());
+    assert_eq!(
+        parse_json(&canonical).unwrap().to_string_canonical(),
+        canonical
+    );
+}
+
+#[test]
+fn test_parse_json_owned() {
+    struct Config<T: 'static> {
+        value: T,
+    }
+
+    fn load(text: String) -> Config<JsonValue> {
+        Config {
+            value: parse_json_owned(text).unwrap(),
+        }
+    }
+
+    let config = load(String::from(r#"{"name": "svc", "ports": [80, 443]}"#));
+    let handle = std::thread::spawn(move || config.value["ports"][1].as_i64());
+    assert_eq!(handle.join().unwrap(), Some(443));
+
+    let err = parse_json_owned(String::from("[1,")).unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnexpectedEof);
+}
+
+/// Nesting depth for the deep-nesting tests, in pairs of levels.
+const DEPTH: usize = 100_000;
+
+/// Returns an array and object nested `2 * depth` levels deep around `leaf`.
+fn deeply_nested(depth: usize, leaf: &str) -> String {
+    format!(
+        "{}{}{}",
+        r#"[{"a":"#.repeat(depth),
+        leaf,
+        "}]".repeat(depth)
+    )
+}
+
+/// Runs `f` on a thread whose stack is far too small for a parser that
+/// recurses per nesting level.
+fn on_small_stack<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+/// Takes apart a value with one container per level, level by level since
+/// dropping it whole would recurse, returning its depth and innermost value.
+fn dismantle(mut value: JsonValue) -> (usize, JsonValue) {
+    let mut depth = 0;
+    loop {
+        value = match value {
+            JsonValue::Array(mut values) => values.pop().unwrap(),
+            JsonValue::Object(map) => map.into_iter().next().unwrap().1,
+            _ => return (depth, value),
+        };
+        depth += 1;
+    }
+}
+
+#[test]
+fn test_deep_nesting_does_not_use_the_call_stack() {
+    let input = deeply_nested(DEPTH, "null");
+    let depth = on_small_stack(move || {
+        let value = JsonParser::new(&input)
+            .with_max_depth(2 * DEPTH)
+            .parse()
+            .unwrap();
+        dismantle(value).0
+    });
+    assert_eq!(depth, 2 * DEPTH);
+}
+
+#[test]
+fn test_deep_nesting_borrowed_and_spanned() {
+    let input = deeply_nested(DEPTH, "\"leaf\"");
+    let depths = on_small_stack(move || {
+        let mut value = JsonParser::new(&input)
+            .with_max_depth(2 * DEPTH)
+            .parse_borrowed()
+            .unwrap();
+        let mut borrowed = 0;
+        loop {
+            value = match value {
+                BorrowedValue::Array(mut values) => values.pop().unwrap(),
+                BorrowedValue::Object(map) => map.into_iter().next().unwrap().1,
+                _ => break,
+            };
+            borrowed += 1;
+        }
+
+        let mut value = JsonParser::new(&input)
+            .with_max_depth(2 * DEPTH)
+            .parse_with_spans()
+            .unwrap();
+        let mut spanned = 0;
+        loop {
+            value = match value.kind {
+                SpannedKind::Array(mut values) => values.pop().unwrap(),
+                SpannedKind::Object(mut members) => members.pop().unwrap().value,
+                SpannedKind::Scalar(_) => break,
+            };
+            spanned += 1;
+        }
+        assert_eq!(value.span.len(), "\"leaf\"".len());
+        (borrowed, spanned)
+    });
+    assert_eq!(depths, (2 * DEPTH, 2 * DEPTH));
+}
+
+#[test]
+fn test_deep_nesting_partial() {
+    let input = deeply_nested(DEPTH, "[1, 2]");
+    let (whole, (depth, leaf)) = on_small_stack(move || {
+        let mut parser = JsonParser::new(&input).with_max_depth(2 * DEPTH + 1);
+        // Skipping the whole document leaves it raw.
+        let whole = parser.parse_partial(&[]).unwrap();
+        // A pointer that leaves the document a few thousand levels down
+        // makes the parser descend that far and skip the rest.
+        let pointer = format!("{}/0/b", "/0/a".repeat(1000));
+        let mut parser = JsonParser::new(&input).with_max_depth(2 * DEPTH + 1);
+        let value = parser.parse_partial(&[&pointer]).unwrap();
+        (whole == JsonValue::Raw(input), dismantle(value))
+    });
+    assert!(whole);
+    assert_eq!(depth, 2002);
+    assert_eq!(leaf, JsonValue::Raw(deeply_nested(DEPTH - 1001, "[1, 2]")));
+}
+
+#[test]
+fn test_deep_nesting_recovering() {
+    let input = deeply_nested(DEPTH, "nul");
+    let (errors, (depth, leaf)) = on_small_stack(move || {
+        let (value, errors) = JsonParser::new(&input)
+            .with_max_depth(2 * DEPTH)
+            .parse_recovering();
+        let errors: Vec<_> = errors.iter().map(|err| err.kind()).collect();
+        (errors, dismantle(value.unwrap()))
+    });
+    assert_eq!(errors, [ParseErrorKind::InvalidLiteral]);
+    assert_eq!(depth, 2 * DEPTH);
+    assert_eq!(leaf, JsonValue::Null);
+}
+
+#[test]
+fn test_parse_with_byte_order_mark() {
+    let input = b"\xEF\xBB\xBF{\"name\": \"John\", \"tags\": [1, 2]}";
+    let expected = parse_json(r#"{"name": "John", "tags": [1, 2]}"#).unwrap();
+    assert_eq!(parse_json_bytes(input).unwrap(), expected);
+    assert_eq!(
+        parse_json(std::str::from_utf8(input).unwrap()).unwrap(),
+        expected
+    );
+    #[cfg(feature = "std")]
+    assert_eq!(parse_json_reader(&input[..]).unwrap(), expected);
+}
+
+/// Fragments that arbitrary inputs are assembled from, chosen to reach deep
+/// into the grammar: structure, partial literals and escapes, numbers,
+/// comments and multi-byte characters next to all of them.
+const FRAGMENTS: &[&str] = &[
+    "{", "}", "[", "]", ",", ":", " ", "\n", "\"", "'", "\\", "\\u", "\\ud83d", "\\ude00", "00e9",
+    "u", "n", "nu", "null", "t", "tru", "true", "f", "false", "N", "NaN", "Infinity", "-", "0",
+    "1", "9", ".", "e", "+", "0x", "F", "/", "*", "//", "/*", "*/", "a", "€", "é", "😀", "\u{0}",
+    "\u{feff}",
+];
+
+/// Runs every parsing entry point over `input`, with strict and lenient
+/// options, touching the errors and positions they produce.
+fn parse_everything(input: &str) {
+    let lenient = || {
+        JsonParser::new(input)
+            .with_trailing_commas(true)
+            .allow_comments(true)
+            .allow_single_quotes(true)
+            .allow_unquoted_keys(true)
+            .allow_non_finite_numbers(true)
+            .allow_control_characters(true)
+            .case_insensitive_literals(true)
+            .allow_json5_numbers(true)
+            .with_arbitrary_precision(true)
+    };
+    for mut parser in [JsonParser::new(input), lenient()] {
+        let mut copy = parser.clone();
+        if let Err(err) = parser.parse() {
+            let _ = (err.to_string(), err.snippet(input));
+        }
+        while copy.parse_one().is_ok() && !copy.remaining().trim().is_empty() {}
+        let _ = (
+            copy.remaining(),
+            copy.remaining_bytes(),
+            copy.consumed_bytes(),
+        );
+
+        let parser = lenient();
+        let _ = parser.clone().parse_borrowed();
+        let _ = parser.clone().parse_with_spans();
+        let _ = parser.clone().parse_recovering();
+        let _ = parser.clone().parse_into(&mut JsonValue::Null);
+        let _ = parser.clone().events().count();
+    }
+    let _ = parse_many(input);
+    let _ = parse_lines(input).count();
+    let _ = JsonParser::from_bytes(input.as_bytes()).parse();
+    #[cfg(feature = "std")]
+    let _ = ReaderParser::with_buffer_size(input.as_bytes(), 3).parse();
+}
+
+proptest::proptest! {
+    #[test]
+    fn prop_parsing_never_panics(
+        fragments in proptest::collection::vec(proptest::sample::select(FRAGMENTS), 0..24)
+    ) {
+        parse_everything(&fragments.concat());
+    }
+
+    #[test]
+    fn prop_parsing_arbitrary_text_never_panics(input in "\\PC{0,32}") {
+        parse_everything(&input);
+    }
+
+    #[test]
+    fn prop_parsing_arbitrary_bytes_never_panics(
+        input in proptest::collection::vec(proptest::num::u8::ANY, 0..48)
+    ) {
+        let _ = parse_json_bytes(&input);
+        let _ = JsonParser::from_bytes(&input).allow_comments(true).parse_borrowed();
+        let mut parser = JsonParser::from_bytes(&input);
+        let _ = (parser.parse_one(), parser.remaining());
+        #[cfg(feature = "std")]
+        let _ = parse_json_reader(&input[..]);
+    }
+}